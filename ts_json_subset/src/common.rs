@@ -13,6 +13,7 @@ pub mod filters {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("\"{0}\"")]
 /// An escaped string literal, surrounded by double quotes.
 pub struct StringLiteral(String);
@@ -21,6 +22,11 @@ impl StringLiteral {
     pub fn from_raw(input: &str) -> Self {
         StringLiteral::from(input.to_string())
     }
+
+    /// The escaped contents, without the quotes
+    pub fn escaped(&self) -> &str {
+        &self.0
+    }
 }
 
 impl From<String> for StringLiteral {
@@ -34,6 +40,7 @@ pub fn sanitize_string_literal(input: &str) -> String {
 }
 
 #[derive(Debug, Clone, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("{0}")]
 /// A numeric literal, must have a numeric value (ie. no Infinity, no NaN)
 pub struct NumericLiteral(f64);
@@ -54,6 +61,7 @@ impl TryFrom<f64> for NumericLiteral {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("{0}")]
 /// A boolean literal
 pub struct BooleanLiteral(bool);
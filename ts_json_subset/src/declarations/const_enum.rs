@@ -2,6 +2,7 @@ use crate::{common::StringLiteral, ident::StrictTSIdent};
 use askama::Template;
 
 #[derive(Debug, Clone, Eq, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "const enum {{ ident }} {{ body }}", ext = "txt")]
 /// A const enum with string literals (TS numeric const enum offer no advantage, consider using union types instead)
 pub struct ConstEnumDeclaration {
@@ -10,6 +11,7 @@ pub struct ConstEnumDeclaration {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = r#"{ {{ variants|join(", ") }} }"#, ext = "txt")]
 /// A description of all variants in a const enum with string literals, see `ConstEnumDeclaration`
 pub struct ConstEnumBody {
@@ -17,6 +19,7 @@ pub struct ConstEnumBody {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ ident }} = {{ value }}", ext = "txt")]
 /// A const enum variant with string literal
 pub struct ConstEnumVariant {
@@ -3,12 +3,14 @@ use crate::{common::filters, ident::StrictTSIdent};
 use askama::Template;
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = r#"{{ identifiers|join(", ") }}"#, ext = "txt")]
 pub struct InterfaceTypeList {
     pub identifiers: Vec<TypeReference>,
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "extends {{ type_list }}", ext = "txt")]
 /// An interface extend identifier list
 pub struct InterfaceExtendsClause {
@@ -16,6 +18,7 @@ pub struct InterfaceExtendsClause {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(
     source = r#"interface {{ ident }}{{ type_params|display_opt }} {{ extends_clause|display_opt }} {{- obj_type -}}"#,
     ext = "txt"
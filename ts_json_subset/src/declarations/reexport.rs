@@ -3,18 +3,34 @@ use askama::Template;
 use crate::ident::StrictTSIdent;
 
 #[derive(Debug, Clone, PartialEq, Eq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = r#"{ {{ reexports|join(", ") }} }"#, ext = "txt")]
 pub struct ReexportDeclaration {
     pub reexports: Vec<ReexportClause>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = r#"{{ scope }} as {{ export_as }}"#, ext = "txt")]
 pub struct ReexportClause {
     pub scope: StrictTSIdent,
     pub export_as: StrictTSIdent,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[template(
+    source = r#"* {% if let Some(namespace) = namespace %}as {{ namespace }} {% endif %}from {{ path }};"#,
+    ext = "txt"
+)]
+/// Reexports all the declarations of another module, e.g. in a barrel file
+pub struct ExportAllDeclaration {
+    /// The path of the module, with its quotes
+    pub path: String,
+    /// The namespace the declarations are reexported in, as in `export * as api from "./api";`
+    pub namespace: Option<StrictTSIdent>,
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::str::FromStr;
@@ -3,6 +3,7 @@ use crate::{common::filters, ident::StrictTSIdent};
 use askama::Template;
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(
     source = "type {{ ident }} {{- type_params|display_opt }} = {{ inner_type }};",
     ext = "txt"
@@ -1,12 +1,19 @@
-use crate::declarations::{
-    const_enum::ConstEnumDeclaration, interface::InterfaceDeclaration,
-    reexport::ReexportDeclaration, type_alias::TypeAliasDeclaration,
+use crate::{
+    declarations::{
+        const_enum::ConstEnumDeclaration,
+        interface::InterfaceDeclaration,
+        reexport::{ExportAllDeclaration, ReexportDeclaration},
+        type_alias::TypeAliasDeclaration,
+    },
+    ident::StrictTSIdent,
 };
 use displaythis::Display;
 use from_variants::FromVariants;
 
 #[derive(Debug, Clone, PartialEq, FromVariants, Display)]
-/// An export statement, with support for exporting interfaces, types, const enum, and reexports
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An export statement, with support for exporting interfaces, types, const enum, reexports and all the declarations
+/// of another module
 pub enum ExportStatement {
     #[display("export {0}")]
     InterfaceDeclaration(InterfaceDeclaration),
@@ -16,4 +23,26 @@ pub enum ExportStatement {
     ConstEnumDeclaration(ConstEnumDeclaration),
     #[display("export {0}")]
     ReexportDeclaration(ReexportDeclaration),
+    #[display("export {0}")]
+    ExportAllDeclaration(ExportAllDeclaration),
+}
+
+impl ExportStatement {
+    /// The identifiers declared by this statement
+    pub fn declared_idents(&self) -> Vec<&StrictTSIdent> {
+        match self {
+            ExportStatement::InterfaceDeclaration(declaration) => vec![&declaration.ident],
+            ExportStatement::TypeAliasDeclaration(declaration) => vec![&declaration.ident],
+            ExportStatement::ConstEnumDeclaration(declaration) => vec![&declaration.ident],
+            ExportStatement::ReexportDeclaration(declaration) => declaration
+                .reexports
+                .iter()
+                .map(|clause| &clause.export_as)
+                .collect(),
+            // The declarations of the other module are unknown here, unless they are in a namespace
+            ExportStatement::ExportAllDeclaration(declaration) => {
+                declaration.namespace.iter().collect()
+            }
+        }
+    }
 }
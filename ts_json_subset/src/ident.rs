@@ -6,11 +6,13 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("{0}")]
 // A valid TS identifier
 pub struct TSIdent(String);
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("{0}")]
 /// A TS identifier that is also checked for reserved keywords
 pub struct StrictTSIdent(TSIdent);
@@ -4,21 +4,56 @@ use displaythis::Display;
 use crate::ident::TSIdent;
 
 #[derive(Debug, Clone, Eq, PartialEq, Template)]
-#[template(source = "import {{ import_kind }} from {{ path }};", ext = "txt")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[template(
+    source = "import {% if type_only %}type {% endif %}{{ import_kind }} from {{ path }};",
+    ext = "txt"
+)]
 /// An import statement, supporting multiple imports from a file
 pub struct ImportStatement {
     pub import_kind: ImportKind,
     pub path: String,
+    /// An `import type`, that only imports the declarations as types. It is erased from the emitted JS, as required
+    /// by `isolatedModules` and `verbatimModuleSyntax` for the imports of types
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub type_only: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ items|join(\", \") }}", ext = "txt")]
 /// An list of imported identifiers
 pub struct ImportList {
-    pub items: Vec<TSIdent>,
+    pub items: Vec<ImportSpecifier>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[template(
+    source = "{{ ident }}{% match alias %}{% when Some with (alias) %} as {{ alias }}{% when None %}{% endmatch %}",
+    ext = "txt"
+)]
+/// An identifier of an [ImportList], optionally imported under another name, e.g. `Status as UserStatus`
+pub struct ImportSpecifier {
+    pub ident: TSIdent,
+    pub alias: Option<TSIdent>,
+}
+
+impl ImportSpecifier {
+    /// The name under which the identifier is known in the importing module
+    pub fn local_name(&self) -> &TSIdent {
+        self.alias.as_ref().unwrap_or(&self.ident)
+    }
+}
+
+impl From<TSIdent> for ImportSpecifier {
+    fn from(ident: TSIdent) -> Self {
+        ImportSpecifier { ident, alias: None }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The identifiers fragment of an import statement
 pub enum ImportKind {
     #[display("{0}")]
@@ -40,12 +75,16 @@ pub mod tests {
         assert_eq!(
             ImportList {
                 items: vec![
-                    TSIdent::from_str("Test").unwrap(),
-                    TSIdent::from_str("TestOther").unwrap()
+                    TSIdent::from_str("Test").unwrap().into(),
+                    TSIdent::from_str("TestOther").unwrap().into(),
+                    ImportSpecifier {
+                        ident: TSIdent::from_str("Status").unwrap(),
+                        alias: Some(TSIdent::from_str("UserStatus").unwrap()),
+                    },
                 ],
             }
             .to_string(),
-            "Test, TestOther",
+            "Test, TestOther, Status as UserStatus",
         );
     }
 
@@ -55,6 +94,7 @@ pub mod tests {
             ImportStatement {
                 import_kind: ImportKind::Identifier(TSIdent::from_str("Test").unwrap()),
                 path: r#""types/users""#.to_string(),
+                type_only: false,
             }
             .to_string(),
             r#"import Test from "types/users";"#
@@ -64,6 +104,7 @@ pub mod tests {
             ImportStatement {
                 import_kind: ImportKind::GlobAsIdentifier(TSIdent::from_str("Test").unwrap()),
                 path: r#""types/users""#.to_string(),
+                type_only: false,
             }
             .to_string(),
             r#"import * as Test from "types/users";"#
@@ -73,14 +114,27 @@ pub mod tests {
             ImportStatement {
                 import_kind: ImportKind::ImportList(ImportList {
                     items: vec![
-                        TSIdent::from_str("Test").unwrap(),
-                        TSIdent::from_str("TestOther").unwrap()
+                        TSIdent::from_str("Test").unwrap().into(),
+                        TSIdent::from_str("TestOther").unwrap().into()
                     ]
                 }),
                 path: r#""types/users""#.to_string(),
+                type_only: false,
             }
             .to_string(),
             r#"import { Test, TestOther } from "types/users";"#
         );
+
+        assert_eq!(
+            ImportStatement {
+                import_kind: ImportKind::ImportList(ImportList {
+                    items: vec![TSIdent::from_str("Test").unwrap().into()]
+                }),
+                path: r#""types/users""#.to_string(),
+                type_only: true,
+            }
+            .to_string(),
+            r#"import type { Test } from "types/users";"#
+        );
     }
 }
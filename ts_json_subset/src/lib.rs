@@ -4,6 +4,8 @@
 //! * type aliases declarations,
 //! * const enums declarations
 //!
+//! The [parser] reads this subset back into the same AST, the [printer] outputs it with a configurable style.
+//!
 //! This subset allows to represent types in TypeScript that can get deserialized from JSON.
 //!
 //! Follows TypeScript grammar as defined in <http://javascript.xgqfrms.xyz/pdfs/TypeScript%20Language%20Specification.pdf>
@@ -17,4 +19,6 @@ pub mod declarations;
 pub mod export;
 pub mod ident;
 pub mod import;
+pub mod parser;
+pub mod printer;
 pub mod types;
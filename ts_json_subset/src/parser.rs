@@ -0,0 +1,1192 @@
+//! A parser for the subset of TypeScript that this crate outputs, giving back the same AST types.
+//!
+//! Whitespace and comments are ignored, as are the formatting variations of tools such as prettier :
+//! `;`, `,` or line breaks between members, trailing separators, leading `|` and `&`, and single-quoted strings.
+//!
+//! Printing a statement and parsing it back gives the same statement, except for :
+//! * unions (resp. intersections) directly nested in unions (resp. intersections), which are flattened,
+//! * verbatim types, which are only parsed back as such when they are template literal types.
+//!
+//! [TsType], [ExportStatement] and [ImportStatement] also implement [FromStr].
+use std::{convert::TryFrom, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    common::{BooleanLiteral, NumericLiteral, StringLiteral},
+    declarations::{
+        const_enum::{ConstEnumBody, ConstEnumDeclaration, ConstEnumVariant},
+        interface::{InterfaceDeclaration, InterfaceExtendsClause, InterfaceTypeList},
+        reexport::{ExportAllDeclaration, ReexportClause, ReexportDeclaration},
+        type_alias::TypeAliasDeclaration,
+    },
+    export::ExportStatement,
+    ident::{IdentError, StrictTSIdent},
+    import::{ImportKind, ImportList, ImportSpecifier, ImportStatement},
+    types::{
+        ArrayType, ExtendsConstraint, IntersectionType, LiteralType, ObjectType, ParenthesizedType,
+        PredefinedType, PrimaryType, PropertyName, PropertySignature, TsType, TupleType,
+        TypeArguments, TypeBody, TypeMember, TypeParameter, TypeParameters, TypeReference,
+        UnionType,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{line}:{column}: {message}")]
+/// A syntax error, at a 1-indexed line and column
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A top-level statement of a module
+pub enum Statement {
+    Import(ImportStatement),
+    Export(ExportStatement),
+}
+
+/// Parses a whole module
+pub fn parse_module(input: &str) -> Result<Vec<Statement>, ParseError> {
+    let mut parser = Parser::new(input)?;
+    let mut statements = Vec::new();
+    while !parser.is_done() {
+        if parser.eat_punct(';') {
+            continue;
+        }
+        statements.push(parser.statement()?);
+    }
+    Ok(statements)
+}
+
+/// Parses a single type
+pub fn parse_type(input: &str) -> Result<TsType, ParseError> {
+    let mut parser = Parser::new(input)?;
+    let ts_type = parser.ts_type()?;
+    parser.expect_end()?;
+    Ok(ts_type)
+}
+
+impl FromStr for TsType {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        parse_type(input)
+    }
+}
+
+impl FromStr for ExportStatement {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input)?;
+        parser.expect_keyword("export")?;
+        let statement = parser.export()?;
+        parser.expect_end()?;
+        Ok(statement)
+    }
+}
+
+impl FromStr for ImportStatement {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input)?;
+        parser.expect_keyword("import")?;
+        let statement = parser.import()?;
+        parser.expect_end()?;
+        Ok(statement)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// The unescaped value of a string literal
+    String(String),
+    Number(f64),
+    /// A template literal type, as written
+    Template(String),
+    Punct(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{}`", ident),
+            Token::String(value) => write!(f, "string {:?}", value),
+            Token::Number(value) => write!(f, "number {}", value),
+            Token::Template(template) => write!(f, "template {}", template),
+            Token::Punct(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PositionedToken {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<PositionedToken>, ParseError> {
+        let mut tokens = Vec::new();
+        while let Some(&c) = self.chars.peek() {
+            let (line, column) = (self.line, self.column);
+            let token = match c {
+                c if c.is_whitespace() => {
+                    self.next_char();
+                    continue;
+                }
+                '/' => {
+                    self.comment()?;
+                    continue;
+                }
+                '"' | '\'' => Token::String(self.string()?),
+                '`' => Token::Template(self.template()?),
+                c if c.is_ascii_digit() => Token::Number(self.number()?),
+                c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                    let mut ident = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if !(c.is_alphanumeric() || c == '_' || c == '$') {
+                            break;
+                        }
+                        ident.push(c);
+                        self.next_char();
+                    }
+                    Token::Ident(ident)
+                }
+                '{' | '}' | '(' | ')' | '[' | ']' | '<' | '>' | ',' | ';' | ':' | '?' | '|'
+                | '&' | '=' | '*' | '-' => {
+                    self.next_char();
+                    Token::Punct(c)
+                }
+                c => return Err(self.error(format!("unexpected character `{}`", c))),
+            };
+            tokens.push(PositionedToken {
+                token,
+                line,
+                column,
+            });
+        }
+        Ok(tokens)
+    }
+
+    fn comment(&mut self) -> Result<(), ParseError> {
+        self.next_char();
+        match self.next_char() {
+            Some('/') => {
+                while let Some(c) = self.next_char() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            Some('*') => {
+                let mut previous = None;
+                while let Some(c) = self.next_char() {
+                    if previous == Some('*') && c == '/' {
+                        return Ok(());
+                    }
+                    previous = Some(c);
+                }
+                Err(self.error("unterminated comment".to_string()))
+            }
+            _ => Err(self.error("unexpected character `/`".to_string())),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let quote = self.next_char();
+        let mut value = String::new();
+        loop {
+            match self.next_char() {
+                None | Some('\n') => return Err(self.error("unterminated string".to_string())),
+                Some(c) if Some(c) == quote => return Ok(value),
+                Some('\\') => value.push(self.escape()?),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseError> {
+        let c = match self.next_char() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('u') => {
+                let braced = self.chars.peek() == Some(&'{');
+                let mut code = String::new();
+                if braced {
+                    self.next_char();
+                    while let Some(c) = self.next_char() {
+                        if c == '}' {
+                            break;
+                        }
+                        code.push(c);
+                    }
+                } else {
+                    (0..4).for_each(|_| code.extend(self.next_char()));
+                }
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(|| self.error(format!("invalid unicode escape `{}`", code)))?
+            }
+            Some(c) => c,
+            None => return Err(self.error("unterminated string".to_string())),
+        };
+        Ok(c)
+    }
+
+    fn template(&mut self) -> Result<String, ParseError> {
+        let mut template = String::new();
+        template.extend(self.next_char());
+        loop {
+            match self.next_char() {
+                None => return Err(self.error("unterminated template literal".to_string())),
+                Some('\\') => {
+                    template.push('\\');
+                    template.extend(self.next_char());
+                }
+                Some('`') => {
+                    template.push('`');
+                    return Ok(template);
+                }
+                Some(c) => template.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, ParseError> {
+        let mut number = String::new();
+        while let Some(&c) = self.chars.peek() {
+            let is_exponent_sign =
+                (c == '-' || c == '+') && matches!(number.chars().last(), Some('e') | Some('E'));
+            if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || is_exponent_sign) {
+                break;
+            }
+            number.push(c);
+            self.next_char();
+        }
+        number
+            .parse()
+            .map_err(|_| self.error(format!("invalid number `{}`", number)))
+    }
+}
+
+struct Parser {
+    tokens: Vec<PositionedToken>,
+    position: usize,
+    end: (usize, usize),
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let lexer = Lexer {
+            chars: input.chars().peekable(),
+            line: 1,
+            column: 1,
+        };
+        let end = input
+            .lines()
+            .enumerate()
+            .last()
+            .map_or((1, 1), |(index, line)| {
+                (index + 1, line.chars().count() + 1)
+            });
+        Ok(Parser {
+            tokens: lexer.tokenize()?,
+            position: 0,
+            end,
+        })
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|token| &token.token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n).map(|token| &token.token)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.token.clone())
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// An error at the current token
+    fn error(&self, message: &str) -> ParseError {
+        let (line, column) = self
+            .tokens
+            .get(self.position)
+            .map_or(self.end, |token| (token.line, token.column));
+        ParseError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        match self.peek() {
+            Some(token) => self.error(&format!("expected {}, found {}", expected, token)),
+            None => self.error(&format!("expected {}, found the end of input", expected)),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("the end of input")),
+        }
+    }
+
+    /// Whether a line break separates the current token from the previous one
+    fn at_line_start(&self) -> bool {
+        match (
+            self.position
+                .checked_sub(1)
+                .and_then(|previous| self.tokens.get(previous)),
+            self.tokens.get(self.position),
+        ) {
+            (Some(previous), Some(current)) => current.line > previous.line,
+            _ => false,
+        }
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat_punct(c) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("`{}`", c)))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+        Err(self.unexpected(&format!("`{}`", keyword)))
+    }
+
+    fn ident<T: FromStr<Err = IdentError>>(&mut self) -> Result<T, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let parsed = T::from_str(ident).map_err(|e| self.error(&e.to_string()))?;
+                self.position += 1;
+                Ok(parsed)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::String(value)) => {
+                let value = value.clone();
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(self.unexpected("a string")),
+        }
+    }
+
+    /// Parses a comma-separated list until the closing delimiter, allowing a trailing comma
+    fn list<T, F>(&mut self, close: char, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = Vec::new();
+        while !self.eat_punct(close) {
+            items.push(item(self)?);
+            if !self.eat_punct(',') {
+                self.expect_punct(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn statement(&mut self) -> Result<Statement, ParseError> {
+        if self.eat_keyword("import") {
+            return Ok(Statement::Import(self.import()?));
+        }
+        if self.eat_keyword("export") {
+            return Ok(Statement::Export(self.export()?));
+        }
+        Err(self.unexpected("`import` or `export`"))
+    }
+
+    fn import(&mut self) -> Result<ImportStatement, ParseError> {
+        // `import type from "..."` is the default import of an identifier named `type`
+        let type_only = self.is_keyword("type")
+            && !matches!(self.peek_nth(1), Some(Token::Ident(ident)) if ident == "from");
+        if type_only {
+            self.position += 1;
+        }
+        let import_kind = if self.eat_punct('*') {
+            self.expect_keyword("as")?;
+            ImportKind::GlobAsIdentifier(self.ident()?)
+        } else if self.eat_punct('{') {
+            ImportKind::ImportList(ImportList {
+                items: self.list('}', |parser| parser.import_specifier())?,
+            })
+        } else {
+            ImportKind::Identifier(self.ident()?)
+        };
+        self.expect_keyword("from")?;
+        let path = StringLiteral::from(self.string()?).to_string();
+        self.eat_punct(';');
+        Ok(ImportStatement {
+            import_kind,
+            path,
+            type_only,
+        })
+    }
+
+    fn import_specifier(&mut self) -> Result<ImportSpecifier, ParseError> {
+        let ident = self.ident()?;
+        let alias = if self.eat_keyword("as") {
+            Some(self.ident()?)
+        } else {
+            None
+        };
+        Ok(ImportSpecifier { ident, alias })
+    }
+
+    fn export(&mut self) -> Result<ExportStatement, ParseError> {
+        let statement = if self.eat_keyword("interface") {
+            self.interface()?.into()
+        } else if self.eat_keyword("type") {
+            self.type_alias()?.into()
+        } else if self.eat_keyword("const") {
+            self.expect_keyword("enum")?;
+            self.const_enum()?.into()
+        } else if self.eat_punct('{') {
+            ReexportDeclaration {
+                reexports: self.list('}', Self::reexport_clause)?,
+            }
+            .into()
+        } else if self.eat_punct('*') {
+            let namespace = if self.eat_keyword("as") {
+                Some(self.ident()?)
+            } else {
+                None
+            };
+            self.expect_keyword("from")?;
+            ExportAllDeclaration {
+                path: StringLiteral::from(self.string()?).to_string(),
+                namespace,
+            }
+            .into()
+        } else {
+            return Err(self.unexpected("a declaration"));
+        };
+        self.eat_punct(';');
+        Ok(statement)
+    }
+
+    fn interface(&mut self) -> Result<InterfaceDeclaration, ParseError> {
+        let ident = self.ident()?;
+        let type_params = self.type_parameters()?;
+        let extends_clause = if self.eat_keyword("extends") {
+            let mut identifiers = vec![self.type_reference()?];
+            while self.eat_punct(',') {
+                identifiers.push(self.type_reference()?);
+            }
+            Some(InterfaceExtendsClause {
+                type_list: InterfaceTypeList { identifiers },
+            })
+        } else {
+            None
+        };
+        self.expect_punct('{')?;
+        let obj_type = self.object_type()?;
+        Ok(InterfaceDeclaration {
+            ident,
+            type_params,
+            extends_clause,
+            obj_type,
+        })
+    }
+
+    fn type_alias(&mut self) -> Result<TypeAliasDeclaration, ParseError> {
+        let ident = self.ident()?;
+        let type_params = self.type_parameters()?;
+        self.expect_punct('=')?;
+        let inner_type = self.ts_type()?;
+        Ok(TypeAliasDeclaration {
+            ident,
+            type_params,
+            inner_type,
+        })
+    }
+
+    fn const_enum(&mut self) -> Result<ConstEnumDeclaration, ParseError> {
+        let ident = self.ident()?;
+        self.expect_punct('{')?;
+        let variants = self.list('}', |parser| {
+            let ident = parser.ident()?;
+            parser.expect_punct('=')?;
+            let value = StringLiteral::from(parser.string()?);
+            Ok(ConstEnumVariant { ident, value })
+        })?;
+        Ok(ConstEnumDeclaration {
+            ident,
+            body: ConstEnumBody { variants },
+        })
+    }
+
+    fn reexport_clause(&mut self) -> Result<ReexportClause, ParseError> {
+        let scope: StrictTSIdent = self.ident()?;
+        let export_as = if self.eat_keyword("as") {
+            self.ident()?
+        } else {
+            scope.clone()
+        };
+        Ok(ReexportClause { scope, export_as })
+    }
+
+    fn type_parameters(&mut self) -> Result<Option<TypeParameters>, ParseError> {
+        if !self.eat_punct('<') {
+            return Ok(None);
+        }
+        let parameters = self.list('>', |parser| {
+            let identifier = parser.ident()?;
+            let constraint = if parser.eat_keyword("extends") {
+                let types = match parser.ts_type()? {
+                    TsType::IntersectionType(intersection) => intersection.types,
+                    ts_type => vec![ts_type],
+                };
+                Some(ExtendsConstraint { types })
+            } else {
+                None
+            };
+            Ok(TypeParameter {
+                identifier,
+                constraint,
+            })
+        })?;
+        Ok(Some(TypeParameters { parameters }))
+    }
+
+    fn type_reference(&mut self) -> Result<TypeReference, ParseError> {
+        let name = self.ident()?;
+        let args = if self.eat_punct('<') {
+            Some(TypeArguments {
+                types: self.list('>', Self::ts_type)?,
+            })
+        } else {
+            None
+        };
+        Ok(TypeReference { name, args })
+    }
+
+    fn ts_type(&mut self) -> Result<TsType, ParseError> {
+        self.eat_punct('|');
+        let mut types = vec![self.intersection_type()?];
+        while self.eat_punct('|') {
+            types.push(self.intersection_type()?);
+        }
+        if types.len() == 1 {
+            return Ok(types.remove(0));
+        }
+        Ok(UnionType { types }.into())
+    }
+
+    fn intersection_type(&mut self) -> Result<TsType, ParseError> {
+        self.eat_punct('&');
+        let mut types = vec![self.array_type()?];
+        while self.eat_punct('&') {
+            types.push(self.array_type()?);
+        }
+        if types.len() == 1 {
+            return Ok(types.remove(0));
+        }
+        Ok(IntersectionType { types }.into())
+    }
+
+    fn array_type(&mut self) -> Result<TsType, ParseError> {
+        let mut ts_type = self.primary_type()?;
+        while self.peek() == Some(&Token::Punct('['))
+            && self.peek_nth(1) == Some(&Token::Punct(']'))
+        {
+            self.position += 2;
+            ts_type = PrimaryType::ArrayType(ArrayType::new(ts_type)).into();
+        }
+        Ok(ts_type)
+    }
+
+    fn primary_type(&mut self) -> Result<TsType, ParseError> {
+        let primary: PrimaryType = match self.peek() {
+            Some(Token::Punct('(')) => {
+                self.position += 1;
+                let inner = self.ts_type()?;
+                self.expect_punct(')')?;
+                return Ok(ParenthesizedType {
+                    inner: Box::new(inner),
+                }
+                .into());
+            }
+            Some(Token::Punct('{')) => {
+                self.position += 1;
+                self.object_type()?.into()
+            }
+            Some(Token::Punct('[')) => {
+                self.position += 1;
+                TupleType {
+                    inner_types: self.list(']', Self::ts_type)?,
+                }
+                .into()
+            }
+            Some(Token::Punct('-')) => {
+                self.position += 1;
+                match self.peek() {
+                    Some(Token::Number(number)) => {
+                        let literal = self.numeric_literal(-number)?;
+                        self.position += 1;
+                        literal
+                    }
+                    _ => return Err(self.unexpected("a number")),
+                }
+            }
+            Some(Token::Number(number)) => {
+                let literal = self.numeric_literal(*number)?;
+                self.position += 1;
+                literal
+            }
+            Some(Token::String(value)) => {
+                let literal = LiteralType::from(StringLiteral::from(value.clone()));
+                self.position += 1;
+                literal.into()
+            }
+            Some(Token::Template(template)) => {
+                let verbatim = PrimaryType::Verbatim(template.clone());
+                self.position += 1;
+                verbatim
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" | "false" => {
+                    let literal = LiteralType::from(BooleanLiteral::from(ident == "true"));
+                    self.position += 1;
+                    literal.into()
+                }
+                "any" | "number" | "boolean" | "string" | "unknown" | "null" | "never" => {
+                    let predefined = match ident.as_str() {
+                        "any" => PredefinedType::Any,
+                        "number" => PredefinedType::Number,
+                        "boolean" => PredefinedType::Boolean,
+                        "string" => PredefinedType::String,
+                        "unknown" => PredefinedType::Unknown,
+                        "null" => PredefinedType::Null,
+                        _ => PredefinedType::Never,
+                    };
+                    self.position += 1;
+                    predefined.into()
+                }
+                _ => self.type_reference()?.into(),
+            },
+            _ => return Err(self.unexpected("a type")),
+        };
+        Ok(primary.into())
+    }
+
+    fn numeric_literal(&self, number: f64) -> Result<PrimaryType, ParseError> {
+        let literal = NumericLiteral::try_from(number).map_err(|e| self.error(&e.to_string()))?;
+        Ok(LiteralType::from(literal).into())
+    }
+
+    /// Parses the members of an object type, after its opening brace
+    fn object_type(&mut self) -> Result<ObjectType, ParseError> {
+        let mut members = Vec::new();
+        while !self.eat_punct('}') {
+            let name = match self.next()? {
+                Token::Ident(ident) => PropertyName::from(ident),
+                Token::String(value) => PropertyName::StringLiteral(StringLiteral::from(value)),
+                _ => {
+                    self.position -= 1;
+                    return Err(self.unexpected("a property name"));
+                }
+            };
+            let optional = self.eat_punct('?');
+            self.expect_punct(':')?;
+            let inner_type = self.ts_type()?;
+            members.push(TypeMember::PropertySignature(PropertySignature {
+                name,
+                optional,
+                inner_type,
+            }));
+            if !self.eat_punct(',') && !self.eat_punct(';') && !self.at_line_start() {
+                self.expect_punct('}')?;
+                break;
+            }
+        }
+        Ok(ObjectType {
+            body: TypeBody { members },
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::ident::TSIdent;
+
+    #[test]
+    fn should_parse_the_printed_module() {
+        let input = r#"// This file was auto-generated with typebinder from Rust source code.
+import { Id, Other } from "../common";
+
+export interface User<T extends string> extends Base {
+	id: Id,
+	"display-name"?: string | null,
+	tags: T[]
+}
+
+export type Event = ({
+	type: "Created"
+} & {
+	id: number
+}) | ({
+	type: "Deleted"
+});
+
+export const enum Kind { One = "one", Two = "two" }
+export { User as Person };
+"#;
+        let statements = parse_module(input).unwrap();
+        assert_eq!(statements.len(), 5);
+        let printed: Vec<String> = statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Import(import) => import.to_string(),
+                Statement::Export(export) => export.to_string(),
+            })
+            .collect();
+        assert_eq!(printed[0], r#"import { Id, Other } from "../common";"#);
+        assert_eq!(
+            printed[1],
+            "export interface User<T extends string> extends Base{\n\tid: Id,\n\t\"display-name\"?: string | null,\n\ttags: T[]\n}"
+        );
+        assert_eq!(
+            printed[2],
+            "export type Event = ( {\n\ttype: \"Created\"\n} & {\n\tid: number\n} ) | ( {\n\ttype: \"Deleted\"\n} );"
+        );
+        assert_eq!(
+            printed[3],
+            r#"export const enum Kind { One = "one", Two = "two" }"#
+        );
+        assert_eq!(printed[4], "export { User as Person }");
+    }
+
+    #[test]
+    fn should_parse_formatted_types() {
+        let formatted =
+            parse_type("\n  | { a: 'x'; b?: -1.5; }\n  | [string, `${number}px`,]").unwrap();
+        assert_eq!(
+            formatted.to_string(),
+            "{\n\ta: \"x\",\n\tb?: -1.5\n} | [ string, `${number}px` ]"
+        );
+        assert_eq!(
+            parse_type("Record<string, (A | B)[]>").unwrap().to_string(),
+            "Record<string, ( A | B )[]>"
+        );
+    }
+
+    #[test]
+    fn should_locate_errors() {
+        assert_eq!(
+            parse_module("export interface A {\n\ta: number,\n\tb: ,\n}").unwrap_err(),
+            ParseError {
+                line: 3,
+                column: 5,
+                message: "expected a type, found `,`".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_type("A<B").unwrap_err().message,
+            "expected `>`, found the end of input"
+        );
+    }
+
+    fn reference(name: &str, args: Vec<TsType>) -> TsType {
+        PrimaryType::TypeReference(TypeReference {
+            name: StrictTSIdent::from_str(name).unwrap(),
+            args: if args.is_empty() {
+                None
+            } else {
+                Some(TypeArguments { types: args })
+            },
+        })
+        .into()
+    }
+
+    fn predefined(predefined: PredefinedType) -> TsType {
+        PrimaryType::Predefined(predefined).into()
+    }
+
+    fn object(members: Vec<(&str, bool, TsType)>) -> ObjectType {
+        ObjectType {
+            body: TypeBody {
+                members: members
+                    .into_iter()
+                    .map(|(name, optional, inner_type)| {
+                        TypeMember::PropertySignature(PropertySignature {
+                            name: PropertyName::from(name.to_string()),
+                            optional,
+                            inner_type,
+                        })
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    fn literal<L: Into<LiteralType>>(literal: L) -> TsType {
+        PrimaryType::LiteralType(literal.into()).into()
+    }
+
+    fn assert_round_trip(statement: ExportStatement) {
+        let printed = statement.to_string();
+        assert_eq!(
+            ExportStatement::from_str(&printed),
+            Ok(statement),
+            "{}",
+            printed
+        );
+    }
+
+    #[test]
+    fn should_round_trip_types() {
+        let types = vec![
+            predefined(PredefinedType::Any),
+            predefined(PredefinedType::Number),
+            predefined(PredefinedType::Boolean),
+            predefined(PredefinedType::String),
+            predefined(PredefinedType::Unknown),
+            predefined(PredefinedType::Null),
+            predefined(PredefinedType::Never),
+            literal(StringLiteral::from_raw("with \"quotes\", \\ and \u{e9}\n")),
+            literal(NumericLiteral::try_from(-1.5e-7).unwrap()),
+            literal(NumericLiteral::try_from(42.0).unwrap()),
+            literal(BooleanLiteral::from(true)),
+            literal(BooleanLiteral::from(false)),
+            PrimaryType::Verbatim("`${number}px`".to_string()).into(),
+            reference(
+                "Record",
+                vec![predefined(PredefinedType::String), reference("T", vec![])],
+            ),
+            PrimaryType::ArrayType(ArrayType::new(
+                UnionType {
+                    types: vec![
+                        predefined(PredefinedType::Number),
+                        predefined(PredefinedType::Null),
+                    ],
+                }
+                .into(),
+            ))
+            .into(),
+            PrimaryType::ArrayType(ArrayType::new(
+                PrimaryType::ArrayType(ArrayType::new(predefined(PredefinedType::String))).into(),
+            ))
+            .into(),
+            PrimaryType::TupleType(TupleType {
+                inner_types: vec![predefined(PredefinedType::Number), reference("A", vec![])],
+            })
+            .into(),
+            PrimaryType::TupleType(TupleType {
+                inner_types: vec![],
+            })
+            .into(),
+            PrimaryType::ObjectType(object(vec![])).into(),
+            UnionType {
+                types: vec![
+                    ParenthesizedType {
+                        inner: Box::new(
+                            IntersectionType {
+                                types: vec![
+                                    PrimaryType::ObjectType(object(vec![(
+                                        "type",
+                                        false,
+                                        literal(StringLiteral::from_raw("A")),
+                                    )]))
+                                    .into(),
+                                    reference("B", vec![]),
+                                ],
+                            }
+                            .into(),
+                        ),
+                    }
+                    .into(),
+                    predefined(PredefinedType::Null),
+                ],
+            }
+            .into(),
+        ];
+        for ts_type in types {
+            let printed = ts_type.to_string();
+            assert_eq!(TsType::from_str(&printed), Ok(ts_type), "{}", printed);
+        }
+    }
+
+    #[test]
+    fn should_round_trip_declarations() {
+        let type_params = TypeParameters {
+            parameters: vec![
+                TypeParameter {
+                    identifier: TSIdent::from_str("K").unwrap(),
+                    constraint: Some(ExtendsConstraint {
+                        types: vec![reference("Key", vec![])],
+                    }),
+                },
+                TypeParameter {
+                    identifier: TSIdent::from_str("V").unwrap(),
+                    constraint: None,
+                },
+            ],
+        };
+
+        assert_round_trip(
+            InterfaceDeclaration {
+                ident: StrictTSIdent::from_str("Map").unwrap(),
+                type_params: Some(type_params.clone()),
+                extends_clause: Some(InterfaceExtendsClause {
+                    type_list: InterfaceTypeList {
+                        identifiers: vec![
+                            TypeReference {
+                                name: StrictTSIdent::from_str("Base").unwrap(),
+                                args: Some(TypeArguments {
+                                    types: vec![reference("V", vec![])],
+                                }),
+                            },
+                            TypeReference {
+                                name: StrictTSIdent::from_str("Other").unwrap(),
+                                args: None,
+                            },
+                        ],
+                    },
+                }),
+                obj_type: object(vec![
+                    (
+                        "entries",
+                        false,
+                        reference(
+                            "Record",
+                            vec![reference("K", vec![]), reference("V", vec![])],
+                        ),
+                    ),
+                    ("type", true, predefined(PredefinedType::String)),
+                    ("kebab-case", false, predefined(PredefinedType::Number)),
+                ]),
+            }
+            .into(),
+        );
+        assert_round_trip(
+            InterfaceDeclaration {
+                ident: StrictTSIdent::from_str("Empty").unwrap(),
+                type_params: None,
+                extends_clause: None,
+                obj_type: object(vec![]),
+            }
+            .into(),
+        );
+        assert_round_trip(
+            TypeAliasDeclaration {
+                ident: StrictTSIdent::from_str("Alias").unwrap(),
+                type_params: Some(type_params),
+                inner_type: UnionType {
+                    types: vec![
+                        PrimaryType::ObjectType(object(vec![(
+                            "\"Quoted\"",
+                            false,
+                            reference("K", vec![]),
+                        )]))
+                        .into(),
+                        predefined(PredefinedType::Null),
+                    ],
+                }
+                .into(),
+            }
+            .into(),
+        );
+        // A constraint of several types is parsed from an intersection, as the printer writes it
+        assert_eq!(
+            ExportStatement::from_str("export type Keyed<K extends string & Key> = K;"),
+            Ok(TypeAliasDeclaration {
+                ident: StrictTSIdent::from_str("Keyed").unwrap(),
+                type_params: Some(TypeParameters {
+                    parameters: vec![TypeParameter {
+                        identifier: TSIdent::from_str("K").unwrap(),
+                        constraint: Some(ExtendsConstraint {
+                            types: vec![
+                                predefined(PredefinedType::String),
+                                reference("Key", vec![])
+                            ],
+                        }),
+                    }],
+                }),
+                inner_type: reference("K", vec![]),
+            }
+            .into())
+        );
+        assert_round_trip(
+            ConstEnumDeclaration {
+                ident: StrictTSIdent::from_str("Kind").unwrap(),
+                body: ConstEnumBody {
+                    variants: vec![
+                        ConstEnumVariant {
+                            ident: StrictTSIdent::from_str("One").unwrap(),
+                            value: StringLiteral::from_raw("one"),
+                        },
+                        ConstEnumVariant {
+                            ident: StrictTSIdent::from_str("Two").unwrap(),
+                            value: StringLiteral::from_raw("t\"wo"),
+                        },
+                    ],
+                },
+            }
+            .into(),
+        );
+        assert_round_trip(
+            ReexportDeclaration {
+                reexports: vec![ReexportClause {
+                    scope: StrictTSIdent::from_str("Inner").unwrap(),
+                    export_as: StrictTSIdent::from_str("Outer").unwrap(),
+                }],
+            }
+            .into(),
+        );
+        assert_round_trip(
+            ExportAllDeclaration {
+                path: r#""./user""#.to_string(),
+                namespace: None,
+            }
+            .into(),
+        );
+        assert_round_trip(
+            ExportAllDeclaration {
+                path: r#""./status""#.to_string(),
+                namespace: Some(StrictTSIdent::from_str("status").unwrap()),
+            }
+            .into(),
+        );
+    }
+
+    #[test]
+    fn should_round_trip_imports() {
+        let imports = vec![
+            ImportStatement {
+                import_kind: ImportKind::Identifier(TSIdent::from_str("Default").unwrap()),
+                path: r#""./default""#.to_string(),
+                type_only: false,
+            },
+            ImportStatement {
+                import_kind: ImportKind::Identifier(TSIdent::from_str("type").unwrap()),
+                path: r#""./default""#.to_string(),
+                type_only: false,
+            },
+            ImportStatement {
+                import_kind: ImportKind::GlobAsIdentifier(TSIdent::from_str("All").unwrap()),
+                path: r#""../all""#.to_string(),
+                type_only: true,
+            },
+            ImportStatement {
+                import_kind: ImportKind::ImportList(ImportList {
+                    items: vec![
+                        TSIdent::from_str("A").unwrap().into(),
+                        ImportSpecifier {
+                            ident: TSIdent::from_str("B").unwrap(),
+                            alias: Some(TSIdent::from_str("OtherB").unwrap()),
+                        },
+                    ],
+                }),
+                path: r#""@scope/types""#.to_string(),
+                type_only: false,
+            },
+            ImportStatement {
+                import_kind: ImportKind::ImportList(ImportList {
+                    items: vec![TSIdent::from_str("A").unwrap().into()],
+                }),
+                path: r#""@scope/types""#.to_string(),
+                type_only: true,
+            },
+        ];
+        for import in imports {
+            let printed = import.to_string();
+            assert_eq!(
+                ImportStatement::from_str(&printed),
+                Ok(import),
+                "{}",
+                printed
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_trailing_input() {
+        assert_eq!(
+            ImportStatement::from_str("import A from \"a\"; export { A as B }")
+                .unwrap_err()
+                .message,
+            "expected the end of input, found `export`"
+        );
+    }
+}
@@ -0,0 +1,706 @@
+//! A pretty-printer with a configurable style, as an alternative to the [Display](std::fmt::Display) implementations.
+//!
+//! The default [PrintConfig] follows the defaults of prettier : 2 spaces, semicolons, trailing commas and
+//! double quotes. Object types and const enums are always expanded, one member per line, and nested objects
+//! are indented according to their depth. Unions are put one member per line when they don't fit in the print
+//! width, or when they have several multiline members.
+//!
+//! The printed statements can be parsed back with the [parser](crate::parser).
+use std::str::FromStr;
+
+use crate::{
+    common::StringLiteral,
+    declarations::{
+        const_enum::ConstEnumDeclaration, interface::InterfaceDeclaration,
+        reexport::ReexportDeclaration, type_alias::TypeAliasDeclaration,
+    },
+    export::ExportStatement,
+    ident::TSIdent,
+    import::{ImportKind, ImportStatement},
+    types::{
+        ArrayTypeInner, IntersectionType, LiteralType, ObjectType, PredefinedType, PrimaryType,
+        PropertyName, TsType, TypeArguments, TypeMember, TypeParameters, UnionType,
+    },
+};
+
+/// Number of columns a tab counts for, when checking the print width
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Indent {
+    Tabs,
+    /// The number of spaces of each level
+    Spaces(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum QuoteStyle {
+    Double,
+    Single,
+}
+
+impl QuoteStyle {
+    fn quote(&self) -> char {
+        match self {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// The style of the printed statements
+pub struct PrintConfig {
+    pub indent: Indent,
+    /// Ends the statements and the members of object types with `;`. Without, the members are only separated
+    /// by line breaks, as prettier does with `semi: false`
+    pub semicolons: bool,
+    /// Adds a `,` after the last variant of const enums
+    pub trailing_commas: bool,
+    /// The quotes of string literals, property names and import paths
+    pub quote_style: QuoteStyle,
+    /// The width that unions are broken at
+    pub print_width: usize,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        PrintConfig {
+            indent: Indent::Spaces(2),
+            semicolons: true,
+            trailing_commas: true,
+            quote_style: QuoteStyle::Double,
+            print_width: 80,
+        }
+    }
+}
+
+/// Printing with a [PrintConfig]
+pub trait Print {
+    fn print(&self, config: &PrintConfig) -> String;
+}
+
+impl Print for ExportStatement {
+    fn print(&self, config: &PrintConfig) -> String {
+        let printer = Printer { config };
+        let declaration = match self {
+            ExportStatement::InterfaceDeclaration(declaration) => printer.interface(declaration),
+            ExportStatement::TypeAliasDeclaration(declaration) => printer.type_alias(declaration),
+            ExportStatement::ConstEnumDeclaration(declaration) => printer.const_enum(declaration),
+            ExportStatement::ReexportDeclaration(declaration) => printer.reexport(declaration),
+            ExportStatement::ExportAllDeclaration(declaration) => format!(
+                "* {}from {}{}",
+                declaration
+                    .namespace
+                    .as_ref()
+                    .map(|namespace| format!("as {} ", namespace))
+                    .unwrap_or_default(),
+                printer.path(&declaration.path),
+                printer.semicolon()
+            ),
+        };
+        format!("export {}", declaration)
+    }
+}
+
+impl Print for ImportStatement {
+    fn print(&self, config: &PrintConfig) -> String {
+        let printer = Printer { config };
+        let import_kind = match &self.import_kind {
+            ImportKind::Identifier(ident) => ident.to_string(),
+            ImportKind::GlobAsIdentifier(ident) => format!("* as {}", ident),
+            ImportKind::ImportList(list) => format!(
+                "{{ {} }}",
+                list.items
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        // The path is stored with its quotes
+        let path = match self
+            .path
+            .strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+        {
+            Some(escaped) => printer.quoted(escaped),
+            None => self.path.clone(),
+        };
+        format!(
+            "import {}{} from {}{}",
+            if self.type_only { "type " } else { "" },
+            import_kind,
+            path,
+            printer.semicolon()
+        )
+    }
+}
+
+impl Print for TsType {
+    fn print(&self, config: &PrintConfig) -> String {
+        Printer { config }.ts_type(self, 0)
+    }
+}
+
+struct Printer<'a> {
+    config: &'a PrintConfig,
+}
+
+impl<'a> Printer<'a> {
+    fn indent(&self, level: usize) -> String {
+        match self.config.indent {
+            Indent::Tabs => "\t".repeat(level),
+            Indent::Spaces(width) => " ".repeat(width * level),
+        }
+    }
+
+    /// The column at the end of the printed text
+    fn column(&self, printed: &str) -> usize {
+        let line = printed.rsplit('\n').next().unwrap_or_default();
+        line.chars()
+            .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+            .sum()
+    }
+
+    fn semicolon(&self) -> &'static str {
+        if self.config.semicolons {
+            ";"
+        } else {
+            ""
+        }
+    }
+
+    /// Quotes an escaped string, only escaping the quotes of the configured style
+    fn quoted(&self, escaped: &str) -> String {
+        let quote = self.config.quote_style.quote();
+        let mut quoted = String::with_capacity(escaped.len() + 2);
+        quoted.push(quote);
+        let mut chars = escaped.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped @ ('"' | '\'')) if escaped != quote => quoted.push(escaped),
+                    Some(escaped) => {
+                        quoted.push('\\');
+                        quoted.push(escaped);
+                    }
+                    None => quoted.push('\\'),
+                },
+                c if c == quote => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push(quote);
+        quoted
+    }
+
+    /// Requotes a module path, that is stored with its quotes
+    fn path(&self, path: &str) -> String {
+        match path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+            Some(escaped) => self.quoted(escaped),
+            None => path.to_string(),
+        }
+    }
+
+    fn string_literal(&self, literal: &StringLiteral) -> String {
+        self.quoted(literal.escaped())
+    }
+
+    fn interface(&self, declaration: &InterfaceDeclaration) -> String {
+        let extends = match &declaration.extends_clause {
+            Some(extends_clause) => format!(
+                " extends {}",
+                extends_clause
+                    .type_list
+                    .identifiers
+                    .iter()
+                    .map(|reference| {
+                        self.primary(&PrimaryType::TypeReference(reference.clone()), 0)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        };
+        format!(
+            "interface {}{}{} {}",
+            declaration.ident,
+            self.type_params(&declaration.type_params),
+            extends,
+            self.object(&declaration.obj_type, 0)
+        )
+    }
+
+    fn type_alias(&self, declaration: &TypeAliasDeclaration) -> String {
+        let head = format!(
+            "type {}{} =",
+            declaration.ident,
+            self.type_params(&declaration.type_params)
+        );
+        // `export ` is not part of the head
+        let annotation = self.annotation(&declaration.inner_type, 0, self.column(&head) + 7);
+        format!("{}{}{}", head, annotation, self.semicolon())
+    }
+
+    fn const_enum(&self, declaration: &ConstEnumDeclaration) -> String {
+        let variants = &declaration.body.variants;
+        if variants.is_empty() {
+            return format!("const enum {} {{}}", declaration.ident);
+        }
+        let body: String = variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let comma = index + 1 < variants.len() || self.config.trailing_commas;
+                format!(
+                    "{}{} = {}{}\n",
+                    self.indent(1),
+                    variant.ident,
+                    self.string_literal(&variant.value),
+                    if comma { "," } else { "" }
+                )
+            })
+            .collect();
+        format!("const enum {} {{\n{}}}", declaration.ident, body)
+    }
+
+    fn reexport(&self, declaration: &ReexportDeclaration) -> String {
+        format!(
+            "{{ {} }}{}",
+            declaration
+                .reexports
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.semicolon()
+        )
+    }
+
+    fn type_params(&self, type_params: &Option<TypeParameters>) -> String {
+        let type_params = match type_params {
+            Some(type_params) => type_params,
+            None => return String::new(),
+        };
+        let parameters: Vec<String> = type_params
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.constraint {
+                Some(constraint) => format!(
+                    "{} extends {}",
+                    parameter.identifier,
+                    self.join(&constraint.types, " & ", 0)
+                ),
+                None => parameter.identifier.to_string(),
+            })
+            .collect();
+        format!("<{}>", parameters.join(", "))
+    }
+
+    fn type_args(&self, type_args: &Option<TypeArguments>, level: usize) -> String {
+        match type_args {
+            Some(type_args) => format!("<{}>", self.join(&type_args.types, ", ", level)),
+            None => String::new(),
+        }
+    }
+
+    fn join(&self, types: &[TsType], separator: &str, level: usize) -> String {
+        types
+            .iter()
+            .map(|ts_type| self.ts_type(ts_type, level))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Prints a type after a `=` or a `:` ending at the given column, breaking the unions that don't fit
+    fn annotation(&self, ts_type: &TsType, level: usize, column: usize) -> String {
+        let ts_type = simplified(ts_type);
+        let union = match ts_type {
+            TsType::UnionType(union) => union,
+            _ => return format!(" {}", self.ts_type(ts_type, level)),
+        };
+        let flat = self.ts_type(ts_type, level);
+        let fits = match flat.find('\n') {
+            None => column + 1 + flat.len() <= self.config.print_width,
+            Some(_) => self.should_hug(union),
+        };
+        if fits {
+            return format!(" {}", flat);
+        }
+        union
+            .types
+            .iter()
+            .map(|ts_type| {
+                format!(
+                    "\n{}| {}",
+                    self.indent(level + 1),
+                    self.union_member(ts_type, level + 2)
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the union is kept on the lines of its only object, as in `{ ... } | null`
+    fn should_hug(&self, union: &UnionType) -> bool {
+        let objects = union
+            .types
+            .iter()
+            .filter(|ts_type| {
+                matches!(
+                    simplified(ts_type),
+                    TsType::PrimaryType(PrimaryType::ObjectType(_))
+                )
+            })
+            .count();
+        let nulls = union
+            .types
+            .iter()
+            .filter(|ts_type| {
+                matches!(
+                    simplified(ts_type),
+                    TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Null))
+                )
+            })
+            .count();
+        objects == 1 && objects + nulls == union.types.len()
+    }
+
+    /// Prints a type whose first line starts at the given indentation level.
+    ///
+    /// As prettier does, the parentheses are only printed where they are needed, and around the intersections
+    /// that are members of a union.
+    fn ts_type(&self, ts_type: &TsType, level: usize) -> String {
+        match simplified(ts_type) {
+            TsType::PrimaryType(primary) => self.primary(primary, level),
+            TsType::UnionType(union) => union
+                .types
+                .iter()
+                .map(|ts_type| self.union_member(ts_type, level))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            TsType::IntersectionType(intersection) => intersection
+                .types
+                .iter()
+                .map(|ts_type| match simplified(ts_type) {
+                    union @ TsType::UnionType(_) => format!("({})", self.ts_type(union, level)),
+                    ts_type => self.ts_type(ts_type, level),
+                })
+                .collect::<Vec<_>>()
+                .join(" & "),
+            TsType::ParenthesizedType(parenthesized) => self.ts_type(&parenthesized.inner, level),
+        }
+    }
+
+    fn union_member(&self, ts_type: &TsType, level: usize) -> String {
+        match simplified(ts_type) {
+            intersection @ TsType::IntersectionType(_) => {
+                format!("({})", self.ts_type(intersection, level))
+            }
+            ts_type => self.ts_type(ts_type, level),
+        }
+    }
+
+    fn primary(&self, primary: &PrimaryType, level: usize) -> String {
+        match primary {
+            PrimaryType::Predefined(predefined) => predefined.to_string(),
+            PrimaryType::TypeReference(reference) => format!(
+                "{}{}",
+                reference.name,
+                self.type_args(&reference.args, level)
+            ),
+            PrimaryType::ObjectType(object) => self.object(object, level),
+            PrimaryType::ArrayType(array) => match array.inner_type.as_ref() {
+                ArrayTypeInner::Primary(primary) => format!("{}[]", self.primary(primary, level)),
+                ArrayTypeInner::Parenthesized(parenthesized) => {
+                    match simplified(&parenthesized.inner) {
+                        TsType::PrimaryType(primary) => {
+                            format!("{}[]", self.primary(primary, level))
+                        }
+                        ts_type => format!("({})[]", self.ts_type(ts_type, level)),
+                    }
+                }
+            },
+            PrimaryType::TupleType(tuple) => {
+                format!("[{}]", self.join(&tuple.inner_types, ", ", level))
+            }
+            PrimaryType::LiteralType(LiteralType::StringLiteral(literal)) => {
+                self.string_literal(literal)
+            }
+            PrimaryType::LiteralType(literal) => literal.to_string(),
+            PrimaryType::Verbatim(verbatim) => verbatim.clone(),
+        }
+    }
+
+    fn object(&self, object: &ObjectType, level: usize) -> String {
+        let members = &object.body.members;
+        if members.is_empty() {
+            return "{}".to_string();
+        }
+        let body: String = members
+            .iter()
+            .map(|member| {
+                let TypeMember::PropertySignature(property) = member;
+                let name = match &property.name {
+                    PropertyName::Identifier(ident) => ident.to_string(),
+                    // As prettier does, only the names that are not identifiers are quoted
+                    PropertyName::StringLiteral(literal) => {
+                        match TSIdent::from_str(literal.escaped()) {
+                            Ok(ident) => ident.to_string(),
+                            Err(_) => self.string_literal(literal),
+                        }
+                    }
+                };
+                let head = format!(
+                    "{}{}{}:",
+                    self.indent(level + 1),
+                    name,
+                    if property.optional { "?" } else { "" }
+                );
+                // As prettier does with `semi: false`, the members are only separated by line breaks
+                let separator = if self.config.semicolons { ";" } else { "" };
+                format!(
+                    "{}{}{}\n",
+                    head,
+                    self.annotation(&property.inner_type, level + 1, self.column(&head)),
+                    separator
+                )
+            })
+            .collect();
+        format!("{{\n{}{}}}", body, self.indent(level))
+    }
+}
+
+/// The type without the parentheses, the single-member unions and the single-member intersections around it
+fn simplified(ts_type: &TsType) -> &TsType {
+    match ts_type {
+        TsType::ParenthesizedType(parenthesized) => simplified(&parenthesized.inner),
+        TsType::UnionType(UnionType { types })
+        | TsType::IntersectionType(IntersectionType { types })
+            if types.len() == 1 =>
+        {
+            simplified(&types[0])
+        }
+        ts_type => ts_type,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::parser::{parse_module, parse_type, Statement};
+
+    fn print_module(source: &str, config: &PrintConfig) -> String {
+        parse_module(source)
+            .unwrap()
+            .iter()
+            .map(|statement| match statement {
+                Statement::Import(import) => import.print(config),
+                Statement::Export(export) => export.print(config),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    const MODULE: &str = r#"
+        import { Id } from "../common";
+        export interface User extends Base { "id": Id, "display-name"?: string | null, role: "Admin" | "User" }
+        export type Event = { type: "Created", data: { user: User, at: { date: string } } } | { type: "Deleted" };
+        export type Nullable = { a: number } | null;
+        export const enum Kind { One = "one", Two = "it's" }
+        export { User as Person };
+        export * from "./user";
+    "#;
+
+    #[test]
+    fn should_print_like_prettier() {
+        assert_eq!(
+            print_module(MODULE, &PrintConfig::default()),
+            r#"import { Id } from "../common";
+export interface User extends Base {
+  id: Id;
+  "display-name"?: string | null;
+  role: "Admin" | "User";
+}
+export type Event =
+  | {
+      type: "Created";
+      data: {
+        user: User;
+        at: {
+          date: string;
+        };
+      };
+    }
+  | {
+      type: "Deleted";
+    };
+export type Nullable = {
+  a: number;
+} | null;
+export const enum Kind {
+  One = "one",
+  Two = "it's",
+}
+export { User as Person };
+export * from "./user";"#
+        );
+    }
+
+    #[test]
+    fn should_follow_the_config() {
+        let config = PrintConfig {
+            indent: Indent::Tabs,
+            semicolons: false,
+            trailing_commas: false,
+            quote_style: QuoteStyle::Single,
+            print_width: 80,
+        };
+        assert_eq!(
+            print_module(MODULE, &config),
+            "import { Id } from '../common'
+export interface User extends Base {
+\tid: Id
+\t'display-name'?: string | null
+\trole: 'Admin' | 'User'
+}
+export type Event =
+\t| {
+\t\t\ttype: 'Created'
+\t\t\tdata: {
+\t\t\t\tuser: User
+\t\t\t\tat: {
+\t\t\t\t\tdate: string
+\t\t\t\t}
+\t\t\t}
+\t\t}
+\t| {
+\t\t\ttype: 'Deleted'
+\t\t}
+export type Nullable = {
+\ta: number
+} | null
+export const enum Kind {
+\tOne = 'one',
+\tTwo = 'it\\'s'
+}
+export { User as Person }
+export * from './user'"
+        );
+    }
+
+    /// An internally tagged enum, whose variants are intersections, with an adjacently tagged enum in a variant
+    const TAGGED_ENUM: &str = r#"
+        export type Event = ({ type: "Created" } & User) | ({ type: "Updated" } & { change: ({ kind: "Name" } & { name: string }) | ({ kind: "Reset" }) }) | ({ type: "Deleted" });
+    "#;
+
+    #[test]
+    fn should_print_the_tagged_enums_like_prettier() {
+        // Formatted with prettier 3 and its default options
+        assert_eq!(
+            print_module(TAGGED_ENUM, &PrintConfig::default()),
+            r#"export type Event =
+  | ({
+      type: "Created";
+    } & User)
+  | ({
+      type: "Updated";
+    } & {
+      change:
+        | ({
+            kind: "Name";
+          } & {
+            name: string;
+          })
+        | {
+            kind: "Reset";
+          };
+    })
+  | {
+      type: "Deleted";
+    };"#
+        );
+        // Formatted with prettier 3 and `semi: false`
+        assert_eq!(
+            print_module(
+                TAGGED_ENUM,
+                &PrintConfig {
+                    semicolons: false,
+                    ..PrintConfig::default()
+                }
+            ),
+            r#"export type Event =
+  | ({
+      type: "Created"
+    } & User)
+  | ({
+      type: "Updated"
+    } & {
+      change:
+        | ({
+            kind: "Name"
+          } & {
+            name: string
+          })
+        | {
+            kind: "Reset"
+          }
+    })
+  | {
+      type: "Deleted"
+    }"#
+        );
+    }
+
+    #[test]
+    fn should_break_the_unions_that_do_not_fit() {
+        let union =
+            r#"export type Status = "Pending" | "Active" | "Suspended" | "Banned" | "Deleted";"#;
+        let config = PrintConfig::default();
+        assert_eq!(print_module(union, &config), union);
+        assert_eq!(
+            print_module(
+                union,
+                &PrintConfig {
+                    print_width: 60,
+                    ..config.clone()
+                }
+            ),
+            "export type Status =\n  | \"Pending\"\n  | \"Active\"\n  | \"Suspended\"\n  | \"Banned\"\n  | \"Deleted\";"
+        );
+        assert_eq!(
+            parse_type("(A | B)[] | [A, B] | Record<string, { a: A }>")
+                .unwrap()
+                .print(&config),
+            "(A | B)[] | [A, B] | Record<string, {\n  a: A;\n}>"
+        );
+    }
+
+    #[test]
+    fn should_print_the_parsed_statements_the_same() {
+        for config in &[
+            PrintConfig::default(),
+            PrintConfig {
+                indent: Indent::Tabs,
+                semicolons: false,
+                trailing_commas: false,
+                quote_style: QuoteStyle::Single,
+                print_width: 20,
+            },
+        ] {
+            for module in &[MODULE, TAGGED_ENUM] {
+                let printed = print_module(module, config);
+                assert_eq!(print_module(&printed, config), printed);
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@ use displaythis::Display;
 use from_variants::FromVariants;
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ inner_type }}[]", ext = "txt")]
 /// A generic TS array
 pub struct ArrayType {
@@ -16,6 +17,7 @@ pub struct ArrayType {
 }
 
 #[derive(Debug, Clone, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArrayTypeInner {
     #[display("{0}")]
     Primary(PrimaryType),
@@ -40,6 +42,7 @@ impl ArrayType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "<{{ parameters|join(\", \") }}>", ext = "txt")]
 /// A identifier list of generic parameters
 pub struct TypeParameters {
@@ -47,6 +50,7 @@ pub struct TypeParameters {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(
     source = "{{ identifier -}} {{ constraint|display_opt -}}",
     ext = "txt"
@@ -57,7 +61,9 @@ pub struct TypeParameter {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = " extends {{ types|join(\", \") }}", ext = "txt")]
+/// The constraint of a generic parameter, that must extend all the given types
 pub struct ExtendsConstraint {
     pub types: Vec<TsType>,
 }
@@ -74,6 +80,7 @@ impl ExtendsConstraint {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "[ {{ inner_types|join(\", \") }} ]", ext = "txt")]
 /// A tuple represented as an array with positional types
 pub struct TupleType {
@@ -81,6 +88,7 @@ pub struct TupleType {
 }
 
 #[derive(Debug, Clone, PartialEq, Display, FromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A literal type, supports strings, numbers and booleans
 pub enum LiteralType {
     #[display("{0}")]
@@ -92,6 +100,7 @@ pub enum LiteralType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "<{{ types|join(\", \") }}>", ext = "txt")]
 /// A list of type arguments use in a generic parameter
 pub struct TypeArguments {
@@ -99,6 +108,7 @@ pub struct TypeArguments {
 }
 
 #[derive(Debug, Clone, PartialEq, Display, FromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A TS combination of TS types, supports unions, intersections and parenthesis
 pub enum TsType {
     #[display("{0}")]
@@ -112,6 +122,7 @@ pub enum TsType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ name }}{{ args|display_opt }}", ext = "txt")]
 /// A type identifier with support for generic parameters
 pub struct TypeReference {
@@ -120,6 +131,7 @@ pub struct TypeReference {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ types|join(\" | \") }}", ext = "txt")]
 /// An union of multiple TS types
 pub struct UnionType {
@@ -127,6 +139,7 @@ pub struct UnionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ types|join(\" & \") }}", ext = "txt")]
 /// An intersection of multiple TS types
 pub struct IntersectionType {
@@ -134,6 +147,7 @@ pub struct IntersectionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "( {{ inner }} )", ext = "txt")]
 /// A TS type surrounded by parenthesis
 pub struct ParenthesizedType {
@@ -141,6 +155,7 @@ pub struct ParenthesizedType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{\n\t{{ body }}\n}", ext = "txt")]
 /// A TS object type
 pub struct ObjectType {
@@ -148,12 +163,14 @@ pub struct ObjectType {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(source = "{{ members|join(\",\\n\t\") }}", ext = "txt")]
 pub struct TypeBody {
     pub members: Vec<TypeMember>,
 }
 
 #[derive(Debug, Clone, PartialEq, Display, FromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A member is a part of a type. It can be a property or a method, though
 /// we currently only support type Properties.
 pub enum TypeMember {
@@ -162,6 +179,7 @@ pub enum TypeMember {
 }
 
 #[derive(Debug, Clone, PartialEq, Template)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[template(
     source = "{{ name }}{% if optional %}?{% endif %}: {{ inner_type }}",
     ext = "txt"
@@ -174,6 +192,7 @@ pub struct PropertySignature {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display, FromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// An object property identifier
 pub enum PropertyName {
     #[display("{0}")]
@@ -192,6 +211,7 @@ impl From<String> for PropertyName {
 }
 
 #[derive(Debug, Clone, PartialEq, Display, FromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A single TS type
 pub enum PrimaryType {
     #[display("{0}")]
@@ -206,9 +226,13 @@ pub enum PrimaryType {
     TupleType(TupleType),
     #[display("{0}")]
     LiteralType(LiteralType),
+    /// A type written by hand, that is output as-is
+    #[display("{0}")]
+    Verbatim(String),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A globally defined TS type
 pub enum PredefinedType {
     #[display("any")]
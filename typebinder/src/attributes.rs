@@ -0,0 +1,375 @@
+//! Support for the `#[typebinder(...)]` attribute, that tweaks the output from the Rust side.
+//!
+//! The attribute is parsed alongside serde's attributes. For the attribute to compile in your code, derive
+//! `Typebinder` from the `typebinder_macros` crate, which registers it and generates nothing.
+//!
+//! Supported on containers (structs and enums) :
+//! * `skip` : the type is not exported, and can't be referred to by the exported types
+//! * `export = false` : the declaration is not emitted, because it is written by hand as a global TS type
+//!   (e.g. in a `.d.ts` file without imports nor exports). References to the type are kept, and are not imported.
+//! * `rename = "Name"` : name of the TS declaration. References from all the modules are renamed as well.
+//! * `type = "..."` : the type is opaque, and is declared as an alias to the given TS type
+//!
+//! Supported on fields :
+//! * `skip` : the named field is not exported
+//! * `type = "..."` : the TS type of the field
+//! * `optional` : the field is optional (`name?: T`)
+//!
+//! Supported on variants :
+//! * `skip` : the variant is not exported
+
+use serde::{Deserialize, Serialize};
+use syn::{spanned::Spanned, Attribute, Lit, Meta, NestedMeta};
+
+const ATTRIBUTE_NAME: &str = "typebinder";
+
+/// `#[typebinder(...)]` attributes of a struct or an enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerAttributes {
+    pub skip: bool,
+    pub export: bool,
+    pub rename: Option<String>,
+    pub ts_type: Option<String>,
+}
+
+impl Default for ContainerAttributes {
+    fn default() -> Self {
+        ContainerAttributes {
+            skip: false,
+            export: true,
+            rename: None,
+            ts_type: None,
+        }
+    }
+}
+
+impl ContainerAttributes {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self, syn::Error> {
+        let mut container_attributes = ContainerAttributes::default();
+        for meta in typebinder_metas(attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                    container_attributes.skip = true
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("export") =>
+                {
+                    container_attributes.export = parse_bool(&name_value.lit)?
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("rename") =>
+                {
+                    container_attributes.rename = Some(parse_string(&name_value.lit)?)
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("type") =>
+                {
+                    container_attributes.ts_type = Some(parse_string(&name_value.lit)?)
+                }
+                _ => return Err(unsupported(&meta, "a container")),
+            }
+        }
+        Ok(container_attributes)
+    }
+
+    /// Whether the declaration of the type should be emitted
+    pub fn is_exported(&self) -> bool {
+        self.export && !self.skip
+    }
+}
+
+/// What the `#[typebinder(...)]` attributes of the declarations of a module change for the other modules,
+/// by Rust name of the declaration
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclarationAttributes {
+    /// The declarations with a `rename`, along with their TS name
+    pub renamed: Vec<(String, String)>,
+    /// The declarations with `skip`
+    pub skipped: Vec<String>,
+    /// The declarations with `export = false`
+    pub global: Vec<String>,
+}
+
+impl DeclarationAttributes {
+    pub fn is_empty(&self) -> bool {
+        self.renamed.is_empty() && self.skipped.is_empty() && self.global.is_empty()
+    }
+}
+
+/// `#[typebinder(...)]` attributes of a field
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldAttributes {
+    pub skip: bool,
+    pub optional: bool,
+    pub ts_type: Option<String>,
+}
+
+impl FieldAttributes {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self, syn::Error> {
+        let mut field_attributes = FieldAttributes::default();
+        for meta in typebinder_metas(attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                    field_attributes.skip = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("optional") => {
+                    field_attributes.optional = true
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("type") =>
+                {
+                    field_attributes.ts_type = Some(parse_string(&name_value.lit)?)
+                }
+                _ => return Err(unsupported(&meta, "a field")),
+            }
+        }
+        Ok(field_attributes)
+    }
+}
+
+/// `#[typebinder(...)]` attributes of an enum variant
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VariantAttributes {
+    pub skip: bool,
+}
+
+impl VariantAttributes {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self, syn::Error> {
+        let mut variant_attributes = VariantAttributes::default();
+        for meta in typebinder_metas(attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                    variant_attributes.skip = true
+                }
+                _ => return Err(unsupported(&meta, "a variant")),
+            }
+        }
+        Ok(variant_attributes)
+    }
+}
+
+fn typebinder_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>, syn::Error> {
+    let mut metas = Vec::new();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(ATTRIBUTE_NAME))
+    {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected an attribute list, e.g. #[typebinder(skip)]",
+                ))
+            }
+        }
+    }
+    Ok(metas)
+}
+
+fn parse_string(lit: &Lit) -> Result<String, syn::Error> {
+    match lit {
+        Lit::Str(lit_str) => Ok(lit_str.value()),
+        _ => Err(syn::Error::new(lit.span(), "expected a string literal")),
+    }
+}
+
+fn parse_bool(lit: &Lit) -> Result<bool, syn::Error> {
+    match lit {
+        Lit::Bool(lit_bool) => Ok(lit_bool.value),
+        _ => Err(syn::Error::new(lit.span(), "expected a boolean literal")),
+    }
+}
+
+fn unsupported(meta: &NestedMeta, target: &str) -> syn::Error {
+    syn::Error::new(
+        meta.span(),
+        format!("unsupported typebinder attribute on {}", target),
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        contexts::{
+            exporter::ExporterContext, import::ImportContext,
+            type_solving::TypeSolvingContextBuilder,
+        },
+        diagnostics::Diagnostics,
+        macros::context::MacroSolvingContext,
+    };
+    use serde_derive_internals::{ast::Container, Ctxt, Derive};
+    use syn::{DeriveInput, Fields, Item};
+
+    fn export(source: &str) -> Vec<String> {
+        let items = syn::parse_file(source).unwrap().items;
+        let type_solving_context = TypeSolvingContextBuilder::default()
+            .add_default_solvers()
+            .finish();
+        let macro_context = MacroSolvingContext::default();
+        let mut import_context = ImportContext::default();
+        import_context.parse_imported(&items, "crate");
+        import_context.parse_scoped(&items);
+        let exporter = ExporterContext {
+            type_solving_context: &type_solving_context,
+            macro_context: &macro_context,
+            import_context,
+            diagnostics: &Diagnostics::default(),
+            source_file: None,
+        };
+        let ctxt = Ctxt::new();
+        let statements = items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Struct(item) => Some(DeriveInput::from(item)),
+                Item::Enum(item) => Some(DeriveInput::from(item)),
+                _ => None,
+            })
+            .flat_map(|derive_input| {
+                let container = Container::from_ast(&ctxt, &derive_input, Derive::Serialize)
+                    .expect("Failed to read container");
+                exporter
+                    .export_statements_from_container(container)
+                    .expect("Failed to export container")
+                    .inner
+            })
+            .map(|statement| statement.to_string())
+            .collect();
+        ctxt.check().unwrap();
+        statements
+    }
+
+    #[test]
+    fn should_parse_container_attributes() {
+        let input: DeriveInput = syn::parse_str(
+            r#"
+            #[derive(Serialize)]
+            #[typebinder(rename = "Renamed", export = false)]
+            #[typebinder(type = "string")]
+            struct MyStruct {}
+            "#,
+        )
+        .unwrap();
+        let attributes = ContainerAttributes::from_attrs(&input.attrs).unwrap();
+        assert_eq!(
+            attributes,
+            ContainerAttributes {
+                skip: false,
+                export: false,
+                rename: Some("Renamed".to_string()),
+                ts_type: Some("string".to_string()),
+            }
+        );
+        assert!(!attributes.is_exported());
+    }
+
+    #[test]
+    fn should_parse_field_attributes() {
+        let input: DeriveInput = syn::parse_str(
+            r#"
+            struct MyStruct {
+                #[typebinder(optional, type = "Record<string, unknown>")]
+                a: Map,
+                #[serde(default)]
+                #[typebinder(skip)]
+                b: u32,
+            }
+            "#,
+        )
+        .unwrap();
+        let fields = match input.data {
+            syn::Data::Struct(data) => match data.fields {
+                Fields::Named(fields) => fields.named,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let attributes: Vec<FieldAttributes> = fields
+            .iter()
+            .map(|field| FieldAttributes::from_attrs(&field.attrs).unwrap())
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![
+                FieldAttributes {
+                    skip: false,
+                    optional: true,
+                    ts_type: Some("Record<string, unknown>".to_string()),
+                },
+                FieldAttributes {
+                    skip: true,
+                    optional: false,
+                    ts_type: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_unsupported_attributes() {
+        let input: DeriveInput = syn::parse_str(
+            r#"
+            #[typebinder(optional)]
+            struct MyStruct {}
+            "#,
+        )
+        .unwrap();
+        assert!(ContainerAttributes::from_attrs(&input.attrs).is_err());
+
+        let input: DeriveInput = syn::parse_str(
+            r#"
+            #[typebinder(rename = 2)]
+            struct MyStruct {}
+            "#,
+        )
+        .unwrap();
+        assert!(ContainerAttributes::from_attrs(&input.attrs).is_err());
+    }
+
+    #[test]
+    fn should_apply_attributes_when_exporting() {
+        let statements = export(
+            r#"
+            #[typebinder(skip)]
+            struct Skipped {}
+
+            #[typebinder(export = false)]
+            struct HandWritten {}
+
+            #[typebinder(type = "`${number}px`")]
+            struct Pixels(u32);
+
+            #[typebinder(rename = "Person")]
+            struct User {
+                name: String,
+            }
+
+            struct Team {
+                #[typebinder(type = "Record<string, Person>")]
+                members: Vec<u32>,
+                #[typebinder(optional)]
+                leader: User,
+                #[typebinder(skip)]
+                secret: String,
+                hand_written: HandWritten,
+            }
+
+            enum Color {
+                Red,
+                #[typebinder(skip)]
+                Blue,
+            }
+            "#,
+        );
+        assert_eq!(
+            statements,
+            vec![
+                "export type Pixels = `${number}px`;".to_string(),
+                "export interface Person {\n\tname: string\n}".to_string(),
+                "export interface Team {\n\tmembers: Record<string, Person>,\n\tleader?: Person,\n\thand_written: HandWritten\n}".to_string(),
+                "export type Color = \"Red\";".to_string(),
+            ]
+        );
+    }
+}
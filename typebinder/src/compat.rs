@@ -0,0 +1,637 @@
+//! Detection of the breaking changes between two versions of the bindings
+//!
+//! The bindings describe the JSON exchanged with the frontend, each change is thus classified for :
+//! * the consumers, that read values of the type (e.g. a response). A change breaks them when the new values
+//!   don't fit the old type, e.g. a union that gets a new variant, or a field that becomes nullable.
+//! * the producers, that write values of the type (e.g. a request). A change breaks them when the old values
+//!   don't fit the new type, e.g. a new required field.
+//!
+//! Declarations are compared by file and by name, the references to other declarations by name only : a change
+//! to a declaration is reported once, on the declaration itself.
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use ts_json_subset::{
+    export::ExportStatement,
+    parser::{parse_module, Statement},
+    types::{
+        ArrayTypeInner, IntersectionType, LiteralType, ObjectType, PredefinedType, PrimaryType,
+        TsType, TypeBody, TypeMember, TypeReference, UnionType,
+    },
+};
+
+use crate::{
+    error::TsExportError,
+    exporters::semantic::{normalize_type, one_line},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+impl Compatibility {
+    fn from_compatible(compatible: bool) -> Self {
+        if compatible {
+            Compatibility::Compatible
+        } else {
+            Compatibility::Breaking
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A change to a declaration, or to one of its fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompatChange {
+    /// The TS file, relative to the bindings folder
+    pub file: String,
+    pub declaration: String,
+    pub field: Option<String>,
+    pub kind: ChangeKind,
+    /// The old signature, unless added
+    pub old: Option<String>,
+    /// The new signature, unless removed
+    pub new: Option<String>,
+    pub consumers: Compatibility,
+    pub producers: Compatibility,
+}
+
+impl CompatChange {
+    pub fn is_breaking(&self) -> bool {
+        self.consumers == Compatibility::Breaking || self.producers == Compatibility::Breaking
+    }
+}
+
+impl std::fmt::Display for CompatChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = |compatibility: Compatibility| match compatibility {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "BREAKING",
+        };
+        write!(
+            f,
+            "{}: {}",
+            self.file,
+            match &self.field {
+                Some(field) => format!("{}.{}", self.declaration, field),
+                None => self.declaration.clone(),
+            }
+        )?;
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, " changed from `{}` to `{}`", old, new)?,
+            (Some(old), None) => write!(f, " removed, was `{}`", old)?,
+            (None, Some(new)) => write!(f, " added as `{}`", new)?,
+            (None, None) => (),
+        }
+        write!(
+            f,
+            " (consumers: {}, producers: {})",
+            label(self.consumers),
+            label(self.producers)
+        )
+    }
+}
+
+/// The changes between two versions of the bindings
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompatReport {
+    pub changes: Vec<CompatChange>,
+}
+
+impl CompatReport {
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(CompatChange::is_breaking)
+    }
+}
+
+/// Compares the bindings of two folders, as output by the [FileExporter](crate::exporters::file::FileExporter)
+pub fn compare_directories(old: &Path, new: &Path) -> Result<CompatReport, TsExportError> {
+    let old = read_declarations(old)?;
+    let new = read_declarations(new)?;
+    Ok(compare_declarations(&old, &new))
+}
+
+/// A declaration, as the normalized type it stands for
+#[derive(Debug, Clone, PartialEq)]
+struct Declaration {
+    type_params: String,
+    ts_type: TsType,
+}
+
+/// The declarations of each file, by relative path and name
+type Declarations = BTreeMap<(String, String), Declaration>;
+
+fn read_declarations(root: &Path) -> Result<Declarations, TsExportError> {
+    let mut files = Vec::new();
+    list_ts_files(root, &mut files)?;
+
+    let mut declarations = Declarations::new();
+    for path in files {
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = std::fs::read_to_string(&path)?;
+        let statements =
+            parse_module(&contents).map_err(|e| TsExportError::TsParseError(path.clone(), e))?;
+        for statement in statements {
+            if let Statement::Export(export) = statement {
+                for (name, declaration) in export_declarations(&export) {
+                    declarations.insert((relative.clone(), name), declaration);
+                }
+            }
+        }
+    }
+    Ok(declarations)
+}
+
+fn list_ts_files(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), TsExportError> {
+    let mut entries = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            list_ts_files(&path, files)?;
+        } else if path.extension() == Some(OsStr::new("ts")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn export_declarations(export: &ExportStatement) -> Vec<(String, Declaration)> {
+    let type_params = |params: &Option<_>| params.as_ref().map(one_line).unwrap_or_default();
+    match export {
+        ExportStatement::InterfaceDeclaration(declaration) => {
+            let object: TsType = PrimaryType::ObjectType(declaration.obj_type.clone()).into();
+            let ts_type = match &declaration.extends_clause {
+                Some(extends_clause) => IntersectionType {
+                    types: extends_clause
+                        .type_list
+                        .identifiers
+                        .iter()
+                        .map(|reference| PrimaryType::TypeReference(reference.clone()).into())
+                        .chain(Some(object))
+                        .collect(),
+                }
+                .into(),
+                None => object,
+            };
+            vec![(
+                declaration.ident.to_string(),
+                Declaration {
+                    type_params: type_params(&declaration.type_params),
+                    ts_type: merge_objects(normalize_type(&ts_type)),
+                },
+            )]
+        }
+        ExportStatement::TypeAliasDeclaration(declaration) => vec![(
+            declaration.ident.to_string(),
+            Declaration {
+                type_params: type_params(&declaration.type_params),
+                ts_type: merge_objects(normalize_type(&declaration.inner_type)),
+            },
+        )],
+        ExportStatement::ConstEnumDeclaration(declaration) => vec![(
+            declaration.ident.to_string(),
+            Declaration {
+                type_params: String::new(),
+                ts_type: UnionType {
+                    types: declaration
+                        .body
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            PrimaryType::LiteralType(LiteralType::StringLiteral(
+                                variant.value.clone(),
+                            ))
+                            .into()
+                        })
+                        .collect(),
+                }
+                .into(),
+            },
+        )],
+        ExportStatement::ReexportDeclaration(declaration) => declaration
+            .reexports
+            .iter()
+            .map(|clause| {
+                (
+                    clause.export_as.to_string(),
+                    Declaration {
+                        type_params: String::new(),
+                        ts_type: PrimaryType::TypeReference(TypeReference {
+                            name: clause.scope.clone(),
+                            args: None,
+                        })
+                        .into(),
+                    },
+                )
+            })
+            .collect(),
+        // The declarations are compared in the module they come from
+        ExportStatement::ExportAllDeclaration(_) => Vec::new(),
+    }
+}
+
+/// Merges the intersections of object types, e.g. of internally tagged enums, into a single object type
+fn merge_objects(ts_type: TsType) -> TsType {
+    match ts_type {
+        TsType::UnionType(union) => UnionType {
+            types: union.types.into_iter().map(merge_objects).collect(),
+        }
+        .into(),
+        TsType::IntersectionType(intersection) => {
+            let types: Vec<TsType> = intersection.types.into_iter().map(merge_objects).collect();
+            let mut members = Vec::new();
+            for ts_type in types.iter() {
+                match ts_type {
+                    TsType::PrimaryType(PrimaryType::ObjectType(object)) => {
+                        members.extend(object.body.members.iter().cloned())
+                    }
+                    _ => return IntersectionType { types }.into(),
+                }
+            }
+            PrimaryType::ObjectType(ObjectType {
+                body: TypeBody { members },
+            })
+            .into()
+        }
+        TsType::PrimaryType(PrimaryType::ObjectType(object)) => {
+            PrimaryType::ObjectType(ObjectType {
+                body: TypeBody {
+                    members: object
+                        .body
+                        .members
+                        .into_iter()
+                        .map(|member| match member {
+                            TypeMember::PropertySignature(mut property) => {
+                                property.inner_type = merge_objects(property.inner_type);
+                                TypeMember::PropertySignature(property)
+                            }
+                        })
+                        .collect(),
+                },
+            })
+            .into()
+        }
+        ts_type => ts_type,
+    }
+}
+
+fn compare_declarations(old: &Declarations, new: &Declarations) -> CompatReport {
+    let mut changes = Vec::new();
+    let change = |(file, declaration): &(String, String),
+                  field: Option<&str>,
+                  kind: ChangeKind,
+                  old: Option<String>,
+                  new: Option<String>,
+                  consumers: bool,
+                  producers: bool| CompatChange {
+        file: file.clone(),
+        declaration: declaration.clone(),
+        field: field.map(ToString::to_string),
+        kind,
+        old,
+        new,
+        consumers: Compatibility::from_compatible(consumers),
+        producers: Compatibility::from_compatible(producers),
+    };
+
+    for (key, old_declaration) in old.iter() {
+        let new_declaration = match new.get(key) {
+            Some(new_declaration) => new_declaration,
+            None => {
+                // The frontend may still refer to it
+                changes.push(change(
+                    key,
+                    None,
+                    ChangeKind::Removed,
+                    Some(one_line(&old_declaration.ts_type)),
+                    None,
+                    false,
+                    false,
+                ));
+                continue;
+            }
+        };
+        if old_declaration == new_declaration {
+            continue;
+        }
+
+        if old_declaration.type_params != new_declaration.type_params {
+            changes.push(change(
+                key,
+                None,
+                ChangeKind::Changed,
+                Some(format!(
+                    "{} = {}",
+                    old_declaration.type_params,
+                    one_line(&old_declaration.ts_type)
+                )),
+                Some(format!(
+                    "{} = {}",
+                    new_declaration.type_params,
+                    one_line(&new_declaration.ts_type)
+                )),
+                false,
+                false,
+            ));
+            continue;
+        }
+
+        match (
+            object_fields(&old_declaration.ts_type),
+            object_fields(&new_declaration.ts_type),
+        ) {
+            (Some(old_fields), Some(new_fields)) => {
+                for (name, old_field) in old_fields.iter() {
+                    let new_field = new_fields.get(name);
+                    if new_field == Some(old_field) {
+                        continue;
+                    }
+                    let kind = match new_field {
+                        Some(_) => ChangeKind::Changed,
+                        None => ChangeKind::Removed,
+                    };
+                    changes.push(change(
+                        key,
+                        Some(name),
+                        kind,
+                        Some(old_field.signature(name)),
+                        new_field.map(|field| field.signature(name)),
+                        is_field_assignable(new_field, Some(old_field)),
+                        is_field_assignable(Some(old_field), new_field),
+                    ));
+                }
+                for (name, new_field) in new_fields.iter() {
+                    if !old_fields.contains_key(name) {
+                        changes.push(change(
+                            key,
+                            Some(name),
+                            ChangeKind::Added,
+                            None,
+                            Some(new_field.signature(name)),
+                            true,
+                            is_field_assignable(None, Some(new_field)),
+                        ));
+                    }
+                }
+            }
+            _ => changes.push(change(
+                key,
+                None,
+                ChangeKind::Changed,
+                Some(one_line(&old_declaration.ts_type)),
+                Some(one_line(&new_declaration.ts_type)),
+                is_assignable(&new_declaration.ts_type, &old_declaration.ts_type),
+                is_assignable(&old_declaration.ts_type, &new_declaration.ts_type),
+            )),
+        }
+    }
+
+    for (key, new_declaration) in new.iter() {
+        if !old.contains_key(key) {
+            changes.push(change(
+                key,
+                None,
+                ChangeKind::Added,
+                None,
+                Some(one_line(&new_declaration.ts_type)),
+                true,
+                true,
+            ));
+        }
+    }
+
+    CompatReport { changes }
+}
+
+#[derive(Debug, PartialEq)]
+struct Field {
+    optional: bool,
+    ts_type: TsType,
+}
+
+impl Field {
+    fn signature(&self, name: &str) -> String {
+        format!(
+            "{}{}: {}",
+            name,
+            if self.optional { "?" } else { "" },
+            one_line(&self.ts_type)
+        )
+    }
+}
+
+fn object_fields(ts_type: &TsType) -> Option<BTreeMap<String, Field>> {
+    match ts_type {
+        TsType::PrimaryType(PrimaryType::ObjectType(object)) => Some(
+            object
+                .body
+                .members
+                .iter()
+                .map(|member| match member {
+                    TypeMember::PropertySignature(property) => (
+                        property.name.to_string(),
+                        Field {
+                            optional: property.optional,
+                            ts_type: property.inner_type.clone(),
+                        },
+                    ),
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Whether a value of the field `from` (missing when `None`) fits the field `to`
+fn is_field_assignable(from: Option<&Field>, to: Option<&Field>) -> bool {
+    match (from, to) {
+        (_, None) => true,
+        (None, Some(to)) => to.optional,
+        (Some(from), Some(to)) => {
+            (!from.optional || to.optional) && is_assignable(&from.ts_type, &to.ts_type)
+        }
+    }
+}
+
+/// Whether the values of the type `from` fit the type `to`. Both types must be normalized.
+fn is_assignable(from: &TsType, to: &TsType) -> bool {
+    if from == to {
+        return true;
+    }
+    match (from, to) {
+        (
+            _,
+            TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Any))
+            | TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Unknown)),
+        ) => true,
+        (
+            TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Any))
+            | TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Never)),
+            _,
+        ) => true,
+        (TsType::UnionType(union), _) => union.types.iter().all(|from| is_assignable(from, to)),
+        (_, TsType::UnionType(union)) => union.types.iter().any(|to| is_assignable(from, to)),
+        (_, TsType::IntersectionType(intersection)) => {
+            intersection.types.iter().all(|to| is_assignable(from, to))
+        }
+        (TsType::IntersectionType(intersection), _) => intersection
+            .types
+            .iter()
+            .any(|from| is_assignable(from, to)),
+        (TsType::PrimaryType(from), TsType::PrimaryType(to)) => is_primary_assignable(from, to),
+        _ => false,
+    }
+}
+
+fn is_primary_assignable(from: &PrimaryType, to: &PrimaryType) -> bool {
+    match (from, to) {
+        (
+            PrimaryType::LiteralType(LiteralType::StringLiteral(_)),
+            PrimaryType::Predefined(PredefinedType::String),
+        )
+        | (
+            PrimaryType::LiteralType(LiteralType::NumericLiteral(_)),
+            PrimaryType::Predefined(PredefinedType::Number),
+        )
+        | (
+            PrimaryType::LiteralType(LiteralType::BooleanLiteral(_)),
+            PrimaryType::Predefined(PredefinedType::Boolean),
+        ) => true,
+        (PrimaryType::ArrayType(from), PrimaryType::ArrayType(to)) => {
+            is_assignable(&array_inner(&from.inner_type), &array_inner(&to.inner_type))
+        }
+        (PrimaryType::TupleType(from), PrimaryType::TupleType(to)) => {
+            from.inner_types.len() == to.inner_types.len()
+                && from
+                    .inner_types
+                    .iter()
+                    .zip(to.inner_types.iter())
+                    .all(|(from, to)| is_assignable(from, to))
+        }
+        (PrimaryType::TupleType(from), PrimaryType::ArrayType(to)) => {
+            let to = array_inner(&to.inner_type);
+            from.inner_types.iter().all(|from| is_assignable(from, &to))
+        }
+        (PrimaryType::ObjectType(_), PrimaryType::ObjectType(_)) => {
+            let from = object_fields(&from.clone().into()).unwrap_or_default();
+            let to = object_fields(&to.clone().into()).unwrap_or_default();
+            to.iter()
+                .all(|(name, to)| is_field_assignable(from.get(name), Some(to)))
+        }
+        // The arguments of generics are assumed to be covariant, as are `Record`, `Partial` and the like
+        (PrimaryType::TypeReference(from), PrimaryType::TypeReference(to)) => {
+            let from_args = from.args.as_ref().map(|args| args.types.as_slice());
+            let to_args = to.args.as_ref().map(|args| args.types.as_slice());
+            from.name == to.name
+                && from_args.map(<[TsType]>::len) == to_args.map(<[TsType]>::len)
+                && from_args
+                    .unwrap_or_default()
+                    .iter()
+                    .zip(to_args.unwrap_or_default())
+                    .all(|(from, to)| is_assignable(from, to))
+        }
+        _ => false,
+    }
+}
+
+fn array_inner(inner: &ArrayTypeInner) -> TsType {
+    match inner {
+        ArrayTypeInner::Primary(primary) => primary.clone().into(),
+        ArrayTypeInner::Parenthesized(parenthesized) => normalize_type(&parenthesized.inner),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{compare_declarations, export_declarations, ChangeKind, Declarations};
+    use ts_json_subset::parser::{parse_module, Statement};
+
+    fn declarations(source: &str) -> Declarations {
+        parse_module(source)
+            .unwrap()
+            .into_iter()
+            .filter_map(|statement| match statement {
+                Statement::Export(export) => Some(export),
+                Statement::Import(_) => None,
+            })
+            .flat_map(|export| export_declarations(&export))
+            .map(|(name, declaration)| (("index.ts".to_string(), name), declaration))
+            .collect()
+    }
+
+    #[test]
+    fn should_classify_the_changes() {
+        let old = declarations(
+            r#"
+            export interface User { id: number, name: string, age: number, email?: string }
+            export type Status = "Active" | "Banned";
+            export type Request = { kind: "A" } | { kind: "B" };
+            export type Removed = string;
+            "#,
+        );
+        let new = declarations(
+            r#"
+            export interface User { id: number, name: string | null, email?: string, role: "Admin" | "User" }
+            export type Status = "Active" | "Banned" | "Deleted";
+            export type Request = { kind: "A" };
+            export type Added = number;
+            "#,
+        );
+        let report = compare_declarations(&old, &new);
+        let changes: Vec<String> = report.changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "index.ts: Removed removed, was `string` (consumers: BREAKING, producers: BREAKING)",
+                "index.ts: Request changed from `{ kind: \"A\" } | { kind: \"B\" }` to `{ kind: \"A\" }` (consumers: compatible, producers: BREAKING)",
+                "index.ts: Status changed from `\"Active\" | \"Banned\"` to `\"Active\" | \"Banned\" | \"Deleted\"` (consumers: BREAKING, producers: compatible)",
+                "index.ts: User.age removed, was `age: number` (consumers: BREAKING, producers: compatible)",
+                "index.ts: User.name changed from `name: string` to `name: string | null` (consumers: BREAKING, producers: compatible)",
+                "index.ts: User.role added as `role: \"Admin\" | \"User\"` (consumers: compatible, producers: BREAKING)",
+                "index.ts: Added added as `number` (consumers: compatible, producers: compatible)",
+            ]
+        );
+        assert!(report.changes[6].kind == ChangeKind::Added);
+        assert!(report.is_breaking());
+    }
+
+    #[test]
+    fn should_accept_compatible_changes() {
+        let old = declarations(
+            r#"
+            export interface Event { type: "Created", id: number }
+            export type Tagged = ({ type: "A" } & { value: number });
+            "#,
+        );
+        let new = declarations(
+            r#"
+            export interface Event { id: number; type: 'Created'; comment?: string; }
+            export type Tagged = { type: "A", value: number };
+            "#,
+        );
+        let report = compare_declarations(&old, &new);
+        assert!(!report.is_breaking(), "{:?}", report);
+    }
+}
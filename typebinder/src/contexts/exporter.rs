@@ -1,7 +1,12 @@
-use std::str::FromStr;
+use std::{path::Path, str::FromStr};
 
-use super::{import::ImportContext, type_solving::TypeSolvingContext};
+use super::{
+    import::{all_objects, ImportContext, UnitContent},
+    type_solving::TypeSolvingContext,
+};
 use crate::{
+    attributes::{ContainerAttributes, FieldAttributes, VariantAttributes},
+    diagnostics::{Diagnostics, Warning, WarningKind},
     error::TsExportError,
     macros::{context::MacroSolvingContext, MacroInfo},
     type_solving::{
@@ -9,21 +14,23 @@ use crate::{
         type_info::TypeInfo,
     },
     type_solving::{result::Solved, ImportEntry},
+    utils::rename::display_type,
 };
+use proc_macro2::Span;
 use serde_derive_internals::{
     ast::{Container, Data, Field, Style, Variant},
     attr::TagType,
 };
-use syn::{GenericParam, Generics, ItemType};
+use syn::{spanned::Spanned, GenericParam, Generics, ItemType};
 use ts_json_subset::declarations::interface::{InterfaceExtendsClause, InterfaceTypeList};
 use ts_json_subset::{
     declarations::{interface::InterfaceDeclaration, type_alias::TypeAliasDeclaration},
     export::ExportStatement,
     ident::{IdentError, StrictTSIdent, TSIdent},
     types::{
-        IntersectionType, LiteralType, ObjectType, ParenthesizedType, PrimaryType, PropertyName,
-        PropertySignature, TsType, TupleType, TypeBody, TypeMember, TypeParameter, TypeParameters,
-        UnionType,
+        IntersectionType, LiteralType, ObjectType, ParenthesizedType, PredefinedType, PrimaryType,
+        PropertyName, PropertySignature, TsType, TupleType, TypeArguments, TypeBody, TypeMember,
+        TypeParameter, TypeParameters, TypeReference, UnionType,
     },
 };
 
@@ -35,6 +42,10 @@ pub struct ExporterContext<'a> {
     pub macro_context: &'a MacroSolvingContext,
     /// A context that contains all the imports
     pub import_context: ImportContext,
+    /// Where warnings are reported
+    pub diagnostics: &'a Diagnostics,
+    /// The Rust file of the module being exported, when known
+    pub source_file: Option<&'a Path>,
 }
 
 pub fn apply_generic_constraints(
@@ -74,6 +85,74 @@ fn extract_type_parameters(generics: &Generics) -> Result<Option<TypeParameters>
 }
 
 impl ExporterContext<'_> {
+    /// Reports a warning located in the current module
+    pub fn warn(&self, kind: WarningKind, span: Span) {
+        self.diagnostics
+            .warn(Warning::new(kind, self.source_file, span));
+    }
+
+    /// Whether values of a solved type are always JSON objects.
+    ///
+    /// Gives back `None` when that depends on declarations of other modules, which are not known here.
+    /// The type parameters are assumed to be objects.
+    pub fn is_object_type(&self, generics: &Generics, ts_type: &TsType) -> Option<bool> {
+        match ts_type {
+            TsType::PrimaryType(primary) => match primary {
+                PrimaryType::ObjectType(_) | PrimaryType::Verbatim(_) => Some(true),
+                PrimaryType::TypeReference(reference) => {
+                    let name = reference.name.to_string();
+                    if generics.type_params().any(|param| param.ident == name) {
+                        Some(true)
+                    } else {
+                        self.import_context.is_object_declaration(&name)
+                    }
+                }
+                PrimaryType::ArrayType(_)
+                | PrimaryType::TupleType(_)
+                | PrimaryType::Predefined(_)
+                | PrimaryType::LiteralType(_) => Some(false),
+            },
+            TsType::ParenthesizedType(parenthesized) => {
+                self.is_object_type(generics, &parenthesized.inner)
+            }
+            TsType::UnionType(union) => all_objects(
+                union
+                    .types
+                    .iter()
+                    .map(|ts_type| self.is_object_type(generics, ts_type)),
+            ),
+            TsType::IntersectionType(intersection) => all_objects(
+                intersection
+                    .types
+                    .iter()
+                    .map(|ts_type| self.is_object_type(generics, ts_type)),
+            ),
+        }
+    }
+
+    /// What a type serializes to that is not an object, see [ImportContext::unit_content](crate::contexts::import::ImportContext::unit_content).
+    ///
+    /// Only the declarations of the current module are known, the other types are given as objects.
+    pub fn unit_content(&self, generics: &Generics, ts_type: &TsType) -> UnitContent {
+        match ts_type {
+            TsType::PrimaryType(PrimaryType::TypeReference(reference))
+                if !generics
+                    .type_params()
+                    .any(|param| param.ident == reference.name.to_string()) =>
+            {
+                self.import_context
+                    .unit_content(&reference.name.to_string())
+            }
+            TsType::ParenthesizedType(parenthesized) => {
+                self.unit_content(generics, &parenthesized.inner)
+            }
+            _ => UnitContent {
+                object: true,
+                ..Default::default()
+            },
+        }
+    }
+
     pub fn solve_type(&self, solver_info: &TypeInfo) -> Result<Solved<TsType>, TsExportError> {
         for solver in self.type_solving_context.solvers() {
             match solver.as_ref().solve_as_type(self, solver_info) {
@@ -82,7 +161,7 @@ impl ExporterContext<'_> {
                 SolverResult::Error(inner) => return Err(inner),
             }
         }
-        Err(TsExportError::UnsolvedType(solver_info.ty.clone()))
+        Err(TsExportError::unsolved_type(solver_info.ty))
     }
 
     pub fn solve_member(
@@ -96,7 +175,56 @@ impl ExporterContext<'_> {
                 SolverResult::Error(inner) => return Err(inner),
             }
         }
-        Err(TsExportError::UnsolvedField(solver_info.field.clone()))
+        Err(TsExportError::unsolved_field(solver_info.field))
+    }
+
+    /// Solves a named field, taking its `#[typebinder(...)]` attributes into account.
+    ///
+    /// Returns `None` if the field is not serialized.
+    pub fn solve_field_member(
+        &self,
+        generics: &Generics,
+        field: &Field,
+    ) -> Result<Option<Solved<TypeMember>>, TsExportError> {
+        let attributes = FieldAttributes::from_attrs(&field.original.attrs)?;
+        if attributes.skip || field.attrs.skip_serializing() {
+            return Ok(None);
+        }
+        let mut solved = match attributes.ts_type {
+            Some(ts_type) => Solved::new(TypeMember::PropertySignature(PropertySignature {
+                name: PropertyName::from(field.attrs.name().serialize_name()),
+                inner_type: TsType::PrimaryType(PrimaryType::Verbatim(ts_type)),
+                optional: false,
+            })),
+            None => self
+                .solve_member(&MemberInfo::from_generics_and_field(generics, field))
+                .map_err(|e| e.with_location(None, field.ty.span()))?,
+        };
+        if attributes.optional {
+            let TypeMember::PropertySignature(property) = &mut solved.inner;
+            property.optional = true;
+        }
+        Ok(Some(solved))
+    }
+
+    /// Solves the type of an unnamed field, taking its `#[typebinder(type = "...")]` attribute into account
+    pub fn solve_field_type(
+        &self,
+        generics: &Generics,
+        field: &Field,
+    ) -> Result<Solved<TsType>, TsExportError> {
+        let attributes = FieldAttributes::from_attrs(&field.original.attrs)?;
+        match attributes.ts_type {
+            Some(ts_type) => Ok(Solved::new(TsType::PrimaryType(PrimaryType::Verbatim(
+                ts_type,
+            )))),
+            None => self
+                .solve_type(&TypeInfo {
+                    generics,
+                    ty: field.ty,
+                })
+                .map_err(|e| e.with_location(None, field.ty.span())),
+        }
     }
 
     pub fn export_statements_from_macro(
@@ -118,18 +246,32 @@ impl ExporterContext<'_> {
         &self,
         container: Container,
     ) -> Result<Solved<Vec<ExportStatement>>, TsExportError> {
-        let name = container.ident.to_string();
+        let attributes = ContainerAttributes::from_attrs(&container.original.attrs)?;
+        if !attributes.is_exported() {
+            return Ok(Solved::new(vec![]));
+        }
+        let name = attributes
+            .rename
+            .unwrap_or_else(|| container.ident.to_string());
+        if let Some(ts_type) = attributes.ts_type {
+            return Ok(Solved::new(vec![export_opaque_type(
+                name,
+                container.generics,
+                ts_type,
+            )?]));
+        }
         match container.data {
-            Data::Enum(variants) => match container.attrs.tag() {
-                TagType::External => self.export_enum_external(name, container.generics, variants),
-                TagType::Internal { tag } => {
-                    self.export_enum_internal(name, container.generics, variants, tag)
-                }
-                TagType::Adjacent { tag, content } => {
-                    self.export_enum_adjacent(name, container.generics, variants, tag, content)
-                }
-                TagType::None => self.export_enum_untagged(name, container.generics, variants),
-            },
+            Data::Enum(variants) => {
+                let variants = variants
+                    .into_iter()
+                    .map(|variant| {
+                        VariantAttributes::from_attrs(&variant.original.attrs)
+                            .map(|attributes| (!attributes.skip).then_some(variant))
+                    })
+                    .filter_map(Result::transpose)
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.export_enum(name, container.generics, variants, container.attrs.tag())
+            }
             Data::Struct(style, fields) => match style {
                 Style::Unit => Ok(Solved::new(vec![])), // Unit structs are a no-op because they dont have a TS representation
                 Style::Newtype => self.export_struct_newtype(name, container.generics, fields),
@@ -139,11 +281,42 @@ impl ExporterContext<'_> {
         }
     }
 
+    fn export_enum(
+        &self,
+        name: String,
+        generics: &Generics,
+        variants: Vec<Variant>,
+        tag: &TagType,
+    ) -> Result<Solved<Vec<ExportStatement>>, TsExportError> {
+        match tag {
+            TagType::External => self.export_enum_external(name, generics, variants),
+            TagType::Internal { tag } => self.export_enum_internal(name, generics, variants, tag),
+            TagType::Adjacent { tag, content } => {
+                self.export_enum_adjacent(name, generics, variants, tag, content)
+            }
+            TagType::None => self.export_enum_untagged(name, generics, variants),
+        }
+    }
+
     pub fn export_statements_from_type_alias(
         &self,
         type_alias: ItemType,
     ) -> Result<Solved<Vec<ExportStatement>>, TsExportError> {
-        let ident = StrictTSIdent::from_str(&type_alias.ident.to_string())?;
+        let attributes = ContainerAttributes::from_attrs(&type_alias.attrs)?;
+        if !attributes.is_exported() {
+            return Ok(Solved::new(vec![]));
+        }
+        let name = attributes
+            .rename
+            .unwrap_or_else(|| type_alias.ident.to_string());
+        if let Some(ts_type) = attributes.ts_type {
+            return Ok(Solved::new(vec![export_opaque_type(
+                name,
+                &type_alias.generics,
+                ts_type,
+            )?]));
+        }
+        let ident = StrictTSIdent::from_str(&name)?;
         let solver_info = TypeInfo {
             generics: &type_alias.generics,
             ty: type_alias.ty.as_ref(),
@@ -187,17 +360,15 @@ impl ExporterContext<'_> {
         } = fields
             .into_iter()
             .try_fold(Accumulator::default(), |mut acc, field| {
-                if field.attrs.skip_serializing() {
+                let Some(mut solved) = self.solve_field_member(generics, &field)? else {
                     return Ok(acc);
-                }
+                };
                 let Accumulator {
                     members,
                     extends_clause,
                     imports,
                     constraints,
                 } = &mut acc;
-                let solver_info = MemberInfo::from_generics_and_field(generics, &field);
-                let mut solved = self.solve_member(&solver_info)?;
                 imports.append(&mut solved.import_entries);
                 constraints.merge(solved.generic_constraints);
                 if field.attrs.flatten() {
@@ -248,12 +419,7 @@ impl ExporterContext<'_> {
         generics: &Generics,
         fields: Vec<Field>,
     ) -> Result<Solved<Vec<ExportStatement>>, TsExportError> {
-        let field = &fields[0];
-        let solver_info = TypeInfo {
-            generics,
-            ty: field.ty,
-        };
-        let solved = self.solve_type(&solver_info)?;
+        let solved = self.solve_field_type(generics, &fields[0])?;
         let mut type_params = extract_type_parameters(generics)?;
         if let Some(params) = type_params.as_mut() {
             apply_generic_constraints(params, &solved.generic_constraints);
@@ -278,14 +444,8 @@ impl ExporterContext<'_> {
         let mut imports: Vec<ImportEntry> = Vec::new();
         let mut constraints = GenericConstraints::default();
         let inner_types: Vec<TsType> = fields
-            .into_iter()
-            .map(|field| {
-                let solver_info = TypeInfo {
-                    generics,
-                    ty: field.ty,
-                };
-                self.solve_type(&solver_info)
-            })
+            .iter()
+            .map(|field| self.solve_field_type(generics, field))
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .map(|mut solved| {
@@ -327,13 +487,14 @@ impl ExporterContext<'_> {
             .map(|variant| {
                 // See serde's TaggedSerializer in order to understand the big match here, namely :
                 // * Tuples are not supported
-                // * Unit structs are no ops
+                // * Unit structs are no ops, as are the unit structs in newtypes
+                // * The unit variants in newtypes are given as `"Variant": null`
                 // * Structs are to be exported field by field in a TS ObjectType
                 // * Newtypes have most types unsupported, but does support :
                 //  * Exporting a map
                 //  * Exporting a struct
-                //  * (References are supported because they are assumed not to deserialize into a
-                //     supported type here)
+                //  * (References to a declaration of the current module are checked, other references
+                //     are assumed to deserialize into a supported type, with a warning)
                 let variant_type = match (variant.style, variant.fields.as_slice()) {
                     (Style::Tuple, _) => {
                         return Err(TsExportError::InvalidSerdeRepresentation(format!(
@@ -342,41 +503,46 @@ impl ExporterContext<'_> {
                         )));
                     }
                     (Style::Newtype, fields) => {
-                        let solver_info = MemberInfo::from_generics_and_field(generics, &fields[0]);
-                        let mut solved = self.solve_member(&solver_info)?;
+                        let mut solved = self.solve_field_type(generics, &fields[0])?;
                         imports.append(&mut solved.import_entries);
                         constraints.merge(solved.generic_constraints);
-                        let TypeMember::PropertySignature(property) = solved.inner;
-                        match property.inner_type {
-                            TsType::PrimaryType(ref primary) => match primary {
-                                PrimaryType::ObjectType(_) => Some(property.inner_type),
-                                PrimaryType::TypeReference(_) => Some(property.inner_type),
-                                PrimaryType::ArrayType(_)
-                                | PrimaryType::TupleType(_)
-                                | PrimaryType::Predefined(_)
-                                | PrimaryType::LiteralType(_) => {
-                                    return Err(TsExportError::InvalidSerdeRepresentation(
-                                        format!("{}::{}", ident, variant.ident),
+                        match self.is_object_type(generics, &solved.inner) {
+                            Some(true) => {
+                                let content = self.unit_content(generics, &solved.inner);
+                                if !content.is_object() {
+                                    return Ok(tagged_unit_content(
+                                        tag,
+                                        &variant.attrs.name().serialize_name(),
+                                        solved.inner,
+                                        content,
                                     ));
                                 }
-                            },
-                            _ => Some(property.inner_type),
+                            }
+                            Some(false) => {
+                                return Err(TsExportError::InvalidSerdeRepresentation(format!(
+                                    "{}::{}",
+                                    ident, variant.ident
+                                )));
+                            }
+                            None => self.warn(
+                                WarningKind::AssumedObjectVariant {
+                                    ident: ident.clone(),
+                                    variant: variant.ident.to_string(),
+                                    ts_type: display_type(&solved.inner),
+                                },
+                                variant.original.ident.span(),
+                            ),
                         }
+                        Some(solved.inner)
                     }
                     (Style::Unit, []) => None,
                     (Style::Struct, fields) => {
                         let members: Vec<TypeMember> = fields
                             .iter()
-                            .filter_map(|field| {
-                                if field.attrs.skip_serializing() {
-                                    return None;
-                                }
-                                let solver_info =
-                                    MemberInfo::from_generics_and_field(generics, field);
-                                Some(self.solve_member(&solver_info))
-                            })
+                            .map(|field| self.solve_field_member(generics, field))
                             .collect::<Result<Vec<_>, _>>()?
                             .into_iter()
+                            .flatten()
                             .map(|mut solved| {
                                 imports.append(&mut solved.import_entries);
                                 constraints.merge(solved.generic_constraints);
@@ -392,15 +558,7 @@ impl ExporterContext<'_> {
 
                 let tag_type = TsType::PrimaryType(PrimaryType::ObjectType(ObjectType {
                     body: TypeBody {
-                        members: vec![TypeMember::PropertySignature(PropertySignature {
-                            name: PropertyName::from(tag.to_string()),
-                            inner_type: TsType::PrimaryType(PrimaryType::LiteralType(
-                                LiteralType::StringLiteral(
-                                    variant.attrs.name().serialize_name().into(),
-                                ),
-                            )),
-                            optional: false,
-                        })],
+                        members: vec![tag_member(tag, &variant.attrs.name().serialize_name())],
                     },
                 }));
                 let inter = TsType::IntersectionType(IntersectionType {
@@ -437,31 +595,24 @@ impl ExporterContext<'_> {
     ) -> Result<Solved<Vec<ExportStatement>>, TsExportError> {
         let mut imports: Vec<ImportEntry> = Vec::new();
         let mut constraints = GenericConstraints::default();
+        let variant_names: Vec<(String, Span)> = variants
+            .iter()
+            .map(|variant| (variant.ident.to_string(), variant.original.ident.span()))
+            .collect();
         let types: Vec<TsType> = variants
             .into_iter()
             .map(|variant| match variant.style {
                 Style::Unit => Ok(Solved::new(TsType::PrimaryType(PrimaryType::Predefined(
                     ts_json_subset::types::PredefinedType::Null,
                 )))),
-                Style::Newtype => {
-                    let field = &variant.fields[0];
-                    self.solve_type(&TypeInfo {
-                        generics,
-                        ty: field.ty,
-                    })
-                }
+                Style::Newtype => self.solve_field_type(generics, &variant.fields[0]),
                 Style::Tuple => {
                     let mut imports = Vec::new();
                     let mut constraints = GenericConstraints::default();
                     let inner_types = variant
                         .fields
-                        .into_iter()
-                        .map(|field| {
-                            self.solve_type(&TypeInfo {
-                                generics,
-                                ty: field.ty,
-                            })
-                        })
+                        .iter()
+                        .map(|field| self.solve_field_type(generics, field))
                         .collect::<Result<Vec<_>, _>>()?
                         .into_iter()
                         .map(|mut solved| {
@@ -484,16 +635,10 @@ impl ExporterContext<'_> {
                     let members: Vec<TypeMember> = variant
                         .fields
                         .into_iter()
-                        .filter_map(|field| {
-                            if field.attrs.skip_serializing() {
-                                return None;
-                            }
-                            Some(self.solve_member(&MemberInfo::from_generics_and_field(
-                                generics, &field,
-                            )))
-                        })
+                        .map(|field| self.solve_field_member(generics, &field))
                         .collect::<Result<Vec<_>, _>>()?
                         .into_iter()
+                        .flatten()
                         .map(|mut solved| {
                             imports.append(&mut solved.import_entries);
                             constraints.merge(solved.generic_constraints);
@@ -517,6 +662,7 @@ impl ExporterContext<'_> {
                 solved.inner
             })
             .collect();
+        self.warn_indistinguishable_variants(&ident, &variant_names, &types);
         let inner_type = TsType::UnionType(UnionType { types });
         let mut type_params = extract_type_parameters(generics)?;
         if let Some(params) = type_params.as_mut() {
@@ -535,6 +681,32 @@ impl ExporterContext<'_> {
         })
     }
 
+    /// Warns about the variants of an untagged enum that have the same TS type as a previous variant,
+    /// as the TS side can't tell them apart
+    fn warn_indistinguishable_variants(
+        &self,
+        ident: &str,
+        variant_names: &[(String, Span)],
+        types: &[TsType],
+    ) {
+        for (index, (name, span)) in variant_names.iter().enumerate() {
+            if let Some(previous) = types[..index]
+                .iter()
+                .position(|ts_type| *ts_type == types[index])
+            {
+                self.warn(
+                    WarningKind::IndistinguishableVariants {
+                        ident: ident.to_string(),
+                        first: variant_names[previous].0.clone(),
+                        second: name.clone(),
+                        ts_type: display_type(&types[index]),
+                    },
+                    *span,
+                );
+            }
+        }
+    }
+
     fn export_enum_adjacent(
         &self,
         ident: String,
@@ -550,13 +722,11 @@ impl ExporterContext<'_> {
             .map(|variant| {
                 let members: Vec<TypeMember> = variant
                     .fields
-                    .into_iter()
-                    .map(|field| {
-                        let solver_info = MemberInfo::from_generics_and_field(generics, &field);
-                        self.solve_member(&solver_info)
-                    })
+                    .iter()
+                    .map(|field| self.solve_field_member(generics, field))
                     .collect::<Result<Vec<_>, _>>()?
                     .into_iter()
+                    .flatten()
                     .map(|mut solved| {
                         imports.append(&mut solved.import_entries);
                         constraints.merge(solved.generic_constraints);
@@ -638,10 +808,7 @@ impl ExporterContext<'_> {
                         LiteralType::StringLiteral(variant_name.into()),
                     )),
                     (Style::Newtype, [field]) => {
-                        let mut solved = self.solve_type(&TypeInfo {
-                            generics,
-                            ty: field.ty,
-                        })?;
+                        let mut solved = self.solve_field_type(generics, field)?;
                         imports.append(&mut solved.import_entries);
                         constraints.merge(solved.generic_constraints);
 
@@ -658,16 +825,10 @@ impl ExporterContext<'_> {
                     (Style::Struct, fields) => {
                         let members: Vec<TypeMember> = fields
                             .iter()
-                            .filter_map(|field| {
-                                if field.attrs.skip_serializing() {
-                                    return None;
-                                }
-                                Some(self.solve_member(&MemberInfo::from_generics_and_field(
-                                    generics, field,
-                                )))
-                            })
+                            .map(|field| self.solve_field_member(generics, field))
                             .collect::<Result<Vec<_>, _>>()?
                             .into_iter()
+                            .flatten()
                             .map(|mut solved| {
                                 imports.append(&mut solved.import_entries);
                                 constraints.merge(solved.generic_constraints);
@@ -690,12 +851,7 @@ impl ExporterContext<'_> {
                     (Style::Tuple, fields) => {
                         let inner_types: Vec<TsType> = fields
                             .iter()
-                            .map(|field| {
-                                self.solve_type(&TypeInfo {
-                                    generics,
-                                    ty: field.ty,
-                                })
-                            })
+                            .map(|field| self.solve_field_type(generics, field))
                             .collect::<Result<Vec<_>, _>>()?
                             .into_iter()
                             .map(|mut solved| {
@@ -740,6 +896,20 @@ impl ExporterContext<'_> {
     }
 }
 
+/// Declares a type whose TS definition is given by `#[typebinder(type = "...")]`
+fn export_opaque_type(
+    name: String,
+    generics: &Generics,
+    ts_type: String,
+) -> Result<ExportStatement, TsExportError> {
+    Ok(TypeAliasDeclaration {
+        ident: StrictTSIdent::from_str(&name)?,
+        inner_type: TsType::PrimaryType(PrimaryType::Verbatim(ts_type)),
+        type_params: extract_type_parameters(generics)?,
+    }
+    .into())
+}
+
 fn extract_inner_types(members: Vec<TypeMember>) -> Vec<TsType> {
     members
         .into_iter()
@@ -752,3 +922,65 @@ fn extract_inner_types(members: Vec<TypeMember>) -> Vec<TsType> {
         })
         .collect()
 }
+
+/// The tag of a variant of an internally tagged enum, e.g. `"kind": "Circle"`
+fn tag_member(tag: &str, variant: &str) -> TypeMember {
+    TypeMember::PropertySignature(PropertySignature {
+        name: PropertyName::from(tag.to_string()),
+        inner_type: TsType::PrimaryType(PrimaryType::LiteralType(LiteralType::StringLiteral(
+            variant.to_string().into(),
+        ))),
+        optional: false,
+    })
+}
+
+/// A newtype variant of an internally tagged enum whose type serializes to a unit struct or to unit variants :
+/// the tag alone for a unit struct, and the tag with `"Variant": null` for each unit variant.
+///
+/// The objects the type also serializes to, if any, are given next to the tag without the unit values,
+/// e.g. `{ "kind": "A" } & Exclude<Inner, string>`.
+fn tagged_unit_content(tag: &str, variant: &str, ts_type: TsType, content: UnitContent) -> TsType {
+    let object = |members: Vec<TypeMember>| {
+        TsType::PrimaryType(PrimaryType::ObjectType(ObjectType {
+            body: TypeBody { members },
+        }))
+    };
+    let unit_variants = content.unit_variants.iter().map(|unit_variant| {
+        object(vec![
+            tag_member(tag, variant),
+            TypeMember::PropertySignature(PropertySignature {
+                name: PropertyName::from(unit_variant.clone()),
+                inner_type: TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Null)),
+                optional: false,
+            }),
+        ])
+    });
+    let unit = Some(object(vec![tag_member(tag, variant)])).filter(|_| content.unit);
+    let excluded: Vec<TsType> = Some(PredefinedType::String)
+        .filter(|_| !content.unit_variants.is_empty())
+        .into_iter()
+        .chain(Some(PredefinedType::Null).filter(|_| content.unit))
+        .map(|predefined| TsType::PrimaryType(PrimaryType::Predefined(predefined)))
+        .collect();
+    let objects = Some(TsType::IntersectionType(IntersectionType {
+        types: vec![
+            object(vec![tag_member(tag, variant)]),
+            TsType::PrimaryType(PrimaryType::TypeReference(TypeReference {
+                name: StrictTSIdent::from_str("Exclude").expect("A valid ident"),
+                args: Some(TypeArguments {
+                    types: vec![ts_type, TsType::UnionType(UnionType { types: excluded })],
+                }),
+            })),
+        ],
+    }))
+    .filter(|_| content.object);
+
+    let mut types: Vec<TsType> = unit_variants.chain(unit).chain(objects).collect();
+    let inner = match types.len() {
+        1 => types.remove(0),
+        _ => TsType::UnionType(UnionType { types }),
+    };
+    TsType::ParenthesizedType(ParenthesizedType {
+        inner: Box::new(inner),
+    })
+}
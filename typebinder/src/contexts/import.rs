@@ -1,8 +1,13 @@
+use crate::attributes::{ContainerAttributes, DeclarationAttributes};
 use proc_macro2::Span;
-use std::collections::HashMap;
+use serde_derive_internals::{
+    ast::{Container, Data},
+    Ctxt, Derive,
+};
+use std::collections::{HashMap, HashSet};
 use syn::{
-    punctuated::Punctuated, token::Colon2, Ident, Item, Path, PathArguments, PathSegment, TypePath,
-    UseTree,
+    punctuated::Punctuated, token::Colon2, Attribute, DeriveInput, Fields, GenericArgument, Ident,
+    Item, ItemEnum, Meta, NestedMeta, Path, PathArguments, PathSegment, Type, TypePath, UseTree,
 };
 
 /// All imports of interest from Rust's prelude (not importing Traits, functions and macros)
@@ -16,6 +21,11 @@ const PRELUDE: &str = r#"
 pub struct ImportContext {
     imported: ImportList,
     scoped: ImportList,
+    /// Declarations of the current module that are renamed with `#[typebinder(rename = "...")]`
+    renamed: HashMap<Ident, Ident>,
+    declaration_attributes: DeclarationAttributes,
+    /// How the declarations of the current module serialize
+    shapes: HashMap<Ident, DeclarationShape>,
     // TODO: Maybe remove, this should probably be static ?
     prelude: ImportList,
 }
@@ -28,9 +38,323 @@ impl ImportContext {
 
     pub fn parse_scoped(&mut self, items: &[Item]) {
         // TODO: Append current_path to all declarations
-        let import_list = parse_declarations(items);
+        let declaration_attributes = parse_declaration_attributes(items);
+        let mut import_list = parse_declarations(items);
+        // Skipped declarations can't be referred to
+        import_list
+            .0
+            .retain(|ident, _| !declaration_attributes.skipped.contains(&ident.to_string()));
+        let renamed = parse_renames(items);
+        renamed
+            .values()
+            .for_each(|rename| import_list.add_declaration(rename.clone()));
         self.scoped = import_list;
+        self.renamed = renamed;
+        self.declaration_attributes = declaration_attributes;
+        self.shapes = parse_shapes(items);
+    }
+
+    /// What the `#[typebinder(...)]` attributes of the declarations of the current module change for the other modules
+    pub fn declaration_attributes(&self) -> &DeclarationAttributes {
+        &self.declaration_attributes
+    }
+
+    /// Whether a type refers to a declaration of the current module that is skipped with `#[typebinder(skip)]`
+    pub fn is_skipped(&self, ident: &Ident) -> bool {
+        !self.imported.contains_key(ident)
+            && self
+                .declaration_attributes
+                .skipped
+                .iter()
+                .any(|skipped| ident == skipped)
+    }
+
+    /// Whether a declaration of the current module serializes to a JSON object, given its TS name.
+    ///
+    /// The unit structs and the unit variants count as objects, an internally tagged enum giving them as
+    /// `{ "tag": ... }` and `{ "tag": ..., "Variant": null }`, see [ImportContext::unit_content].
+    /// Gives back `None` when it is not declared in the current module, or when that depends on declarations of other modules.
+    pub fn is_object_declaration(&self, name: &str) -> Option<bool> {
+        let mut visited = HashSet::new();
+        self.is_object_declaration_inner(name, &mut visited)
+    }
+
+    fn is_object_declaration_inner(
+        &self,
+        name: &str,
+        visited: &mut HashSet<String>,
+    ) -> Option<bool> {
+        if !visited.insert(name.to_string()) {
+            return Some(true);
+        }
+        let (_, shape) = self
+            .shapes
+            .iter()
+            .find(|(ident, _)| *self.renamed.get(ident).unwrap_or(ident) == name)?;
+        self.is_object_shape(shape, visited)
+    }
+
+    fn is_object_shape(
+        &self,
+        shape: &DeclarationShape,
+        visited: &mut HashSet<String>,
+    ) -> Option<bool> {
+        match shape {
+            DeclarationShape::Object | DeclarationShape::Null | DeclarationShape::String(_) => {
+                Some(true)
+            }
+            DeclarationShape::Tuple => Some(false),
+            DeclarationShape::Transparent(ty) => self.is_object_type(ty, visited),
+            DeclarationShape::Union(shapes) => all_objects(
+                shapes
+                    .iter()
+                    .map(|shape| self.is_object_shape(shape, visited)),
+            ),
+        }
+    }
+
+    fn is_object_type(&self, ty: &Type, visited: &mut HashSet<String>) -> Option<bool> {
+        match ty {
+            Type::Path(ty_path) => {
+                let segment = ty_path.path.segments.last()?;
+                match segment.ident.to_string().as_str() {
+                    "HashMap" | "BTreeMap" | "IndexMap" => Some(true),
+                    "Box" | "Rc" | "Arc" => match &segment.arguments {
+                        PathArguments::AngleBracketed(arguments) => {
+                            all_objects(arguments.args.iter().map(|argument| match argument {
+                                GenericArgument::Type(ty) => self.is_object_type(ty, visited),
+                                _ => Some(true),
+                            }))
+                        }
+                        _ => None,
+                    },
+                    "bool" | "char" | "str" | "String" | "u8" | "u16" | "u32" | "u64" | "u128"
+                    | "usize" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "f32" | "f64"
+                    | "Option" | "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => Some(false),
+                    name if ty_path.path.segments.len() == 1 => {
+                        let name = match self.renamed.iter().find(|(ident, _)| *ident == name) {
+                            Some((_, rename)) => rename.to_string(),
+                            None => name.to_string(),
+                        };
+                        self.is_object_declaration_inner(&name, visited)
+                    }
+                    _ => None,
+                }
+            }
+            Type::Reference(reference) => self.is_object_type(&reference.elem, visited),
+            Type::Paren(paren) => self.is_object_type(&paren.elem, visited),
+            Type::Group(group) => self.is_object_type(&group.elem, visited),
+            // `()` is serialized as a unit struct
+            Type::Tuple(tuple) if tuple.elems.is_empty() => Some(true),
+            Type::Tuple(_) | Type::Array(_) | Type::Slice(_) => Some(false),
+            _ => None,
+        }
     }
+
+    /// What a declaration of the current module serializes to that is not an object, given its TS name
+    pub fn unit_content(&self, name: &str) -> UnitContent {
+        let mut content = UnitContent::default();
+        self.unit_content_inner(name, &mut HashSet::new(), &mut content);
+        content
+    }
+
+    fn unit_content_inner(
+        &self,
+        name: &str,
+        visited: &mut HashSet<String>,
+        content: &mut UnitContent,
+    ) {
+        if !visited.insert(name.to_string()) {
+            return;
+        }
+        let shape = self
+            .shapes
+            .iter()
+            .find(|(ident, _)| *self.renamed.get(ident).unwrap_or(ident) == name)
+            .map(|(_, shape)| shape);
+        match shape {
+            Some(shape) => self.unit_content_shape(shape, visited, content),
+            None => content.object = true,
+        }
+    }
+
+    fn unit_content_shape(
+        &self,
+        shape: &DeclarationShape,
+        visited: &mut HashSet<String>,
+        content: &mut UnitContent,
+    ) {
+        match shape {
+            DeclarationShape::Object | DeclarationShape::Tuple => content.object = true,
+            DeclarationShape::Null => content.unit = true,
+            DeclarationShape::String(variant) => content.unit_variants.push(variant.clone()),
+            DeclarationShape::Transparent(ty) => match ty.as_ref() {
+                Type::Tuple(tuple) if tuple.elems.is_empty() => content.unit = true,
+                Type::Path(ty_path) if ty_path.path.segments.len() == 1 => {
+                    let ident = &ty_path.path.segments[0].ident;
+                    let name = self.renamed.get(ident).unwrap_or(ident).to_string();
+                    self.unit_content_inner(&name, visited, content)
+                }
+                _ => content.object = true,
+            },
+            DeclarationShape::Union(shapes) => shapes
+                .iter()
+                .for_each(|shape| self.unit_content_shape(shape, visited, content)),
+        }
+    }
+}
+
+/// What a declaration serializes to that is not an object : the parts an internally tagged enum gives differently
+/// next to its tag, see serde's `TaggedSerializer`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitContent {
+    /// Some values serialize to objects, that are flattened next to the tag
+    pub object: bool,
+    /// A unit struct, given as the tag alone
+    pub unit: bool,
+    /// The serialized names of the unit variants, given as `"Variant": null` next to the tag
+    pub unit_variants: Vec<String>,
+}
+
+impl UnitContent {
+    pub fn is_object(&self) -> bool {
+        !self.unit && self.unit_variants.is_empty()
+    }
+}
+
+/// Whether all the given types are objects : `Some(false)` as soon as one is not, `None` when some are unknown
+pub(crate) fn all_objects(objects: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    objects
+        .into_iter()
+        .try_fold(Some(true), |all, object| match object {
+            Some(false) => Err(()),
+            Some(true) => Ok(all),
+            None => Ok(None),
+        })
+        .unwrap_or(Some(false))
+}
+
+/// How a declaration serializes
+#[derive(Debug, Clone)]
+pub enum DeclarationShape {
+    /// A struct with named fields, or a tagged enum, serialized as a JSON object
+    Object,
+    /// A tuple struct, serialized as an array
+    Tuple,
+    /// A unit struct, serialized as `null`
+    Null,
+    /// A unit variant of an externally tagged enum, serialized as a string, given its serialized name
+    String(String),
+    /// A newtype struct or a type alias, serialized as the inner type
+    Transparent(Box<Type>),
+    /// An enum, serialized as any of its variants
+    Union(Vec<DeclarationShape>),
+}
+
+pub fn parse_shapes(items: &[Item]) -> HashMap<Ident, DeclarationShape> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item_struct) => {
+                Some((item_struct.ident.clone(), fields_shape(&item_struct.fields)))
+            }
+            Item::Enum(item_enum) => {
+                let shape = match enum_tagging(&item_enum.attrs) {
+                    // Unit variants are serialized as their name, the others as `{ "Variant": ... }`
+                    EnumTagging::External => {
+                        let names = serialized_variant_names(item_enum);
+                        DeclarationShape::Union(
+                            item_enum
+                                .variants
+                                .iter()
+                                .map(|variant| match variant.fields {
+                                    Fields::Unit => DeclarationShape::String(
+                                        names
+                                            .get(&variant.ident)
+                                            .cloned()
+                                            .unwrap_or_else(|| variant.ident.to_string()),
+                                    ),
+                                    _ => DeclarationShape::Object,
+                                })
+                                .collect(),
+                        )
+                    }
+                    EnumTagging::Tagged => DeclarationShape::Object,
+                    EnumTagging::Untagged => DeclarationShape::Union(
+                        item_enum
+                            .variants
+                            .iter()
+                            .map(|variant| fields_shape(&variant.fields))
+                            .collect(),
+                    ),
+                };
+                Some((item_enum.ident.clone(), shape))
+            }
+            Item::Type(item_type) => Some((
+                item_type.ident.clone(),
+                DeclarationShape::Transparent(item_type.ty.clone()),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The names the variants of an enum are serialized as, given `#[serde(rename)]` and `#[serde(rename_all)]`
+fn serialized_variant_names(item_enum: &ItemEnum) -> HashMap<Ident, String> {
+    let derive_input = DeriveInput::from(item_enum.clone());
+    let ctxt = Ctxt::new();
+    let names = Container::from_ast(&ctxt, &derive_input, Derive::Serialize)
+        .map(|container| match container.data {
+            Data::Enum(variants) => variants
+                .iter()
+                .map(|variant| (variant.ident.clone(), variant.attrs.name().serialize_name()))
+                .collect(),
+            Data::Struct(..) => HashMap::new(),
+        })
+        .unwrap_or_default();
+    // Invalid attributes are reported when the enum is exported
+    let _ = ctxt.check();
+    names
+}
+
+/// How a struct, or a variant of an untagged enum, serializes
+fn fields_shape(fields: &Fields) -> DeclarationShape {
+    match fields {
+        Fields::Named(_) => DeclarationShape::Object,
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            DeclarationShape::Transparent(Box::new(fields.unnamed[0].ty.clone()))
+        }
+        Fields::Unnamed(_) => DeclarationShape::Tuple,
+        Fields::Unit => DeclarationShape::Null,
+    }
+}
+
+/// The representation of an enum, see <https://serde.rs/enum-representations.html>
+enum EnumTagging {
+    External,
+    /// Internally or adjacently tagged, always serialized as an object
+    Tagged,
+    Untagged,
+}
+
+fn enum_tagging(attrs: &[Attribute]) -> EnumTagging {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .fold(EnumTagging::External, |tagging, nested| match nested {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("untagged") => {
+                EnumTagging::Untagged
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("tag") => {
+                EnumTagging::Tagged
+            }
+            _ => tagging,
+        })
 }
 
 impl Default for ImportContext {
@@ -41,6 +365,9 @@ impl Default for ImportContext {
         ImportContext {
             imported: Default::default(),
             scoped: Default::default(),
+            renamed: Default::default(),
+            declaration_attributes: Default::default(),
+            shapes: Default::default(),
             prelude,
         }
     }
@@ -95,7 +422,8 @@ impl ImportList {
                     .iter()
                     .for_each(|use_tree| self.add_use_tree(segments.clone(), use_tree, crate_name));
             }
-            UseTree::Glob(_) => log::warn!("Glob imports are not supported by typebinder"),
+            // Not supported, reported as a warning by the ModuleStep, see `find_glob_imports`
+            UseTree::Glob(_) => (),
         }
     }
 
@@ -115,6 +443,34 @@ pub fn parse_uses(items: &[Item], crate_name: &str) -> ImportList {
     import_list
 }
 
+/// Finds the glob imports (e.g. `use models::*;`), that are not supported by typebinder
+pub fn find_glob_imports(items: &[Item]) -> Vec<(String, Span)> {
+    fn visit(prefix: &str, use_tree: &UseTree, globs: &mut Vec<(String, Span)>) {
+        match use_tree {
+            UseTree::Path(path) => {
+                let prefix = format!("{}{}::", prefix, path.ident);
+                visit(&prefix, &path.tree, globs)
+            }
+            UseTree::Group(group) => group
+                .items
+                .iter()
+                .for_each(|use_tree| visit(prefix, use_tree, globs)),
+            UseTree::Glob(glob) => globs.push((format!("{}*", prefix), glob.star_token.span)),
+            UseTree::Name(_) | UseTree::Rename(_) => (),
+        }
+    }
+
+    let mut globs = Vec::new();
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Use(item) => Some(item),
+            _ => None,
+        })
+        .for_each(|item_use| visit("", &item_use.tree, &mut globs));
+    globs
+}
+
 pub fn parse_declarations(items: &[Item]) -> ImportList {
     let mut import_list = ImportList::default();
     items.iter().for_each(|item| match item {
@@ -127,10 +483,62 @@ pub fn parse_declarations(items: &[Item]) -> ImportList {
     import_list
 }
 
+/// Finds the declarations renamed with `#[typebinder(rename = "...")]`, so that references
+/// from the current module use the TS name.
+///
+/// Invalid attributes and names that are not Rust identifiers are ignored here, and reported
+/// when exporting the declaration.
+pub fn parse_renames(items: &[Item]) -> HashMap<Ident, Ident> {
+    container_attributes(items)
+        .filter_map(|(ident, attributes)| {
+            let rename = syn::parse_str::<Ident>(attributes.rename.as_ref()?).ok()?;
+            Some((ident.clone(), rename))
+        })
+        .collect()
+}
+
+/// Finds the declarations whose `#[typebinder(...)]` attributes change how the other modules refer to them
+pub fn parse_declaration_attributes(items: &[Item]) -> DeclarationAttributes {
+    let mut declaration_attributes = DeclarationAttributes {
+        renamed: parse_renames(items)
+            .into_iter()
+            .map(|(ident, rename)| (ident.to_string(), rename.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    declaration_attributes.renamed.sort();
+    for (ident, attributes) in container_attributes(items) {
+        if attributes.skip {
+            declaration_attributes.skipped.push(ident.to_string());
+        } else if !attributes.export {
+            declaration_attributes.global.push(ident.to_string());
+        }
+    }
+    declaration_attributes
+}
+
+/// The valid `#[typebinder(...)]` attributes of the declarations
+fn container_attributes(items: &[Item]) -> impl Iterator<Item = (&Ident, ContainerAttributes)> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Enum(item_enum) => Some((&item_enum.ident, &item_enum.attrs)),
+            Item::Struct(item_struct) => Some((&item_struct.ident, &item_struct.attrs)),
+            Item::Type(item_type) => Some((&item_type.ident, &item_type.attrs)),
+            _ => None,
+        })
+        .filter_map(|(ident, attrs)| Some((ident, ContainerAttributes::from_attrs(attrs).ok()?)))
+}
+
 impl ImportContext {
     pub fn solve_import(&self, ty_path: &TypePath) -> Option<syn::Type> {
         let segment = ty_path.path.segments.first().expect("Empty path");
         let ident = &segment.ident;
+        if let Some(rename) = self.renamed_declaration(ident) {
+            let mut ty_path = ty_path.clone();
+            ty_path.path.segments[0].ident = rename.clone();
+            return Some(ty_path.into());
+        }
         let found_segments = self
             .imported
             .get(ident)
@@ -150,6 +558,15 @@ impl ImportContext {
 
         Some(TypePath { qself: None, path }.into())
     }
+
+    fn renamed_declaration(&self, ident: &Ident) -> Option<&Ident> {
+        if self.imported.contains_key(ident) {
+            return None;
+        }
+        self.renamed
+            .get(ident)
+            .filter(|rename| !self.renamed.contains_key(*rename))
+    }
 }
 
 #[cfg(test)]
@@ -180,6 +597,24 @@ pub mod tests {
         struct C<T> { _t: T }
     "#;
 
+    #[test]
+    fn test_find_glob_imports() {
+        let src =
+            syn::parse_file("use crate::models::*;\nuse std::{collections::*, fmt::Display};")
+                .expect("Failed to parse globs");
+        let globs: Vec<(String, usize)> = find_glob_imports(&src.items)
+            .into_iter()
+            .map(|(path, span)| (path, span.start().line))
+            .collect();
+        assert_eq!(
+            globs,
+            vec![
+                ("crate::models::*".to_string(), 1),
+                ("std::collections::*".to_string(), 2)
+            ]
+        );
+    }
+
     #[test]
     fn test_import_scoped() {
         let src = syn::parse_file(EXAMPLE).expect("Failed to parse EXAMPLE");
@@ -22,7 +22,7 @@ use crate::type_solving::solvers::{
     array::ArraySolver, chrono::ChronoSolver, collections::CollectionsSolver,
     generics::GenericsSolver, import::ImportSolver, option::OptionSolver,
     primitives::PrimitivesSolver, ranges::RangesSolver, reference::ReferenceSolver,
-    serde_json_value::SerdeJsonValueSolver, tuple::TupleSolver,
+    serde_json_value::SerdeJsonValueSolver, smart_pointers::SmartPointerSolver, tuple::TupleSolver,
 };
 
 #[derive(Default)]
@@ -39,6 +39,7 @@ impl TypeSolvingContextBuilder {
     pub fn add_default_solvers(self) -> Self {
         self.add_solver(TupleSolver)
             .add_solver(ReferenceSolver)
+            .add_solver(SmartPointerSolver::default())
             .add_solver(ArraySolver)
             .add_solver(CollectionsSolver::default())
             .add_solver(PrimitivesSolver::default())
@@ -0,0 +1,101 @@
+//! Warnings about the Rust input, that do not prevent the bindings from being generated
+use std::{
+    fmt::Display,
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{error::SourceLocation, lints::Lint};
+
+/// What a warning is about
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum WarningKind {
+    #[error("Glob imports are not supported, types imported by `{0}` will not be resolved")]
+    GlobImportIgnored(String),
+    #[error("Type `{0}` is neither declared nor imported in its module, it is assumed to be declared in the same TS module")]
+    UnknownType(String),
+    #[error("Variants `{first}` and `{second}` of untagged enum `{ident}` are both exported as `{ts_type}`, they can't be told apart in TS")]
+    IndistinguishableVariants {
+        ident: String,
+        first: String,
+        second: String,
+        ts_type: String,
+    },
+    #[error("Variant `{variant}` of internally tagged enum `{ident}` contains `{ts_type}`, that is declared in another module : it is assumed to serialize to a JSON object")]
+    AssumedObjectVariant {
+        ident: String,
+        variant: String,
+        ts_type: String,
+    },
+    #[error("{message} [{lint}]")]
+    Lint { lint: Lint, message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub location: Option<SourceLocation>,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, file: Option<&Path>, span: Span) -> Self {
+        let mut location = SourceLocation::from_span(span);
+        location.file = file.map(Path::to_path_buf);
+        Warning {
+            kind,
+            location: Some(location),
+        }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+/// The sink the warnings are collected into while a pipeline runs.
+///
+/// It is shared by the threads that export the modules in parallel.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    warnings: Mutex<Vec<Warning>>,
+}
+
+impl Diagnostics {
+    pub fn warn(&self, warning: Warning) {
+        self.warnings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(warning);
+    }
+
+    /// The warnings, sorted by location so that they don't depend on the order the modules were exported in.
+    ///
+    /// The warnings at the same location, e.g. in modules read without a file, are sorted by message.
+    pub fn into_warnings(self) -> Vec<Warning> {
+        let mut warnings = self
+            .warnings
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        warnings.sort_by_cached_key(|warning| {
+            let location = warning.location.as_ref().map(|location| {
+                (
+                    location.file.clone(),
+                    location.start.line,
+                    location.start.column,
+                )
+            });
+            // The warnings without location come last
+            (location.is_none(), location, warning.kind.to_string())
+        });
+        warnings
+    }
+}
@@ -1,7 +1,15 @@
-use std::path::PathBuf;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-use syn::GenericArgument;
+use proc_macro2::{LineColumn, Span};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
 use thiserror::Error;
+
+use crate::lints::Lint;
 use ts_json_subset::types::TsType;
 
 #[derive(Debug, Error)]
@@ -18,24 +26,185 @@ pub enum TsExportError {
     SynError(#[from] syn::parse::Error),
     #[error("JSON error {0}")]
     SerdeJsonError(#[from] serde_json::Error),
-    #[error("Could not resolve type {:?}", _0)]
-    UnsolvedType(syn::Type),
-    #[error("Could not resolve field {:?}", _0)]
-    UnsolvedField(syn::Field),
+    #[error("TOML error {0}")]
+    TomlError(#[from] toml::de::Error),
+    #[error("Invalid mapping for `{path}` : {reason}")]
+    InvalidPathMapping { path: String, reason: String },
+    #[error("Could not resolve type `{}`", _0)]
+    UnsolvedType(String),
+    #[error("Could not resolve field `{}`", _0)]
+    UnsolvedField(String),
     #[error("Unexpected TS type {:?}", _0)]
     UnexpectedType(TsType),
     #[error("Expected generics")]
     ExpectedGenerics,
     #[error("Empty generics")]
     EmptyGenerics,
-    #[error("Wrong generic type {:?}", _0)]
-    WrongGenericType(GenericArgument),
+    #[error("Wrong generic type `{}`", _0)]
+    WrongGenericType(String),
+    #[error("The crate `{0}` can't be exported as a child module of the root module, which declares a module of the same name")]
+    ModuleNameCollision(String),
     #[error("Cargo.toml error {0}")]
     CargoTomlError(#[from] crate::utils::cargo::Error),
     #[error("Identifier error {}", _0)]
     TSIdentError(#[from] ts_json_subset::ident::IdentError),
     #[error("Malformed input")]
     MalformedInput,
+    #[error("Several declarations are named `{name}` in the bundle : {}", sources.join(", "))]
+    BundleNameCollision { name: String, sources: Vec<String> },
+    #[error("The barrel {:?} would export `{name}` from several modules : {}", barrel, sources.iter().map(|source| format!("{:?}", source)).collect::<Vec<_>>().join(", "))]
+    BarrelNameCollision {
+        barrel: PathBuf,
+        name: String,
+        sources: Vec<PathBuf>,
+    },
+    #[error("Could not parse the TS file {:?}: {}", _0, _1)]
+    TsParseError(PathBuf, ts_json_subset::parser::ParseError),
     #[error("Variant {} leads to an invalid serde representation", _0)]
     InvalidSerdeRepresentation(String),
+    #[error("The type `{name}` is skipped with #[typebinder(skip)], but {module} refers to it")]
+    SkippedTypeReferenced { name: String, module: String },
+    #[error("Root type {} was not found", _0)]
+    RootNotFound(String),
+    #[error("Recursive type {} can't be expressed in TS, the recursion must go through a struct field, an enum variant with data or a Vec", .0.join(" -> "))]
+    UnexpressibleCycle(Vec<String>),
+    #[error("{message} [{lint}]")]
+    LintDenied { lint: Lint, message: String },
+    #[error("{location}: {error}")]
+    Located {
+        location: SourceLocation,
+        error: Box<TsExportError>,
+    },
+    #[error("{} errors occurred:\n{}", .0.len(), .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    Multiple(Vec<TsExportError>),
+}
+
+/// Where an error happened in the Rust source code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// The Rust module file, when known
+    pub file: Option<PathBuf>,
+    /// Start of the offending code. Lines are 1-indexed, columns are 0-indexed.
+    #[serde(with = "line_column")]
+    pub start: LineColumn,
+    /// End of the offending code
+    #[serde(with = "line_column")]
+    pub end: LineColumn,
+}
+
+/// (De)serializes a LineColumn as a `(line, column)` tuple
+mod line_column {
+    use proc_macro2::LineColumn;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &LineColumn, serializer: S) -> Result<S::Ok, S::Error> {
+        (value.line, value.column).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LineColumn, D::Error> {
+        let (line, column) = <(usize, usize)>::deserialize(deserializer)?;
+        Ok(LineColumn { line, column })
+    }
+}
+
+impl SourceLocation {
+    pub fn from_span(span: Span) -> Self {
+        SourceLocation {
+            file: None,
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}", self.start.line, self.start.column + 1)
+    }
+}
+
+impl TsExportError {
+    /// A type that no solver could solve, located at the type.
+    ///
+    /// The Rust code is kept as text, so that the error can be sent between threads.
+    pub fn unsolved_type(ty: &syn::Type) -> Self {
+        TsExportError::UnsolvedType(ty.to_token_stream().to_string()).with_location(None, ty.span())
+    }
+
+    /// A field that no solver could solve, located at the field
+    pub fn unsolved_field(field: &syn::Field) -> Self {
+        TsExportError::UnsolvedField(field.to_token_stream().to_string())
+            .with_location(None, field.span())
+    }
+
+    /// The span of the Rust code this error is about, when the error carries it
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            TsExportError::SynError(error) => Some(error.span()),
+            _ => None,
+        }
+    }
+
+    /// Where the error happened, if it has been located
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            TsExportError::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// The error, without its location
+    pub fn without_location(&self) -> &TsExportError {
+        match self {
+            TsExportError::Located { error, .. } => error.without_location(),
+            error => error,
+        }
+    }
+
+    /// Locates the error : at its own span if it has one, at the given span otherwise.
+    ///
+    /// The innermost location is kept, the file is only filled if it was unknown.
+    pub fn with_location(self, file: Option<&Path>, span: Span) -> Self {
+        let (mut location, error) = match self {
+            TsExportError::Located { location, error } => (location, error),
+            error => (
+                SourceLocation::from_span(error.span().unwrap_or(span)),
+                Box::new(error),
+            ),
+        };
+        if location.file.is_none() {
+            location.file = file.map(Path::to_path_buf);
+        }
+        TsExportError::Located { location, error }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_innermost_location() {
+        let item: syn::ItemStruct = syn::parse_str("struct A {\n    a: fn(),\n}").unwrap();
+        let field = item.fields.iter().next().unwrap();
+        let error = TsExportError::unsolved_type(&field.ty)
+            .with_location(None, Span::call_site())
+            .with_location(Some(Path::new("src/lib.rs")), item.ident.span());
+
+        let location = error.location().unwrap();
+        assert_eq!(location.file.as_deref(), Some(Path::new("src/lib.rs")));
+        assert_eq!((location.start.line, location.start.column), (2, 7));
+        assert_eq!((location.end.line, location.end.column), (2, 11));
+        assert_eq!(
+            error.to_string(),
+            "src/lib.rs:2:8: Could not resolve type `fn ()`"
+        );
+        assert!(matches!(
+            error.without_location(),
+            TsExportError::UnsolvedType(_)
+        ));
+    }
 }
@@ -0,0 +1,480 @@
+//! Barrel files : an `index.ts` per directory that reexports all of its modules, so that the bindings can be imported
+//! from a directory (e.g. `types/api`) instead of from each module (e.g. `types/api/user`).
+//!
+//! A module that has child modules (e.g. `api.ts` next to the `api` directory) also reexports the barrel of its
+//! directory, since TS resolves `types/api` to `api.ts` before `api/index.ts`.
+//!
+//! The reexports whose names collide are put in a namespace, e.g. `api::Status` and `models::status::Status` are
+//! reexported by the root barrel as `api.Status` and `models.Status`.
+use crate::error::TsExportError;
+use crate::pipeline::module_step::ModuleStepResultData;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use ts_json_subset::{
+    declarations::reexport::ExportAllDeclaration, export::ExportStatement, ident::StrictTSIdent,
+};
+
+const BARREL_FILE_NAME: &str = "index.ts";
+
+/// Adds the `export * from` statements of the barrels to the modules, given with their output path.
+///
+/// Gives back the completed modules along with the new barrel files. The modules and directories whose names collide
+/// are reexported in a namespace named after them (`export * as api from "./api";`), and a barrel that would still
+/// export several declarations of the same name is an error.
+///
+/// With `js_extension`, the reexported files are given with the `.js` extension, as required by the ESM resolution of
+/// Node, see [ImportStrategy](crate::pipeline::imports::ImportStrategy).
+pub(crate) fn add_barrels(
+    root_path: &Path,
+    modules: Vec<(PathBuf, ModuleStepResultData)>,
+    js_extension: bool,
+) -> Result<Vec<(PathBuf, ModuleStepResultData)>, TsExportError> {
+    let mut modules: BTreeMap<PathBuf, ModuleStepResultData> = modules
+        .into_iter()
+        .map(|(path, module)| {
+            let relative = path.strip_prefix(root_path).unwrap_or(&path).to_path_buf();
+            (relative, module)
+        })
+        .collect();
+
+    let mut directories: BTreeSet<PathBuf> = BTreeSet::new();
+    for path in modules.keys() {
+        let mut directory = path.parent();
+        while let Some(current) = directory {
+            directories.insert(current.to_path_buf());
+            directory = current.parent();
+        }
+    }
+
+    // The names exported by each module file once completed, and by each directory
+    let mut exported: BTreeMap<PathBuf, Vec<(String, PathBuf)>> = BTreeMap::new();
+    let declared = |path: &Path, module: &ModuleStepResultData| -> Vec<(String, PathBuf)> {
+        module
+            .exports
+            .iter()
+            .flat_map(|statement| statement.declared_idents())
+            .map(|ident| (ident.to_string(), path.to_path_buf()))
+            .collect()
+    };
+    let mut reexports: BTreeMap<PathBuf, Vec<ExportAllDeclaration>> = BTreeMap::new();
+
+    // Deepest directories first, as their names are reexported by their parents
+    let mut ordered: Vec<&PathBuf> = directories.iter().collect();
+    ordered.sort_by_key(|directory| std::cmp::Reverse(directory.components().count()));
+    for directory in ordered {
+        let barrel_path = directory.join(BARREL_FILE_NAME);
+        let mut barrel_reexports: Vec<Reexport> = Vec::new();
+
+        for (path, module) in modules.iter() {
+            if path.parent() != Some(directory.as_path()) {
+                continue;
+            }
+            if *path == barrel_path {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut names = declared(path, module);
+            let child_directory = directory.join(&stem);
+            if let Some(child_names) = exported.get(&child_directory) {
+                let mut child = [Reexport {
+                    specifier: file_specifier(&format!("./{}/index", stem), js_extension),
+                    names: child_names.clone(),
+                    namespace: stem.clone(),
+                    source: child_directory,
+                    namespaced: false,
+                }];
+                namespace_collisions(&names, &mut child);
+                let [child] = child;
+                check_collisions(root_path, path, names.iter().chain(child.names.iter()))?;
+                names.extend(child.names.iter().cloned());
+                reexports
+                    .entry(path.clone())
+                    .or_default()
+                    .push(child.declaration());
+            }
+            barrel_reexports.push(Reexport {
+                specifier: file_specifier(&format!("./{}", stem), js_extension),
+                names,
+                namespace: stem,
+                source: path.clone(),
+                namespaced: false,
+            });
+        }
+        for child_directory in directories.iter() {
+            if child_directory.parent() != Some(directory.as_path()) {
+                continue;
+            }
+            let name = child_directory
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            // Reexported through the module of the same name
+            if modules.contains_key(&directory.join(format!("{}.ts", name))) {
+                continue;
+            }
+            let names = exported.get(child_directory).cloned().unwrap_or_default();
+            // ESM does not resolve the directories to their index
+            let specifier = if js_extension {
+                file_specifier(&format!("./{}/index", name), js_extension)
+            } else {
+                format!("./{}", name)
+            };
+            barrel_reexports.push(Reexport {
+                specifier,
+                names,
+                namespace: name,
+                source: child_directory.clone(),
+                namespaced: false,
+            });
+        }
+
+        let own = match modules.get(&barrel_path) {
+            Some(module) => declared(&barrel_path, module),
+            None => Vec::new(),
+        };
+        namespace_collisions(&own, &mut barrel_reexports);
+        let names: Vec<(String, PathBuf)> = own
+            .into_iter()
+            .chain(
+                barrel_reexports
+                    .iter()
+                    .flat_map(|reexport| reexport.names.iter().cloned()),
+            )
+            .collect();
+        check_collisions(root_path, &barrel_path, names.iter())?;
+        exported.insert(directory.clone(), names);
+        if !barrel_reexports.is_empty() {
+            reexports
+                .entry(barrel_path)
+                .or_default()
+                .extend(barrel_reexports.iter().map(Reexport::declaration));
+        }
+    }
+
+    for (path, declarations) in reexports {
+        let module = modules.entry(path.clone()).or_insert_with(|| {
+            let directory = path.parent().unwrap_or_else(|| Path::new(""));
+            barrel_module(directory)
+        });
+        for declaration in declarations {
+            module
+                .exports
+                .push(ExportStatement::ExportAllDeclaration(declaration));
+            module.dependencies.push(Vec::new());
+        }
+    }
+
+    Ok(modules
+        .into_iter()
+        .map(|(path, module)| (root_path.join(path), module))
+        .collect())
+}
+
+/// A module or a directory reexported by a barrel
+struct Reexport {
+    specifier: String,
+    /// The names it exports, with the file that declares them
+    names: Vec<(String, PathBuf)>,
+    /// The namespace it is reexported in when its names collide with others, named after the module or the directory
+    namespace: String,
+    /// The module file or the directory
+    source: PathBuf,
+    namespaced: bool,
+}
+
+impl Reexport {
+    fn declaration(&self) -> ExportAllDeclaration {
+        ExportAllDeclaration {
+            path: format!("\"{}\"", self.specifier),
+            namespace: if self.namespaced {
+                StrictTSIdent::from_str(&self.namespace).ok()
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Reexports in a namespace the modules and directories whose names collide with the names of the others or with
+/// the declarations of the barrel itself, e.g. `export * as status from "./status";`.
+///
+/// The namespaces that are not valid TS idents are left out, for the collision to be reported.
+fn namespace_collisions(own: &[(String, PathBuf)], reexports: &mut [Reexport]) {
+    let mut sources: BTreeMap<&str, usize> = BTreeMap::new();
+    let reexported = reexports.iter().flat_map(|reexport| {
+        reexport
+            .names
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<BTreeSet<&str>>()
+    });
+    for name in own.iter().map(|(name, _)| name.as_str()).chain(reexported) {
+        *sources.entry(name).or_default() += 1;
+    }
+    let colliding: Vec<usize> = reexports
+        .iter()
+        .enumerate()
+        .filter(|(_, reexport)| {
+            reexport
+                .names
+                .iter()
+                .any(|(name, _)| sources.get(name.as_str()).copied().unwrap_or_default() > 1)
+        })
+        .filter(|(_, reexport)| StrictTSIdent::from_str(&reexport.namespace).is_ok())
+        .map(|(index, _)| index)
+        .collect();
+    for index in colliding {
+        let reexport = &mut reexports[index];
+        reexport.names = vec![(reexport.namespace.clone(), reexport.source.clone())];
+        reexport.namespaced = true;
+    }
+}
+
+fn file_specifier(path: &str, js_extension: bool) -> String {
+    if js_extension {
+        format!("{}.js", path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// An empty module for the barrel of a directory, named after it
+fn barrel_module(directory: &Path) -> ModuleStepResultData {
+    let segments = directory
+        .components()
+        .map(|component| {
+            let segment = component.as_os_str().to_string_lossy();
+            syn::PathSegment::from(syn::Ident::new(&segment, proc_macro2::Span::call_site()))
+        })
+        .collect();
+    ModuleStepResultData {
+        exports: Vec::new(),
+        imports: Vec::new(),
+        path: syn::Path {
+            leading_colon: None,
+            segments,
+        },
+        dependencies: Vec::new(),
+        crate_name: String::new(),
+        declaration_attributes: Default::default(),
+    }
+}
+
+/// Fails on the first name that is exported twice
+fn check_collisions<'a, I>(
+    root_path: &Path,
+    barrel_path: &Path,
+    names: I,
+) -> Result<(), TsExportError>
+where
+    I: Iterator<Item = &'a (String, PathBuf)>,
+{
+    let mut sources: BTreeMap<&str, Vec<&Path>> = BTreeMap::new();
+    for (name, path) in names {
+        sources.entry(name).or_default().push(path);
+    }
+    match sources.into_iter().find(|(_, paths)| paths.len() > 1) {
+        Some((name, paths)) => Err(TsExportError::BarrelNameCollision {
+            barrel: root_path.join(barrel_path),
+            name: name.to_string(),
+            sources: paths.into_iter().map(|path| root_path.join(path)).collect(),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::add_barrels;
+    use crate::{error::TsExportError, pipeline::module_step::ModuleStepResultData};
+    use std::{path::PathBuf, str::FromStr};
+    use ts_json_subset::export::ExportStatement;
+
+    fn module(exports: Vec<&str>) -> ModuleStepResultData {
+        ModuleStepResultData {
+            dependencies: exports.iter().map(|_| Vec::new()).collect(),
+            exports: exports
+                .into_iter()
+                .map(|export| ExportStatement::from_str(export).unwrap())
+                .collect(),
+            imports: Vec::new(),
+            path: syn::Path {
+                leading_colon: None,
+                segments: Default::default(),
+            },
+            crate_name: "app".to_string(),
+            declaration_attributes: Default::default(),
+        }
+    }
+
+    fn barrels(modules: Vec<(&str, Vec<&str>)>) -> Result<Vec<(String, String)>, TsExportError> {
+        barrels_with_extension(modules, false)
+    }
+
+    fn barrels_with_extension(
+        modules: Vec<(&str, Vec<&str>)>,
+        js_extension: bool,
+    ) -> Result<Vec<(String, String)>, TsExportError> {
+        let root = PathBuf::from("/out");
+        let modules = modules
+            .into_iter()
+            .map(|(path, exports)| (root.join(path), module(exports)))
+            .collect();
+        Ok(add_barrels(&root, modules, js_extension)?
+            .into_iter()
+            .map(|(path, module)| {
+                let exports: Vec<String> = module.exports.iter().map(ToString::to_string).collect();
+                (
+                    path.strip_prefix(&root).unwrap().display().to_string(),
+                    exports.join("\n"),
+                )
+            })
+            .collect())
+    }
+
+    #[test]
+    fn should_generate_the_barrels() {
+        let barrels = barrels(vec![
+            ("index.ts", vec!["export type Id = number;"]),
+            ("api.ts", vec!["export type Request = string;"]),
+            ("api/user.ts", vec!["export type User = Id;"]),
+            ("models/post.ts", vec!["export type Post = string;"]),
+            (
+                "models/nested/comment.ts",
+                vec!["export type Comment = string;"],
+            ),
+        ])
+        .unwrap();
+        let expected: Vec<(&str, &str)> = vec![
+            ("api/index.ts", r#"export * from "./user";"#),
+            ("api/user.ts", "export type User = Id;"),
+            (
+                "api.ts",
+                r#"export type Request = string;
+export * from "./api/index";"#,
+            ),
+            (
+                "index.ts",
+                r#"export type Id = number;
+export * from "./api";
+export * from "./models";"#,
+            ),
+            (
+                "models/index.ts",
+                r#"export * from "./post";
+export * from "./nested";"#,
+            ),
+            ("models/nested/comment.ts", "export type Comment = string;"),
+            ("models/nested/index.ts", r#"export * from "./comment";"#),
+            ("models/post.ts", "export type Post = string;"),
+        ];
+        assert_eq!(
+            barrels,
+            expected
+                .into_iter()
+                .map(|(path, contents)| (path.to_string(), contents.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_add_the_js_extension() {
+        let barrels = barrels_with_extension(
+            vec![
+                ("api.ts", vec!["export type Request = string;"]),
+                ("api/user.ts", vec!["export type User = string;"]),
+                ("models/post.ts", vec!["export type Post = string;"]),
+            ],
+            true,
+        )
+        .unwrap();
+        let reexports: Vec<&str> = barrels
+            .iter()
+            .flat_map(|(_, contents)| contents.lines())
+            .filter(|line| line.starts_with("export *"))
+            .collect();
+        assert_eq!(
+            reexports,
+            vec![
+                r#"export * from "./user.js";"#,
+                r#"export * from "./api/index.js";"#,
+                r#"export * from "./api.js";"#,
+                r#"export * from "./models/index.js";"#,
+                r#"export * from "./post.js";"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_namespace_the_colliding_reexports() {
+        let barrels = barrels(vec![
+            ("api.ts", vec!["export type Status = string;"]),
+            ("api/user.ts", vec!["export type User = string;"]),
+            ("models/status.ts", vec!["export type Status = number;"]),
+            ("models/post.ts", vec!["export type Post = string;"]),
+        ])
+        .unwrap();
+        let index = barrels
+            .iter()
+            .find(|(path, _)| path == "index.ts")
+            .map(|(_, contents)| contents.as_str());
+        assert_eq!(
+            index,
+            Some(
+                r#"export * as api from "./api";
+export * as models from "./models";"#
+            )
+        );
+        let models = barrels
+            .iter()
+            .find(|(path, _)| path == "models/index.ts")
+            .map(|(_, contents)| contents.as_str());
+        assert_eq!(
+            models,
+            Some(
+                r#"export * from "./post";
+export * from "./status";"#
+            )
+        );
+    }
+
+    #[test]
+    fn should_namespace_the_child_barrel_colliding_with_its_module() {
+        let barrels = barrels(vec![
+            ("api.ts", vec!["export type Status = string;"]),
+            ("api/status.ts", vec!["export type Status = number;"]),
+        ])
+        .unwrap();
+        let api = barrels
+            .iter()
+            .find(|(path, _)| path == "api.ts")
+            .map(|(_, contents)| contents.as_str());
+        assert_eq!(
+            api,
+            Some(
+                r#"export type Status = string;
+export * as api from "./api/index";"#
+            )
+        );
+    }
+
+    #[test]
+    fn should_detect_the_collisions_with_the_namespaces() {
+        let error = barrels(vec![
+            ("index.ts", vec!["export type api = string;"]),
+            ("api/user.ts", vec!["export type Status = string;"]),
+            ("models/user.ts", vec!["export type Status = number;"]),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The barrel \"/out/index.ts\" would export `api` from several modules : \"/out/index.ts\", \"/out/api\""
+        );
+    }
+}
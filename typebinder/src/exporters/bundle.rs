@@ -0,0 +1,476 @@
+use super::Exporter;
+use crate::error::TsExportError;
+use crate::exporters::{file::write_if_changed, utils::render_statements, HeaderComment};
+use crate::type_solving::ImportEntry;
+use crate::utils::rename::rename_statement;
+use crate::{pipeline::module_step::ModuleStepResultData, utils::display_path::DisplayPath};
+use indexmap::{IndexMap, IndexSet};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+};
+use ts_json_subset::{
+    export::ExportStatement,
+    import::{ImportKind, ImportList, ImportSpecifier, ImportStatement},
+    printer::PrintConfig,
+};
+
+/// A strategy that outputs all the modules to a single TS file, e.g. for a small consumer that only wants a `types.ts`.
+///
+/// The imports between the bundled modules are dropped, the other ones are kept.
+/// Declarations of the same name in several modules are an error, unless [BundleExporter::set_rename_duplicates] is set.
+pub struct BundleExporter {
+    path: PathBuf,
+    header_comment: HeaderComment,
+    print_config: Option<PrintConfig>,
+    rename_duplicates: bool,
+    modules: Vec<BundledModule>,
+}
+
+/// A module to bundle, its Rust path being kept as text
+struct BundledModule {
+    path: String,
+    crate_name: String,
+    exports: Vec<ExportStatement>,
+    imports: Vec<ImportStatement>,
+    dependencies: Vec<ImportEntry>,
+}
+
+impl BundleExporter {
+    pub fn new(path: PathBuf) -> Self {
+        BundleExporter {
+            path,
+            header_comment: HeaderComment::Standard,
+            print_config: None,
+            rename_duplicates: false,
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn set_header_comment(&mut self, header_comment: HeaderComment) {
+        self.header_comment = header_comment;
+    }
+
+    /// Prints the statements with the given style instead of the default one
+    pub fn set_print_config(&mut self, print_config: PrintConfig) {
+        self.print_config = Some(print_config);
+    }
+
+    /// Prefixes the declarations of the same name with the PascalCase path of their module (e.g. `models::user::Status`
+    /// becomes `ModelsUserStatus`) instead of failing. A declaration of the root module keeps its name.
+    pub fn set_rename_duplicates(&mut self, rename_duplicates: bool) {
+        self.rename_duplicates = rename_duplicates;
+    }
+
+    /// The bundled module that an import path refers to, if any
+    fn resolve<'a>(&'a self, module: &'a BundledModule, import_path: &'a str) -> Option<&'a str> {
+        if import_path.is_empty() {
+            return Some(&module.path);
+        }
+        // Paths of the current crate may be prefixed with its name, e.g. after `use crate::...`
+        let in_crate = import_path
+            .strip_prefix(&module.crate_name)
+            .and_then(|rest| match rest {
+                "" => Some(""),
+                rest => rest.strip_prefix("::"),
+            });
+        self.modules
+            .iter()
+            .map(|other| other.path.as_str())
+            .find(|path| *path == import_path || Some(*path) == in_crate)
+    }
+
+    fn bundle(&self) -> Result<String, TsExportError> {
+        let mut declarations: IndexMap<String, Vec<&str>> = IndexMap::new();
+        for module in self.modules.iter() {
+            for statement in module.exports.iter() {
+                for ident in statement.declared_idents() {
+                    declarations
+                        .entry(ident.to_string())
+                        .or_default()
+                        .push(&module.path);
+                }
+            }
+        }
+
+        // The imports from outside of the bundle, grouped by TS path. They are type-only if they all were
+        let mut external: IndexMap<String, (bool, IndexSet<ImportSpecifier>)> = IndexMap::new();
+        for module in self.modules.iter() {
+            for statement in module.imports.iter() {
+                let items = match &statement.import_kind {
+                    ImportKind::ImportList(list) => &list.items,
+                    _ => continue,
+                };
+                let is_bundled = items.iter().any(|item| {
+                    module.dependencies.iter().any(|entry| {
+                        entry.ident == item.ident.to_string()
+                            && entry.local_name() == item.local_name().to_string()
+                            && self.resolve(module, &entry.path).is_some()
+                    })
+                });
+                if !is_bundled {
+                    let (type_only, names) = external
+                        .entry(statement.path.clone())
+                        .or_insert_with(|| (true, IndexSet::new()));
+                    *type_only &= statement.type_only;
+                    names.extend(items.iter().cloned());
+                }
+            }
+        }
+
+        let mut errors = Vec::new();
+        let mut external_names: IndexMap<String, Vec<String>> = IndexMap::new();
+        for (path, (_, items)) in external.iter() {
+            for item in items.iter() {
+                external_names
+                    .entry(item.local_name().to_string())
+                    .or_default()
+                    .push(format!("import from {}", path));
+            }
+        }
+        for (name, sources) in external_names.iter() {
+            let declared = declarations
+                .get(name)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if sources.len() > 1 || !declared.is_empty() {
+                errors.push(TsExportError::BundleNameCollision {
+                    name: name.to_string(),
+                    sources: sources
+                        .iter()
+                        .cloned()
+                        .chain(declared.iter().map(|path| module_name(path)))
+                        .collect(),
+                });
+            }
+        }
+
+        let mut taken: BTreeSet<String> = declarations
+            .keys()
+            .cloned()
+            .chain(external_names.keys().map(ToString::to_string))
+            .collect();
+        let mut renames: HashMap<(&str, &str), String> = HashMap::new();
+        for (name, paths) in declarations.iter().filter(|(_, paths)| paths.len() > 1) {
+            if !self.rename_duplicates {
+                errors.push(TsExportError::BundleNameCollision {
+                    name: name.clone(),
+                    sources: paths.iter().map(|path| module_name(path)).collect(),
+                });
+                continue;
+            }
+            for path in paths.iter().filter(|path| !path.is_empty()) {
+                let prefixed = format!("{}{}", pascal_case(path), name);
+                let mut renamed = prefixed.clone();
+                let mut suffix = 2;
+                while taken.contains(&renamed) {
+                    renamed = format!("{}{}", prefixed, suffix);
+                    suffix += 1;
+                }
+                log::info!("Renaming {} of {} to {}", name, module_name(path), renamed);
+                taken.insert(renamed.clone());
+                renames.insert((path, name), renamed);
+            }
+        }
+        match errors.len() {
+            0 => (),
+            1 => return Err(errors.remove(0)),
+            _ => return Err(TsExportError::Multiple(errors)),
+        }
+
+        let mut exports = Vec::new();
+        for module in self.modules.iter() {
+            let rename = |name: &str| -> Option<String> {
+                if declarations
+                    .get(name)
+                    .is_some_and(|paths| paths.contains(&module.path.as_str()))
+                {
+                    return renames.get(&(module.path.as_str(), name)).cloned();
+                }
+                // The declaration may have been imported under an alias, that the bundle does not need
+                let (path, entry) = module
+                    .dependencies
+                    .iter()
+                    .filter(|entry| entry.local_name() == name)
+                    .find_map(|entry| Some((self.resolve(module, &entry.path)?, entry)))?;
+                renames
+                    .get(&(path, entry.ident.as_str()))
+                    .cloned()
+                    .or_else(|| entry.alias.as_ref().map(|_| entry.ident.clone()))
+            };
+            for statement in module.exports.iter() {
+                let mut statement = statement.clone();
+                rename_statement(&mut statement, &rename)?;
+                exports.push(statement);
+            }
+        }
+
+        let imports = external
+            .into_iter()
+            .map(|(path, (type_only, items))| ImportStatement {
+                import_kind: ImportKind::ImportList(ImportList {
+                    items: items.into_iter().collect(),
+                }),
+                path,
+                type_only,
+            })
+            .collect::<Vec<_>>();
+
+        let contents = render_statements(&imports, &exports, self.print_config.as_ref());
+        let root_path = syn::Path {
+            leading_colon: None,
+            segments: Default::default(),
+        };
+        Ok(match self.header_comment.render(&root_path) {
+            None => contents,
+            Some(comment) => format!("{}\n\n{}", comment, contents),
+        })
+    }
+}
+
+impl Exporter for BundleExporter {
+    type Error = TsExportError;
+    type Output = ();
+
+    fn export_module(&mut self, process_result: ModuleStepResultData) -> Result<(), TsExportError> {
+        log::info!("Bundling module {}", DisplayPath(&process_result.path));
+        self.modules.push(BundledModule {
+            path: DisplayPath(&process_result.path).to_string(),
+            crate_name: process_result.crate_name,
+            exports: process_result.exports,
+            imports: process_result.imports,
+            dependencies: process_result.dependencies.into_iter().flatten().collect(),
+        });
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), TsExportError> {
+        self.modules.sort_by(|a, b| a.path.cmp(&b.path));
+        let contents = self.bundle()?;
+        if write_if_changed(&self.path, &contents)? {
+            log::info!("Outputting the bundle at {:?}", self.path);
+        } else {
+            log::info!("Bundle at {:?} is unchanged", self.path);
+        }
+        Ok(())
+    }
+}
+
+fn module_name(path: &str) -> String {
+    if path.is_empty() {
+        "the root module".to_string()
+    } else {
+        format!("module {}", path)
+    }
+}
+
+/// `models::user_data` -> `ModelsUserData`
+fn pascal_case(path: &str) -> String {
+    path.split([':', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::BundleExporter;
+    use crate::{
+        error::TsExportError,
+        exporters::{Exporter, HeaderComment},
+        pipeline::module_step::ModuleStepResultData,
+        type_solving::ImportEntry,
+    };
+    use std::str::FromStr;
+    use ts_json_subset::{export::ExportStatement, import::ImportStatement};
+
+    /// A module of the crate `app`, each export depending on the given (path, ident) entries
+    fn module(path: &str, exports: Vec<(&str, Vec<(&str, &str)>)>) -> ModuleStepResultData {
+        let (exports, dependencies): (Vec<ExportStatement>, Vec<Vec<ImportEntry>>) = exports
+            .into_iter()
+            .map(|(export, entries)| {
+                (
+                    ExportStatement::from_str(export).unwrap(),
+                    entries
+                        .into_iter()
+                        .map(|(path, ident)| ImportEntry {
+                            path: path.to_string(),
+                            ident: ident.to_string(),
+                            alias: None,
+                        })
+                        .collect(),
+                )
+            })
+            .unzip();
+        // Without a PathMapper, the imports keep the Rust paths
+        let mut imports: Vec<ImportStatement> = Vec::new();
+        for entry in dependencies.iter().flatten().filter(|e| !e.path.is_empty()) {
+            imports.push(
+                ImportStatement::from_str(&format!(
+                    "import {{ {} }} from \"{}\";",
+                    entry.ident,
+                    if entry.path == "serde_json" {
+                        "@types/json"
+                    } else {
+                        &entry.path
+                    }
+                ))
+                .unwrap(),
+            );
+        }
+        ModuleStepResultData {
+            exports,
+            imports,
+            path: syn::parse_str(path).unwrap_or_else(|_| syn::Path {
+                leading_colon: None,
+                segments: Default::default(),
+            }),
+            dependencies,
+            crate_name: "app".to_string(),
+            declaration_attributes: Default::default(),
+        }
+    }
+
+    fn bundle(
+        modules: Vec<ModuleStepResultData>,
+        rename_duplicates: bool,
+    ) -> Result<String, TsExportError> {
+        let mut exporter = BundleExporter::new("types.ts".into());
+        exporter.set_header_comment(HeaderComment::None);
+        exporter.set_rename_duplicates(rename_duplicates);
+        for module in modules {
+            exporter.export_module(module).unwrap();
+        }
+        exporter.modules.sort_by(|a, b| a.path.cmp(&b.path));
+        exporter.bundle()
+    }
+
+    fn modules() -> Vec<ModuleStepResultData> {
+        vec![
+            module(
+                "",
+                vec![(
+                    "export interface Root { user: User, value: Value }",
+                    vec![("models", "User"), ("serde_json", "Value")],
+                )],
+            ),
+            module(
+                "models",
+                vec![
+                    (
+                        "export interface User { id: number, status: Status }",
+                        vec![("", "Status")],
+                    ),
+                    (r#"export type Status = "Active" | "Banned";"#, vec![]),
+                ],
+            ),
+            module(
+                "api",
+                vec![
+                    (
+                        "export interface Response<T> { user: User, status: Status, data: T }",
+                        vec![("app::models", "User"), ("", "Status")],
+                    ),
+                    ("export interface Status { ok: boolean }", vec![]),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn should_bundle_the_modules() {
+        let mut modules = modules();
+        modules.pop();
+        assert_eq!(
+            bundle(modules, false).unwrap(),
+            r#"import { Value } from "@types/json";
+export interface Root {
+	user: User,
+	value: Value
+}
+export interface User {
+	id: number,
+	status: Status
+}
+export type Status = "Active" | "Banned";
+"#
+        );
+    }
+
+    #[test]
+    fn should_report_the_duplicates() {
+        let error = bundle(modules(), false).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Several declarations are named `Status` in the bundle : module api, module models"
+        );
+    }
+
+    #[test]
+    fn should_rename_the_duplicates() {
+        assert_eq!(
+            bundle(modules(), true).unwrap(),
+            r#"import { Value } from "@types/json";
+export interface Root {
+	user: User,
+	value: Value
+}
+export interface Response<T> {
+	user: User,
+	status: ApiStatus,
+	data: T
+}
+export interface ApiStatus {
+	ok: boolean
+}
+export interface User {
+	id: number,
+	status: ModelsStatus
+}
+export type ModelsStatus = "Active" | "Banned";
+"#
+        );
+    }
+
+    #[test]
+    fn should_rename_the_aliased_references() {
+        let mut modules = modules();
+        modules[0] = module(
+            "",
+            vec![(
+                "export interface Root { user: User, status: OtherStatus }",
+                vec![("models", "User"), ("api", "Status")],
+            )],
+        );
+        modules[0].dependencies[0][1].alias = Some("OtherStatus".to_string());
+        modules[0].imports[1] =
+            ImportStatement::from_str(r#"import { Status as OtherStatus } from "api";"#).unwrap();
+        assert_eq!(
+            bundle(modules, true).unwrap(),
+            r#"export interface Root {
+	user: User,
+	status: ApiStatus
+}
+export interface Response<T> {
+	user: User,
+	status: ApiStatus,
+	data: T
+}
+export interface ApiStatus {
+	ok: boolean
+}
+export interface User {
+	id: number,
+	status: ModelsStatus
+}
+export type ModelsStatus = "Active" | "Banned";
+"#
+        );
+    }
+}
@@ -1,19 +1,34 @@
 use crate::exporters::utils::{get_file_contents, get_output_file_path};
 use crate::{
     error::TsExportError,
-    exporters::{Exporter, HeaderComment},
+    exporters::{
+        barrel::add_barrels,
+        manifest::Manifest,
+        semantic::{semantic_changes, SemanticChange},
+        Exporter, HeaderComment,
+    },
     pipeline::module_step::ModuleStepResultData,
 };
-use displaythis::Display;
-use std::collections::HashMap;
-use std::io::Read;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use ts_json_subset::printer::PrintConfig;
 
+/// Number of unchanged lines shown around the changes of a [DiffHunk]
+const CONTEXT_LINES: usize = 3;
+
+/// A strategy that compares the generated modules with the existing files, without writing anything.
+///
+/// The comparison is given back as a [CheckReport] when done.
 pub struct CheckExport {
     root_path: PathBuf,
     default_module_name: Option<String>,
     header_comment: HeaderComment,
-    patches: HashMap<PathBuf, DiffPatch>,
+    print_config: Option<PrintConfig>,
+    files: Vec<FileReport>,
+    manifest: Manifest,
+    semantic: bool,
+    barrels: Option<Vec<(PathBuf, ModuleStepResultData)>>,
+    js_extension: bool,
 }
 
 impl CheckExport {
@@ -22,7 +37,12 @@ impl CheckExport {
             root_path,
             default_module_name: None,
             header_comment: HeaderComment::Standard,
-            patches: HashMap::default(),
+            print_config: None,
+            files: Vec::new(),
+            manifest: Manifest::default(),
+            semantic: false,
+            barrels: None,
+            js_extension: false,
         }
     }
 
@@ -34,110 +54,385 @@ impl CheckExport {
                 .expect("Invalid UTF-8 name for module")
         });
     }
+
+    /// Compares with the statements printed with the given style instead of the default one
+    pub fn set_print_config(&mut self, print_config: PrintConfig) {
+        self.print_config = Some(print_config);
+    }
+
+    /// Compares the declarations of the existing files rather than their text, see [semantic_changes].
+    ///
+    /// The outdated files are then reported with [SemanticChange]s instead of diff hunks.
+    pub fn set_semantic(&mut self, semantic: bool) {
+        self.semantic = semantic;
+    }
+
+    /// Also compares the `index.ts` barrel of each directory, see [FileExporter::set_barrels]
+    ///
+    /// [FileExporter::set_barrels]: crate::exporters::file::FileExporter::set_barrels
+    pub fn set_barrels(&mut self, barrels: bool) {
+        self.barrels = if barrels { Some(Vec::new()) } else { None };
+    }
+
+    /// Compares the barrels reexporting the modules with the `.js` extension, see [FileExporter::set_js_extension]
+    ///
+    /// [FileExporter::set_js_extension]: crate::exporters::file::FileExporter::set_js_extension
+    pub fn set_js_extension(&mut self, js_extension: bool) {
+        self.js_extension = js_extension;
+    }
+
+    fn check_barrels(&mut self) -> Result<(), TsExportError> {
+        if let Some(modules) = self.barrels.take() {
+            for (path, process_result) in add_barrels(&self.root_path, modules, self.js_extension)?
+            {
+                self.check_module(path, process_result)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_module(
+        &mut self,
+        path: PathBuf,
+        process_result: ModuleStepResultData,
+    ) -> Result<(), TsExportError> {
+        log::info!("Comparing module at {:?}", path);
+        self.manifest.insert(&self.root_path, &path);
+
+        let changes = |file_contents: &str| {
+            semantic_changes(
+                file_contents,
+                &process_result.imports,
+                &process_result.exports,
+            )
+        };
+        let existing_file_contents = match std::fs::read_to_string(&path) {
+            Ok(file_contents) => Some(file_contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let (hunks, changes) = match &existing_file_contents {
+            Some(file_contents) if self.semantic => (Vec::new(), changes(file_contents)),
+            _ => {
+                let generated_file_contents = get_file_contents(
+                    process_result,
+                    &self.header_comment,
+                    self.print_config.as_ref(),
+                );
+                let file_contents = existing_file_contents.as_deref().unwrap_or_default();
+                (
+                    diff_hunks(file_contents, &generated_file_contents),
+                    Vec::new(),
+                )
+            }
+        };
+        let status = match existing_file_contents {
+            None => FileStatus::Missing,
+            Some(_) if hunks.is_empty() && changes.is_empty() => FileStatus::UpToDate,
+            Some(_) => FileStatus::Outdated,
+        };
+        self.files.push(FileReport {
+            path,
+            status,
+            hunks,
+            changes,
+        });
+
+        Ok(())
+    }
+}
+
+/// The result of a check : the status of each file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckReport {
+    /// Sorted by path
+    pub files: Vec<FileReport>,
+}
+
+impl CheckReport {
+    /// Whether all the files are up to date
+    pub fn is_up_to_date(&self) -> bool {
+        self.files
+            .iter()
+            .all(|file| file.status == FileStatus::UpToDate)
+    }
+
+    /// The files that are not up to date
+    pub fn outdated_files(&self) -> impl Iterator<Item = &FileReport> {
+        self.files
+            .iter()
+            .filter(|file| file.status != FileStatus::UpToDate)
+    }
+}
+
+/// A file of the bindings, along with the hunks or the changes that would bring it up to date
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub hunks: Vec<DiffHunk>,
+    /// The changes to the declarations of an outdated file, in semantic mode
+    pub changes: Vec<SemanticChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    UpToDate,
+    /// The file does not exist yet, the hunks create it
+    Missing,
+    /// The file differs from the generated module
+    Outdated,
+    /// A file of the manifest that is no longer generated, the hunks delete it
+    Orphaned,
+}
+
+/// A group of changes, along with the unchanged lines around them.
+///
+/// Line numbers are 1-indexed. When a side of the hunk is empty, its start is the line after which it would be.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
 }
 
-#[derive(Debug, Display)]
-pub enum DiffKind {
-    #[display("+{0}")]
+/// A line of a [DiffHunk], where the existing file is the old side and the generated module the new one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
+pub enum DiffLine {
+    Context(String),
     Added(String),
-    #[display("-{0}")]
     Removed(String),
 }
 
-#[derive(Debug, Display)]
-#[display("{line}: {kind}")]
-pub struct DiffChange {
-    line: usize,
-    kind: DiffKind,
+impl std::fmt::Display for DiffHunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )?;
+        self.lines.iter().try_for_each(|line| match line {
+            DiffLine::Context(line) => write!(f, "\n {}", line),
+            DiffLine::Added(line) => write!(f, "\n+{}", line),
+            DiffLine::Removed(line) => write!(f, "\n-{}", line),
+        })
+    }
 }
 
-#[derive(Debug)]
-pub enum DiffPatch {
-    NewFile(String),
-    Changes(Vec<DiffChange>),
+/// Displays the file as a unified diff, from the existing file to the generated one
+impl std::fmt::Display for FileReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self.path.display().to_string();
+        let (old, new) = match self.status {
+            FileStatus::Missing => ("/dev/null", path.as_str()),
+            FileStatus::Orphaned => (path.as_str(), "/dev/null"),
+            FileStatus::UpToDate | FileStatus::Outdated => (path.as_str(), path.as_str()),
+        };
+        write!(f, "--- {}\n+++ {}", old, new)?;
+        self.hunks
+            .iter()
+            .try_for_each(|hunk| write!(f, "\n{}", hunk))?;
+        self.changes
+            .iter()
+            .try_for_each(|change| write!(f, "\n{}", change))
+    }
 }
 
-struct FileChange {
-    path: PathBuf,
-    diff: DiffPatch,
-}
+/// Computes the hunks that turn `existing` into `generated`
+pub fn diff_hunks(existing: &str, generated: &str) -> Vec<DiffHunk> {
+    // Each line, along with the number of old and new lines before it
+    let mut old_line = 0;
+    let mut new_line = 0;
+    let existing_lines: Vec<&str> = existing.lines().collect();
+    let generated_lines: Vec<&str> = generated.lines().collect();
+    let lines: Vec<(usize, usize, DiffLine)> = diff::slice(&existing_lines, &generated_lines)
+        .into_iter()
+        .map(|result| {
+            let position = (old_line, new_line);
+            let line = match result {
+                diff::Result::Both(line, _) => {
+                    old_line += 1;
+                    new_line += 1;
+                    DiffLine::Context(line.to_string())
+                }
+                diff::Result::Left(line) => {
+                    old_line += 1;
+                    DiffLine::Removed(line.to_string())
+                }
+                diff::Result::Right(line) => {
+                    new_line += 1;
+                    DiffLine::Added(line.to_string())
+                }
+            };
+            (position.0, position.1, line)
+        })
+        .collect();
 
-impl std::fmt::Display for FileChange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        // This could maybe use a bit of a cleanup to display the change in a prettier way
-        let path_str = self.path.to_str().expect("Path was not valid UTF8");
-        match &self.diff {
-            DiffPatch::NewFile(contents) => {
-                write!(
-                    f,
-                    "NEW MODULE {} DOES NOT EXIST IN THE BINDINGS YET:\n{}",
-                    path_str, &contents
-                )?;
-            }
-            DiffPatch::Changes(changes) => {
-                write!(f, "MODULE {} IS NOT UP TO DATE:", path_str)?;
-                changes.iter().try_for_each(|c| write!(f, "{}", c))?;
-            }
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, line))| !matches!(line, DiffLine::Context(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut hunks = Vec::new();
+    let mut index = 0;
+    while index < changes.len() {
+        let start = changes[index].saturating_sub(CONTEXT_LINES);
+        let mut last_change = changes[index];
+        // Changes separated by few enough unchanged lines share their context
+        while index + 1 < changes.len() && changes[index + 1] - last_change <= 2 * CONTEXT_LINES + 1
+        {
+            index += 1;
+            last_change = changes[index];
         }
-        Ok(())
+        let end = (last_change + CONTEXT_LINES + 1).min(lines.len());
+        hunks.push(make_hunk(&lines[start..end]));
+        index += 1;
+    }
+    hunks
+}
+
+fn make_hunk(lines: &[(usize, usize, DiffLine)]) -> DiffHunk {
+    let (old_before, new_before, _) = lines[0];
+    let old_lines = lines
+        .iter()
+        .filter(|(_, _, line)| !matches!(line, DiffLine::Added(_)))
+        .count();
+    let new_lines = lines
+        .iter()
+        .filter(|(_, _, line)| !matches!(line, DiffLine::Removed(_)))
+        .count();
+    let start = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+    DiffHunk {
+        old_start: start(old_before, old_lines),
+        old_lines,
+        new_start: start(new_before, new_lines),
+        new_lines,
+        lines: lines.iter().map(|(_, _, line)| line.clone()).collect(),
     }
 }
 
 impl Exporter for CheckExport {
     type Error = TsExportError;
+    type Output = CheckReport;
 
     fn export_module(&mut self, process_result: ModuleStepResultData) -> Result<(), TsExportError> {
         let path =
             get_output_file_path(&process_result, &self.default_module_name, &self.root_path);
-        log::info!("Comparing module at {:?}", path);
-
-        let generated_file_contents = get_file_contents(process_result, &self.header_comment);
-
-        match std::fs::File::open(&path) {
-            Ok(mut file) => {
-                let mut file_contents = String::new();
-                file.read_to_string(&mut file_contents)?;
-                let file_diff = diff::lines(&generated_file_contents, &file_contents);
-                let changes: Vec<DiffChange> = file_diff
-                    .into_iter()
-                    .enumerate()
-                    .filter_map(|(line, dif)| match dif {
-                        diff::Result::Both(_, _) => None,
-                        diff::Result::Left(l) => Some(DiffChange {
-                            line,
-                            kind: DiffKind::Added(l.to_string()),
-                        }),
-                        diff::Result::Right(r) => Some(DiffChange {
-                            line,
-                            kind: DiffKind::Removed(r.to_string()),
-                        }),
-                    })
-                    .collect();
-                if !changes.is_empty() {
-                    self.patches.insert(path, DiffPatch::Changes(changes));
-                }
-
+        match &mut self.barrels {
+            Some(modules) => {
+                modules.push((path, process_result));
                 Ok(())
             }
-            Err(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => {
-                    self.patches
-                        .insert(path, DiffPatch::NewFile(generated_file_contents));
-                    Ok(())
-                }
-                _ => Err(e.into()),
-            },
+            None => self.check_module(path, process_result),
         }
     }
 
-    fn finish(self) {
-        let is_ok = self.patches.is_empty();
-        self.patches
-            .into_iter()
-            .for_each(|(path, diff)| log::error!("{}", FileChange { path, diff }));
-
-        if !is_ok {
-            std::process::exit(1);
+    fn finish(mut self) -> Result<CheckReport, TsExportError> {
+        self.check_barrels()?;
+        if let Some(previous) = Manifest::read(&self.root_path)? {
+            for path in previous.missing_from(&self.manifest, &self.root_path) {
+                let file_contents = match std::fs::read_to_string(&path) {
+                    Ok(file_contents) => file_contents,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                self.files.push(FileReport {
+                    path,
+                    status: FileStatus::Orphaned,
+                    hunks: diff_hunks(&file_contents, ""),
+                    changes: Vec::new(),
+                });
+            }
         }
+
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(CheckReport { files: self.files })
+    }
+
+    /// The files of the modules that failed are not reported as orphaned
+    fn finish_incomplete(mut self) -> Result<CheckReport, TsExportError> {
+        self.check_barrels()?;
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(CheckReport { files: self.files })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{diff_hunks, DiffHunk, DiffLine, FileReport, FileStatus};
+    use std::path::PathBuf;
+
+    fn numbered_lines(count: usize) -> Vec<String> {
+        (1..=count).map(|line| format!("line {}", line)).collect()
+    }
+
+    #[test]
+    fn should_group_changes_in_hunks() {
+        let existing = numbered_lines(20);
+        let mut generated = existing.clone();
+        generated[1] = "changed 2".to_string();
+        generated[3] = "changed 4".to_string();
+        generated.remove(15);
+
+        let hunks = diff_hunks(&existing.join("\n"), &generated.join("\n"));
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            (
+                hunks[0].old_start,
+                hunks[0].old_lines,
+                hunks[0].new_start,
+                hunks[0].new_lines
+            ),
+            (1, 7, 1, 7)
+        );
+        assert_eq!(
+            hunks[1],
+            DiffHunk {
+                old_start: 13,
+                old_lines: 7,
+                new_start: 13,
+                new_lines: 6,
+                lines: vec![
+                    DiffLine::Context("line 13".to_string()),
+                    DiffLine::Context("line 14".to_string()),
+                    DiffLine::Context("line 15".to_string()),
+                    DiffLine::Removed("line 16".to_string()),
+                    DiffLine::Context("line 17".to_string()),
+                    DiffLine::Context("line 18".to_string()),
+                    DiffLine::Context("line 19".to_string()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn should_display_a_unified_diff() {
+        let report = FileReport {
+            path: PathBuf::from("out/index.ts"),
+            status: FileStatus::Missing,
+            hunks: diff_hunks("", "export type A = number;\nexport type B = string;\n"),
+            changes: Vec::new(),
+        };
+        assert_eq!(
+            report.to_string(),
+            "--- /dev/null\n+++ out/index.ts\n@@ -0,0 +1,2 @@\n+export type A = number;\n+export type B = string;"
+        );
+        assert_eq!(
+            serde_json::to_value(&report.hunks[0].lines[0]).unwrap(),
+            serde_json::json!({ "kind": "added", "text": "export type A = number;" })
+        );
+    }
+
+    #[test]
+    fn should_find_no_hunks_in_identical_files() {
+        let contents = numbered_lines(5).join("\n");
+        assert!(diff_hunks(&contents, &contents).is_empty());
+        assert_eq!(diff_hunks("", "export {};")[0].old_start, 0);
     }
 }
@@ -1,18 +1,31 @@
 use super::Exporter;
 use crate::error::TsExportError;
 use crate::exporters::utils::{get_file_contents, get_output_file_path};
-use crate::exporters::HeaderComment;
+use crate::exporters::{
+    barrel::add_barrels,
+    manifest::{Manifest, MANIFEST_FILE_NAME},
+    HeaderComment,
+};
 use crate::{pipeline::module_step::ModuleStepResultData, utils::display_path::DisplayPath};
 use std::{
+    ffi::OsString,
     io::Write,
     path::{Path, PathBuf},
 };
+use ts_json_subset::printer::PrintConfig;
 
 /// A strategy that will output a TS file given its path
+///
+/// The generated files are listed in a [Manifest] written to the root path when done.
 pub struct FileExporter {
     root_path: PathBuf,
     default_module_name: Option<String>,
     header_comment: HeaderComment,
+    print_config: Option<PrintConfig>,
+    prune: bool,
+    barrels: Option<Vec<(PathBuf, ModuleStepResultData)>>,
+    js_extension: bool,
+    manifest: Manifest,
 }
 
 impl Default for FileExporter {
@@ -22,6 +35,11 @@ impl Default for FileExporter {
             root_path,
             default_module_name: None,
             header_comment: HeaderComment::Standard,
+            print_config: None,
+            prune: false,
+            barrels: None,
+            js_extension: false,
+            manifest: Manifest::default(),
         }
     }
 }
@@ -32,6 +50,11 @@ impl FileExporter {
             root_path: path,
             default_module_name: None,
             header_comment: HeaderComment::Standard,
+            print_config: None,
+            prune: false,
+            barrels: None,
+            js_extension: false,
+            manifest: Manifest::default(),
         }
     }
 
@@ -51,32 +74,237 @@ impl FileExporter {
     pub fn set_header_comment(&mut self, header_comment: HeaderComment) {
         self.header_comment = header_comment;
     }
+
+    /// Prints the statements with the given style instead of the default one
+    pub fn set_print_config(&mut self, print_config: PrintConfig) {
+        self.print_config = Some(print_config);
+    }
+
+    /// Deletes the files listed in the previous manifest that are no longer generated
+    pub fn set_prune(&mut self, prune: bool) {
+        self.prune = prune;
+    }
+
+    /// Also outputs an `index.ts` barrel per directory that reexports its modules, see [add_barrels].
+    ///
+    /// The modules are then written when done, as the barrels depend on all of them.
+    pub fn set_barrels(&mut self, barrels: bool) {
+        self.barrels = if barrels { Some(Vec::new()) } else { None };
+    }
+
+    /// Reexports the modules with the `.js` extension in the barrels, along with [ImportStrategy::Relative]
+    ///
+    /// [ImportStrategy::Relative]: crate::pipeline::imports::ImportStrategy::Relative
+    pub fn set_js_extension(&mut self, js_extension: bool) {
+        self.js_extension = js_extension;
+    }
+
+    fn write_module(
+        &mut self,
+        path: PathBuf,
+        process_result: ModuleStepResultData,
+    ) -> Result<(), TsExportError> {
+        let file_contents = get_file_contents(
+            process_result,
+            &self.header_comment,
+            self.print_config.as_ref(),
+        );
+        self.manifest.insert(&self.root_path, &path);
+        if write_if_changed(&path, &file_contents)? {
+            log::info!("Outputting module at {:?}", path);
+        } else {
+            log::info!("Module at {:?} is unchanged", path);
+        }
+        Ok(())
+    }
+
+    fn write_barrels(&mut self) -> Result<(), TsExportError> {
+        if let Some(modules) = self.barrels.take() {
+            for (path, process_result) in add_barrels(&self.root_path, modules, self.js_extension)?
+            {
+                self.write_module(path, process_result)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_manifest(&self) -> Result<(), TsExportError> {
+        let previous = Manifest::read(&self.root_path)?;
+        if self.prune {
+            let stale_files = previous
+                .map(|previous| previous.missing_from(&self.manifest, &self.root_path))
+                .unwrap_or_default();
+            for path in stale_files {
+                log::info!("Deleting the stale module at {:?}", path);
+                match std::fs::remove_file(&path) {
+                    Ok(_) => remove_empty_parents(&path, &self.root_path),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        write_if_changed(
+            &self.root_path.join(MANIFEST_FILE_NAME),
+            &self.manifest.render(),
+        )?;
+        Ok(())
+    }
+}
+
+/// Removes the directories left empty by a deleted file, up to the root path
+fn remove_empty_parents(path: &Path, root_path: &Path) {
+    let mut directory = path.parent();
+    while let Some(current) = directory {
+        if current == root_path || std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        directory = current.parent();
+    }
 }
 
 impl Exporter for FileExporter {
     type Error = TsExportError;
+    type Output = ();
 
     fn export_module(&mut self, process_result: ModuleStepResultData) -> Result<(), TsExportError> {
         log::info!("Exporting module {}", DisplayPath(&process_result.path));
         let path =
             get_output_file_path(&process_result, &self.default_module_name, &self.root_path);
 
-        log::info!("Outputting module at {:?}", path);
-        let file_contents = get_file_contents(process_result, &self.header_comment);
-
-        if let Err(e) =
-            std::fs::create_dir_all(path.parent().expect("Failed to get dir of output module"))
-        {
-            match e.kind() {
-                std::io::ErrorKind::AlreadyExists => (),
-                _ => panic!("{}", e),
+        match &mut self.barrels {
+            Some(modules) => {
+                modules.push((path, process_result));
+                Ok(())
             }
+            None => self.write_module(path, process_result),
         }
+    }
 
-        let mut file = std::fs::File::create(&path).expect("Failed to open file");
-        file.write_all(file_contents.as_bytes())
-            .expect("Failed to write");
+    fn finish(mut self) -> Result<(), TsExportError> {
+        self.write_barrels()?;
+        self.write_manifest()
+    }
 
-        Ok(())
+    /// The previous manifest is kept, so that the files of the modules that failed are neither pruned now nor
+    /// forgotten by the next run
+    fn finish_incomplete(mut self) -> Result<(), TsExportError> {
+        log::warn!("Some modules failed to export, the stale files are not pruned");
+        self.write_barrels()
+    }
+}
+
+/// Writes the file unless it already has the given contents, so that its mtime only changes along with it.
+///
+/// The contents are written to a temporary file that is then renamed, so that the file is never seen half-written.
+/// Returns whether the file was written.
+pub(crate) fn write_if_changed(path: &Path, contents: &str) -> Result<bool, TsExportError> {
+    match std::fs::read(path) {
+        Ok(existing) if existing == contents.as_bytes() => return Ok(false),
+        Ok(_) => (),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let written = std::fs::File::create(&temp_path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{write_if_changed, FileExporter};
+    use crate::{
+        exporters::{manifest::Manifest, Exporter},
+        utils::temp_dir::TempDir,
+    };
+
+    #[test]
+    fn should_only_write_changed_files() {
+        let temp_dir = TempDir::new("should_only_write_changed_files");
+        let directory = temp_dir.path().to_path_buf();
+        let path = directory.join("nested").join("module.ts");
+
+        assert!(write_if_changed(&path, "export type A = number;\n").unwrap());
+        assert!(!write_if_changed(&path, "export type A = number;\n").unwrap());
+        assert!(write_if_changed(&path, "export type A = string;\n").unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "export type A = string;\n"
+        );
+
+        // Only the module is left, no temporary file
+        let entries = std::fs::read_dir(directory.join("nested")).unwrap().count();
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn should_prune_the_stale_files() {
+        let temp_dir = TempDir::new("should_prune_the_stale_files");
+        let directory = temp_dir.path().to_path_buf();
+        let kept = directory.join("index.ts");
+        let stale = directory.join("models").join("user.ts");
+        let handwritten = directory.join("handwritten.ts");
+        for path in [&kept, &stale, &handwritten] {
+            write_if_changed(path, "export {};\n").unwrap();
+        }
+
+        let mut previous = FileExporter::new(directory.clone());
+        previous.manifest.insert(&directory, &kept);
+        previous.manifest.insert(&directory, &stale);
+        previous.finish().unwrap();
+
+        let mut current = FileExporter::new(directory.clone());
+        current.set_prune(true);
+        current.manifest.insert(&directory, &kept);
+        current.finish().unwrap();
+
+        assert!(kept.exists());
+        assert!(handwritten.exists());
+        assert!(!stale.exists());
+        assert!(!directory.join("models").exists());
+        assert_eq!(
+            Manifest::read(&directory).unwrap().unwrap().files,
+            vec!["index.ts".to_string()].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn should_not_prune_when_incomplete() {
+        let temp_dir = TempDir::new("should_not_prune_when_incomplete");
+        let directory = temp_dir.path().to_path_buf();
+        let kept = directory.join("index.ts");
+        let failed = directory.join("models").join("user.ts");
+        for path in [&kept, &failed] {
+            write_if_changed(path, "export {};\n").unwrap();
+        }
+
+        let mut previous = FileExporter::new(directory.clone());
+        previous.manifest.insert(&directory, &kept);
+        previous.manifest.insert(&directory, &failed);
+        previous.finish().unwrap();
+
+        let mut current = FileExporter::new(directory.clone());
+        current.set_prune(true);
+        current.manifest.insert(&directory, &kept);
+        current.finish_incomplete().unwrap();
+
+        assert!(failed.exists());
+        assert_eq!(Manifest::read(&directory).unwrap().unwrap().files.len(), 2);
     }
 }
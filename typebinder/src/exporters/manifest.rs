@@ -0,0 +1,101 @@
+//! The list of the files generated in an output folder
+//!
+//! The manifest is written next to the generated files, so that the files that are no longer generated
+//! (e.g. when a Rust module is deleted or renamed) can be told apart from the files written by hand.
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::TsExportError;
+
+/// Name of the manifest file, in the output root
+pub const MANIFEST_FILE_NAME: &str = ".typebinder-manifest.json";
+
+/// The files generated in an output folder, relative to it and with `/` separators
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: BTreeSet<String>,
+}
+
+impl Manifest {
+    /// Reads the manifest of an output folder, if there is one
+    pub fn read(root_path: &Path) -> Result<Option<Manifest>, TsExportError> {
+        match std::fs::read_to_string(root_path.join(MANIFEST_FILE_NAME)) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The contents of the manifest file
+    pub fn render(&self) -> String {
+        let mut contents =
+            serde_json::to_string_pretty(self).expect("Failed to serialize the manifest");
+        contents.push('\n');
+        contents
+    }
+
+    /// Adds a generated file, given its full path
+    pub fn insert(&mut self, root_path: &Path, path: &Path) {
+        if let Some(relative) = relative_path(root_path, path) {
+            self.files.insert(relative);
+        }
+    }
+
+    /// The files of the manifest that are not in `other`, as full paths
+    pub fn missing_from(&self, other: &Manifest, root_path: &Path) -> Vec<PathBuf> {
+        self.files
+            .difference(&other.files)
+            .filter_map(|relative| full_path(root_path, relative))
+            .collect()
+    }
+}
+
+fn relative_path(root_path: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root_path).ok()?;
+    let segments: Option<Vec<&str>> = relative
+        .components()
+        .map(|component| match component {
+            Component::Normal(segment) => segment.to_str(),
+            _ => None,
+        })
+        .collect();
+    segments.map(|segments| segments.join("/"))
+}
+
+/// The full path of a manifest entry. Entries that would point outside of the root are ignored.
+fn full_path(root_path: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| root_path.join(relative))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::Manifest;
+
+    #[test]
+    fn should_list_the_files_no_longer_generated() {
+        let root = Path::new("/out");
+        let mut previous = Manifest::default();
+        previous.insert(root, &root.join("models").join("user.ts"));
+        previous.insert(root, &root.join("index.ts"));
+        previous.insert(root, Path::new("/elsewhere/index.ts"));
+        previous.files.insert("../escape.ts".to_string());
+
+        let mut current = Manifest::default();
+        current.insert(root, &root.join("index.ts"));
+
+        assert_eq!(
+            previous.missing_from(&current, root),
+            vec![PathBuf::from("/out/models/user.ts")]
+        );
+    }
+}
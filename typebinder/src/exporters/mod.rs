@@ -4,23 +4,38 @@ use crate::{
     utils::display_path::DisplayPath,
 };
 
+pub(crate) mod barrel;
+pub mod bundle;
 pub mod check;
 pub mod file;
+pub mod manifest;
+pub mod semantic;
 pub mod stdout;
 pub mod utils;
 
 /// An abstraction that specifies the behaviour of how to handle a resulting process' data
 pub trait Exporter {
     type Error: Into<TsExportError>;
+    /// What the exporter gives back when done, e.g. a report
+    type Output;
 
     /// Consumes the process result to do something with it
     fn export_module(&mut self, process_result: ModuleStepResultData) -> Result<(), Self::Error>;
 
     /// Called when the exporter's process is done
-    fn finish(self)
+    fn finish(self) -> Result<Self::Output, Self::Error>
+    where
+        Self: Sized;
+
+    /// Called instead of [Exporter::finish] when some declarations or modules failed to export, see
+    /// [PipelineConfig::collect_errors](crate::pipeline::config::PipelineConfig::collect_errors).
+    ///
+    /// The exported modules are incomplete : the exporter must not act upon the ones that are missing, e.g. delete their files.
+    fn finish_incomplete(self) -> Result<Self::Output, Self::Error>
     where
         Self: Sized,
     {
+        self.finish()
     }
 }
 
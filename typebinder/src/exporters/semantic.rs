@@ -0,0 +1,668 @@
+//! Structural comparison of TS modules, that ignores their formatting
+//!
+//! The existing file is parsed back into the `ts_json_subset` AST, and its declarations are compared by name
+//! with the generated ones. Types are compared once normalized : parentheses are dropped, unions and
+//! intersections are flattened, quotes are dropped from property names and members are compared regardless
+//! of their order.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use serde::Serialize;
+use ts_json_subset::{
+    declarations::{
+        const_enum::ConstEnumDeclaration, interface::InterfaceDeclaration,
+        type_alias::TypeAliasDeclaration,
+    },
+    export::ExportStatement,
+    ident::TSIdent,
+    import::{ImportKind, ImportStatement},
+    parser::{parse_module, parse_type, Statement},
+    types::{
+        ArrayType, ArrayTypeInner, IntersectionType, ObjectType, PrimaryType, PropertyName,
+        PropertySignature, TsType, TupleType, TypeArguments, TypeBody, TypeMember, TypeReference,
+        UnionType,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+/// A difference between the existing file and the generated module
+pub enum SemanticChange {
+    AddedDeclaration {
+        name: String,
+        declaration: String,
+    },
+    RemovedDeclaration {
+        name: String,
+        declaration: String,
+    },
+    ChangedDeclaration {
+        name: String,
+        existing: String,
+        generated: String,
+    },
+    AddedField {
+        declaration: String,
+        field: String,
+        signature: String,
+    },
+    RemovedField {
+        declaration: String,
+        field: String,
+        signature: String,
+    },
+    ChangedField {
+        declaration: String,
+        field: String,
+        existing: String,
+        generated: String,
+    },
+    AddedImport {
+        name: String,
+        path: String,
+        /// Imported with `import type`
+        type_only: bool,
+    },
+    RemovedImport {
+        name: String,
+        path: String,
+        type_only: bool,
+    },
+    /// The existing file could not be parsed
+    InvalidFile {
+        error: String,
+    },
+}
+
+impl std::fmt::Display for SemanticChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticChange::AddedDeclaration { declaration, .. } => write!(f, "+ {}", declaration),
+            SemanticChange::RemovedDeclaration { declaration, .. } => {
+                write!(f, "- {}", declaration)
+            }
+            SemanticChange::ChangedDeclaration {
+                existing,
+                generated,
+                ..
+            } => write!(f, "- {}\n+ {}", existing, generated),
+            SemanticChange::AddedField {
+                declaration,
+                signature,
+                ..
+            } => write!(f, "+ {}.{}", declaration, signature),
+            SemanticChange::RemovedField {
+                declaration,
+                signature,
+                ..
+            } => write!(f, "- {}.{}", declaration, signature),
+            SemanticChange::ChangedField {
+                declaration,
+                existing,
+                generated,
+                ..
+            } => write!(
+                f,
+                "- {}.{}\n+ {}.{}",
+                declaration, existing, declaration, generated
+            ),
+            SemanticChange::AddedImport {
+                name,
+                path,
+                type_only,
+            } => write!(f, "+ {}", import_line(name, path, *type_only)),
+            SemanticChange::RemovedImport {
+                name,
+                path,
+                type_only,
+            } => write!(f, "- {}", import_line(name, path, *type_only)),
+            SemanticChange::InvalidFile { error } => write!(f, "- invalid file: {}", error),
+        }
+    }
+}
+
+fn import_line(name: &str, path: &str, type_only: bool) -> String {
+    let kind = if type_only { "import type" } else { "import" };
+    format!("{} {{ {} }} from {}", kind, name, path)
+}
+
+/// Compares the existing contents of a file with the generated statements
+pub fn semantic_changes(
+    existing: &str,
+    imports: &[ImportStatement],
+    exports: &[ExportStatement],
+) -> Vec<SemanticChange> {
+    let statements = match parse_module(existing) {
+        Ok(statements) => statements,
+        Err(error) => {
+            return vec![SemanticChange::InvalidFile {
+                error: error.to_string(),
+            }]
+        }
+    };
+    let mut existing_imports = Vec::new();
+    let mut existing_exports = Vec::new();
+    for statement in statements {
+        match statement {
+            Statement::Import(import) => existing_imports.push(import),
+            Statement::Export(export) => existing_exports.push(export),
+        }
+    }
+
+    let mut changes = Vec::new();
+    compare_imports(&existing_imports, imports, &mut changes);
+    compare_declarations(&existing_exports, exports, &mut changes);
+    changes
+}
+
+fn compare_imports(
+    existing: &[ImportStatement],
+    generated: &[ImportStatement],
+    changes: &mut Vec<SemanticChange>,
+) {
+    let existing = imported_names(existing);
+    let generated = imported_names(generated);
+    changes.extend(
+        existing
+            .difference(&generated)
+            .map(|(path, name, type_only)| SemanticChange::RemovedImport {
+                name: name.clone(),
+                path: path.clone(),
+                type_only: *type_only,
+            }),
+    );
+    changes.extend(
+        generated
+            .difference(&existing)
+            .map(|(path, name, type_only)| SemanticChange::AddedImport {
+                name: name.clone(),
+                path: path.clone(),
+                type_only: *type_only,
+            }),
+    );
+}
+
+/// The `(path, name, type_only)` triples of the imports
+fn imported_names(imports: &[ImportStatement]) -> BTreeSet<(String, String, bool)> {
+    imports
+        .iter()
+        .flat_map(|import| {
+            let names = match &import.import_kind {
+                ImportKind::Identifier(ident) => vec![ident.to_string()],
+                ImportKind::GlobAsIdentifier(ident) => vec![format!("* as {}", ident)],
+                ImportKind::ImportList(list) => {
+                    list.items.iter().map(ToString::to_string).collect()
+                }
+            };
+            names
+                .into_iter()
+                .map(move |name| (import.path.clone(), name, import.type_only))
+        })
+        .collect()
+}
+
+/// A declaration, once normalized
+#[derive(Debug, PartialEq)]
+enum Declaration {
+    /// An interface or an alias of an object type
+    Object {
+        header: String,
+        fields: BTreeMap<String, String>,
+    },
+    Alias {
+        header: String,
+        inner_type: TsType,
+    },
+    ConstEnum {
+        fields: BTreeMap<String, String>,
+    },
+    Reexport {
+        scope: String,
+    },
+    /// Named after the module it reexports, e.g. `* from "./user"` or `* as user from "./user"`
+    ExportAll,
+}
+
+impl Declaration {
+    /// A one-line description of the declaration, when it is added or removed
+    fn describe(&self, name: &str) -> String {
+        match self {
+            Declaration::Object { header, fields } => format!(
+                "{} {{ {} }}",
+                header,
+                fields.values().cloned().collect::<Vec<_>>().join(", ")
+            ),
+            Declaration::Alias { header, inner_type } => {
+                format!("{} = {}", header, one_line(inner_type))
+            }
+            Declaration::ConstEnum { fields } => format!(
+                "const enum {} {{ {} }}",
+                name,
+                fields.values().cloned().collect::<Vec<_>>().join(", ")
+            ),
+            Declaration::Reexport { scope } => format!("{{ {} as {} }}", scope, name),
+            Declaration::ExportAll => name.to_string(),
+        }
+    }
+}
+
+fn compare_declarations(
+    existing: &[ExportStatement],
+    generated: &[ExportStatement],
+    changes: &mut Vec<SemanticChange>,
+) {
+    let existing = declarations(existing);
+    let generated = declarations(generated);
+
+    for (name, declaration) in existing.iter() {
+        if !generated.contains_key(name) {
+            changes.push(SemanticChange::RemovedDeclaration {
+                name: name.clone(),
+                declaration: declaration.describe(name),
+            });
+        }
+    }
+    for (name, declaration) in generated.iter() {
+        let existing_declaration = match existing.get(name) {
+            Some(existing_declaration) => existing_declaration,
+            None => {
+                changes.push(SemanticChange::AddedDeclaration {
+                    name: name.clone(),
+                    declaration: declaration.describe(name),
+                });
+                continue;
+            }
+        };
+        match (existing_declaration, declaration) {
+            (
+                Declaration::Object {
+                    header: existing_header,
+                    fields: existing_fields,
+                },
+                Declaration::Object { header, fields },
+            ) if existing_header == header => {
+                compare_fields(name, existing_fields, fields, changes)
+            }
+            (
+                Declaration::ConstEnum {
+                    fields: existing_fields,
+                },
+                Declaration::ConstEnum { fields },
+            ) => compare_fields(name, existing_fields, fields, changes),
+            (existing_declaration, declaration) if existing_declaration != declaration => changes
+                .push(SemanticChange::ChangedDeclaration {
+                    name: name.clone(),
+                    existing: existing_declaration.describe(name),
+                    generated: declaration.describe(name),
+                }),
+            _ => (),
+        }
+    }
+}
+
+fn compare_fields(
+    declaration: &str,
+    existing: &BTreeMap<String, String>,
+    generated: &BTreeMap<String, String>,
+    changes: &mut Vec<SemanticChange>,
+) {
+    for (field, signature) in existing.iter() {
+        if !generated.contains_key(field) {
+            changes.push(SemanticChange::RemovedField {
+                declaration: declaration.to_string(),
+                field: field.clone(),
+                signature: signature.clone(),
+            });
+        }
+    }
+    for (field, signature) in generated.iter() {
+        match existing.get(field) {
+            None => changes.push(SemanticChange::AddedField {
+                declaration: declaration.to_string(),
+                field: field.clone(),
+                signature: signature.clone(),
+            }),
+            Some(existing_signature) if existing_signature != signature => {
+                changes.push(SemanticChange::ChangedField {
+                    declaration: declaration.to_string(),
+                    field: field.clone(),
+                    existing: existing_signature.clone(),
+                    generated: signature.clone(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+}
+
+fn declarations(exports: &[ExportStatement]) -> BTreeMap<String, Declaration> {
+    let mut declarations = BTreeMap::new();
+    for export in exports {
+        match export {
+            ExportStatement::InterfaceDeclaration(declaration) => {
+                declarations.insert(declaration.ident.to_string(), interface(declaration));
+            }
+            ExportStatement::TypeAliasDeclaration(declaration) => {
+                declarations.insert(declaration.ident.to_string(), type_alias(declaration));
+            }
+            ExportStatement::ConstEnumDeclaration(declaration) => {
+                declarations.insert(declaration.ident.to_string(), const_enum(declaration));
+            }
+            ExportStatement::ReexportDeclaration(declaration) => {
+                for clause in declaration.reexports.iter() {
+                    declarations.insert(
+                        clause.export_as.to_string(),
+                        Declaration::Reexport {
+                            scope: clause.scope.to_string(),
+                        },
+                    );
+                }
+            }
+            ExportStatement::ExportAllDeclaration(declaration) => {
+                let namespace = declaration
+                    .namespace
+                    .as_ref()
+                    .map(|namespace| format!("as {} ", namespace))
+                    .unwrap_or_default();
+                declarations.insert(
+                    format!("* {}from {}", namespace, declaration.path),
+                    Declaration::ExportAll,
+                );
+            }
+        }
+    }
+    declarations
+}
+
+fn interface(declaration: &InterfaceDeclaration) -> Declaration {
+    let mut header = format!(
+        "interface {}{}",
+        declaration.ident,
+        declaration
+            .type_params
+            .as_ref()
+            .map(one_line)
+            .unwrap_or_default()
+    );
+    if let Some(extends_clause) = &declaration.extends_clause {
+        let extended: Vec<String> = extends_clause
+            .type_list
+            .identifiers
+            .iter()
+            .map(|reference| one_line(&normalize_reference(reference)))
+            .collect();
+        header = format!("{} extends {}", header, extended.join(", "));
+    }
+    Declaration::Object {
+        header,
+        fields: object_fields(&declaration.obj_type),
+    }
+}
+
+fn type_alias(declaration: &TypeAliasDeclaration) -> Declaration {
+    let header = format!(
+        "type {}{}",
+        declaration.ident,
+        declaration
+            .type_params
+            .as_ref()
+            .map(one_line)
+            .unwrap_or_default()
+    );
+    match normalize_type(&declaration.inner_type) {
+        TsType::PrimaryType(PrimaryType::ObjectType(obj_type)) => Declaration::Object {
+            header,
+            fields: object_fields(&obj_type),
+        },
+        inner_type => Declaration::Alias { header, inner_type },
+    }
+}
+
+fn const_enum(declaration: &ConstEnumDeclaration) -> Declaration {
+    Declaration::ConstEnum {
+        fields: declaration
+            .body
+            .variants
+            .iter()
+            .map(|variant| (variant.ident.to_string(), one_line(variant)))
+            .collect(),
+    }
+}
+
+/// The normalized signature of each field of an object, by name
+fn object_fields(obj_type: &ObjectType) -> BTreeMap<String, String> {
+    obj_type
+        .body
+        .members
+        .iter()
+        .map(|member| match normalize_member(member) {
+            TypeMember::PropertySignature(property) => {
+                (property.name.to_string(), one_line(&property))
+            }
+        })
+        .collect()
+}
+
+/// Displays a type on a single line
+pub(crate) fn one_line<T: std::fmt::Display>(value: &T) -> String {
+    value
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalizes a type, so that two types that only differ by their formatting are equal.
+///
+/// Unions and intersections of a single type are replaced by this type, as they are displayed the same.
+pub(crate) fn normalize_type(ts_type: &TsType) -> TsType {
+    match ts_type {
+        TsType::ParenthesizedType(parenthesized) => normalize_type(&parenthesized.inner),
+        TsType::UnionType(union) => {
+            let mut types = Vec::new();
+            for ts_type in union.types.iter() {
+                match normalize_type(ts_type) {
+                    TsType::UnionType(inner) => types.extend(inner.types),
+                    ts_type => types.push(ts_type),
+                }
+            }
+            if types.len() == 1 {
+                return types.remove(0);
+            }
+            UnionType { types }.into()
+        }
+        TsType::IntersectionType(intersection) => {
+            let mut types = Vec::new();
+            for ts_type in intersection.types.iter() {
+                match normalize_type(ts_type) {
+                    TsType::IntersectionType(inner) => types.extend(inner.types),
+                    ts_type => types.push(ts_type),
+                }
+            }
+            if types.len() == 1 {
+                return types.remove(0);
+            }
+            IntersectionType { types }.into()
+        }
+        TsType::PrimaryType(primary) => normalize_primary(primary),
+    }
+}
+
+fn normalize_primary(primary: &PrimaryType) -> TsType {
+    let normalized: PrimaryType = match primary {
+        // A type written by hand is compared as parsed, when it is part of the subset
+        PrimaryType::Verbatim(verbatim) => {
+            return match parse_type(verbatim) {
+                Ok(ts_type) if ts_type != TsType::PrimaryType(primary.clone()) => {
+                    normalize_type(&ts_type)
+                }
+                _ => primary.clone().into(),
+            }
+        }
+        PrimaryType::TypeReference(reference) => normalize_reference(reference).into(),
+        PrimaryType::ObjectType(obj_type) => {
+            let mut members: Vec<TypeMember> =
+                obj_type.body.members.iter().map(normalize_member).collect();
+            members.sort_by_key(|member| match member {
+                TypeMember::PropertySignature(property) => property.name.to_string(),
+            });
+            ObjectType {
+                body: TypeBody { members },
+            }
+            .into()
+        }
+        PrimaryType::ArrayType(array) => {
+            let inner = match array.inner_type.as_ref() {
+                ArrayTypeInner::Primary(primary) => TsType::PrimaryType(primary.clone()),
+                ArrayTypeInner::Parenthesized(parenthesized) => {
+                    TsType::ParenthesizedType(parenthesized.clone())
+                }
+            };
+            ArrayType::new(normalize_type(&inner)).into()
+        }
+        PrimaryType::TupleType(tuple) => TupleType {
+            inner_types: tuple.inner_types.iter().map(normalize_type).collect(),
+        }
+        .into(),
+        PrimaryType::Predefined(_) | PrimaryType::LiteralType(_) => primary.clone(),
+    };
+    normalized.into()
+}
+
+fn normalize_reference(reference: &TypeReference) -> TypeReference {
+    TypeReference {
+        name: reference.name.clone(),
+        args: reference.args.as_ref().map(|args| TypeArguments {
+            types: args.types.iter().map(normalize_type).collect(),
+        }),
+    }
+}
+
+fn normalize_member(member: &TypeMember) -> TypeMember {
+    match member {
+        TypeMember::PropertySignature(property) => {
+            TypeMember::PropertySignature(PropertySignature {
+                name: normalize_property_name(&property.name),
+                optional: property.optional,
+                inner_type: normalize_type(&property.inner_type),
+            })
+        }
+    }
+}
+
+/// Drops the quotes around property names that don't need them
+fn normalize_property_name(name: &PropertyName) -> PropertyName {
+    match name {
+        PropertyName::StringLiteral(literal) => {
+            let quoted = literal.to_string();
+            match TSIdent::from_str(&quoted[1..quoted.len() - 1]) {
+                Ok(ident) => PropertyName::Identifier(ident),
+                Err(_) => name.clone(),
+            }
+        }
+        PropertyName::Identifier(_) => name.clone(),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{semantic_changes, SemanticChange};
+    use ts_json_subset::parser::{parse_module, Statement};
+
+    fn changes(existing: &str, generated: &str) -> Vec<SemanticChange> {
+        let (imports, exports) = parse_module(generated).unwrap().into_iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut imports, mut exports), statement| {
+                match statement {
+                    Statement::Import(import) => imports.push(import),
+                    Statement::Export(export) => exports.push(export),
+                }
+                (imports, exports)
+            },
+        );
+        semantic_changes(existing, &imports, &exports)
+    }
+
+    const GENERATED: &str = r#"import { Id } from "./common";
+
+export interface User {
+	id: Id,
+	"name": string,
+	nickname?: string | null
+}
+
+export type Event = ({
+	type: "Created"
+} & {
+	id: number
+}) | ({
+	type: "Deleted"
+});
+"#;
+
+    #[test]
+    fn should_ignore_the_formatting() {
+        let prettified = r#"// Formatted with prettier
+import { Id } from './common';
+
+export type Event =
+  | ({ type: 'Created' } & { id: number })
+  | { type: 'Deleted' };
+
+export interface User {
+  nickname?: string | null;
+  id: Id;
+  name: string;
+}
+"#;
+        assert_eq!(changes(prettified, GENERATED), vec![]);
+    }
+
+    #[test]
+    fn should_report_the_changed_declarations_and_fields() {
+        let outdated = r#"
+import { Id, Other } from "./common";
+
+export interface User {
+	id: number,
+	name: string,
+	age: number
+}
+
+export type Removed = string;
+"#;
+        let changes: Vec<String> = changes(outdated, GENERATED)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "- import { Other } from \"./common\"",
+                "- type Removed = string",
+                "+ type Event = { type: \"Created\" } & { id: number } | { type: \"Deleted\" }",
+                "- User.age: number",
+                "- User.id: number\n+ User.id: Id",
+                "+ User.nickname?: string | null",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_the_type_only_imports() {
+        let type_only = GENERATED.replace("import {", "import type {");
+        let changes: Vec<String> = changes(&type_only, GENERATED)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "- import type { Id } from \"./common\"",
+                "+ import { Id } from \"./common\"",
+            ]
+        );
+    }
+}
@@ -1,12 +1,24 @@
 use crate::error::TsExportError;
-use crate::exporters::Exporter;
+use crate::exporters::{utils::render_statements, Exporter};
 use crate::{pipeline::module_step::ModuleStepResultData, utils::display_path::DisplayPath};
+use ts_json_subset::printer::PrintConfig;
 
 /// A strategy that prints the name and the output module's statements to stdout.
-pub struct StdoutExport;
+#[derive(Default)]
+pub struct StdoutExport {
+    print_config: Option<PrintConfig>,
+}
+
+impl StdoutExport {
+    /// Prints the statements with the given style instead of the default one
+    pub fn set_print_config(&mut self, print_config: PrintConfig) {
+        self.print_config = Some(print_config);
+    }
+}
 
 impl Exporter for StdoutExport {
     type Error = TsExportError;
+    type Output = ();
 
     fn export_module(&mut self, process_result: ModuleStepResultData) -> Result<(), TsExportError> {
         println!("//------");
@@ -16,20 +28,18 @@ impl Exporter for StdoutExport {
         }
         println!("// {}", display_path);
         println!("// ------");
-        let output: String = process_result
-            .imports
-            .into_iter()
-            .map(|statement| format!("{}\n", statement))
-            .chain(
-                process_result
-                    .exports
-                    .into_iter()
-                    .map(|stm| stm.to_string()),
-            )
-            .collect();
+        let output = render_statements(
+            &process_result.imports,
+            &process_result.exports,
+            self.print_config.as_ref(),
+        );
 
         println!("{}", output);
 
         Ok(())
     }
+
+    fn finish(self) -> Result<(), TsExportError> {
+        Ok(())
+    }
 }
@@ -1,5 +1,10 @@
 use crate::exporters::HeaderComment;
 use crate::pipeline::module_step::ModuleStepResultData;
+use ts_json_subset::{
+    export::ExportStatement,
+    import::ImportStatement,
+    printer::{Print, PrintConfig},
+};
 
 use std::path::{Path, PathBuf};
 
@@ -31,22 +36,37 @@ pub(crate) fn get_output_file_path(
 pub(crate) fn get_file_contents(
     process_result: ModuleStepResultData,
     header_comment: &HeaderComment,
+    print_config: Option<&PrintConfig>,
 ) -> String {
     let header = header_comment.render(&process_result.path);
-    let main_content: String = process_result
-        .imports
-        .into_iter()
-        .map(|statement| format!("{}\n", statement))
-        .chain(
-            process_result
-                .exports
-                .into_iter()
-                .map(|stm| format!("{}\n", stm)),
-        )
-        .collect();
+    let main_content = render_statements(
+        &process_result.imports,
+        &process_result.exports,
+        print_config,
+    );
 
     match header {
         None => main_content,
         Some(comment) => format!("{}\n\n{}", comment, main_content),
     }
 }
+
+/// Renders a statement per line, with the [PrintConfig] if any
+pub(crate) fn render_statements(
+    imports: &[ImportStatement],
+    exports: &[ExportStatement],
+    print_config: Option<&PrintConfig>,
+) -> String {
+    let imports = imports.iter().map(|statement| match print_config {
+        Some(config) => statement.print(config),
+        None => statement.to_string(),
+    });
+    let exports = exports.iter().map(|statement| match print_config {
+        Some(config) => statement.print(config),
+        None => statement.to_string(),
+    });
+    imports
+        .chain(exports)
+        .map(|statement| format!("{}\n", statement))
+        .collect()
+}
@@ -13,12 +13,16 @@ use error::TsExportError;
 use exporters::stdout::StdoutExport;
 use macros::context::MacroSolvingContext;
 use path_mapper::PathMapper;
-use pipeline::Pipeline;
+use pipeline::{config::PipelineConfig, Pipeline};
 use step_spawner::mod_reader::RustModuleReader;
 
+pub mod attributes;
+pub mod compat;
 pub mod contexts;
+pub mod diagnostics;
 pub mod error;
 pub mod exporters;
+pub mod lints;
 pub mod macros;
 pub mod path_mapper;
 pub mod pipeline;
@@ -26,6 +30,7 @@ pub mod step_spawner;
 pub mod type_solving;
 pub mod utils;
 
+pub use proc_macro2;
 pub use syn;
 pub use ts_json_subset as ts;
 
@@ -41,8 +46,9 @@ pub fn process_file<P: AsRef<Path>>(path: P) -> Result<(), TsExportError> {
 
     Pipeline {
         pipeline_step_spawner: RustModuleReader::try_new(path.as_ref().to_path_buf())?,
-        exporter: StdoutExport,
+        exporter: StdoutExport::default(),
         path_mapper: PathMapper::default(),
+        config: PipelineConfig::default(),
     }
     .launch(&solving_context, &macro_context)?;
 
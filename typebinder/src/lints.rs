@@ -0,0 +1,576 @@
+//! Lints for Rust types that do not round-trip through JSON and JavaScript
+//!
+//! The lints are run on the Rust declarations, field by field, because the information they need
+//! is lost once the types are solved (e.g. `u64` and `u32` both become `number`). The type aliases of
+//! the module are seen through, and the fields serialized with `#[serde(with = "...")]` are not linted.
+//!
+//! * `large-integer` : 64 and 128 bits integers, and `usize`/`isize` on 64 bits targets, may exceed `Number.MAX_SAFE_INTEGER` (2^53 - 1) and lose precision
+//! * `non-finite-float` : `NaN` and infinite floats are serialized as `null`
+//! * `non-string-map-key` : JSON object keys are strings, numeric or boolean map keys are not typed correctly on the TS side
+//! * `nested-option` : `Option<Option<T>>` serializes both `None` and `Some(None)` as `null`
+//! * `shadowed-untagged-variant` : a variant of an untagged enum accepts all the values of a later variant,
+//!   which is then never deserialized
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use proc_macro2::Span;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use serde_derive_internals::{
+    ast::{Container, Data, Field, Style},
+    attr::TagType,
+};
+use syn::{spanned::Spanned, GenericArgument, ItemType, PathArguments, Type};
+use thiserror::Error;
+
+use crate::attributes::{ContainerAttributes, FieldAttributes, VariantAttributes};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, displaythis::Display, Serialize, Deserialize)]
+pub enum Lint {
+    #[display("large-integer")]
+    LargeInteger,
+    #[display("non-finite-float")]
+    NonFiniteFloat,
+    #[display("non-string-map-key")]
+    NonStringMapKey,
+    #[display("nested-option")]
+    NestedOption,
+    #[display("shadowed-untagged-variant")]
+    ShadowedUntaggedVariant,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Unknown lint {0}")]
+pub struct UnknownLint(String);
+
+impl FromStr for Lint {
+    type Err = UnknownLint;
+
+    fn from_str(input: &str) -> Result<Self, UnknownLint> {
+        match input {
+            "large-integer" => Ok(Lint::LargeInteger),
+            "non-finite-float" => Ok(Lint::NonFiniteFloat),
+            "non-string-map-key" => Ok(Lint::NonStringMapKey),
+            "nested-option" => Ok(Lint::NestedOption),
+            "shadowed-untagged-variant" => Ok(Lint::ShadowedUntaggedVariant),
+            _ => Err(UnknownLint(input.to_string())),
+        }
+    }
+}
+
+/// What to do when a lint is triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintLevel {
+    /// The lint is not checked
+    Allow,
+    /// The lint is reported as a warning
+    Warn,
+    /// The lint is reported as an error, and the declaration is not exported
+    Deny,
+}
+
+/// The level of each lint. Lints are warnings unless configured otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<Lint, LintLevel>,
+}
+
+impl LintConfig {
+    pub fn set_level(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(LintLevel::Warn)
+    }
+}
+
+impl Hash for LintConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut levels: Vec<(String, LintLevel)> = self
+            .levels
+            .iter()
+            .filter(|(_, level)| **level != LintLevel::Warn)
+            .map(|(lint, level)| (lint.to_string(), *level))
+            .collect();
+        levels.sort();
+        levels.hash(state);
+    }
+}
+
+/// A lint triggered by a declaration
+#[derive(Debug, Clone)]
+pub struct LintViolation {
+    pub lint: Lint,
+    pub message: String,
+    pub span: Span,
+}
+
+/// The type aliases of a module that the lints see through, e.g. `type Id = u64;`
+#[derive(Debug, Clone, Default)]
+pub struct TypeAliases(HashMap<String, Type>);
+
+impl TypeAliases {
+    /// The aliases without type parameters, as the parameters of the others are not substituted.
+    ///
+    /// The aliases given a TS type with `#[typebinder(type = "...")]` are not seen through, like such fields.
+    pub fn new<'a, I: IntoIterator<Item = &'a ItemType>>(items: I) -> Self {
+        TypeAliases(
+            items
+                .into_iter()
+                .filter(|item| item.generics.params.is_empty())
+                .filter(|item| {
+                    ContainerAttributes::from_attrs(&item.attrs)
+                        .map(|attributes| attributes.ts_type.is_none())
+                        .unwrap_or(true)
+                })
+                .map(|item| (item.ident.to_string(), (*item.ty).clone()))
+                .collect(),
+        )
+    }
+
+    /// The aliased type of a type that names an alias of the module
+    fn resolve(&self, ty: &Type) -> Option<&Type> {
+        match ty {
+            Type::Path(ty_path) if ty_path.qself.is_none() => {
+                let ident = ty_path.path.get_ident()?;
+                self.0.get(&ident.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// The type an alias of the module stands for, through the aliases of aliases
+    fn resolve_all<'a>(&'a self, mut ty: &'a Type) -> &'a Type {
+        // Bounded, as an alias may refer to itself
+        for _ in 0..self.0.len() {
+            match self.resolve(ty) {
+                Some(aliased) => ty = aliased,
+                None => break,
+            }
+        }
+        ty
+    }
+}
+
+/// Checks a type alias declaration
+pub fn check_type_alias(
+    item: &ItemType,
+    aliases: &TypeAliases,
+    config: &LintConfig,
+) -> Vec<LintViolation> {
+    let attributes = ContainerAttributes::from_attrs(&item.attrs).unwrap_or_default();
+    if !attributes.is_exported() || attributes.ts_type.is_some() {
+        return Vec::new();
+    }
+
+    let mut violations = Vec::new();
+    check_type(&item.ty, aliases, &mut Vec::new(), None, &mut violations);
+    let mut lints = HashSet::new();
+    violations.retain(|violation| {
+        lints.insert(violation.lint) && config.level(violation.lint) != LintLevel::Allow
+    });
+    violations
+}
+
+/// Checks the fields and variants of a container
+pub fn check_container(
+    container: &Container,
+    aliases: &TypeAliases,
+    config: &LintConfig,
+) -> Vec<LintViolation> {
+    let attributes = ContainerAttributes::from_attrs(&container.original.attrs).unwrap_or_default();
+    if !attributes.is_exported() || attributes.ts_type.is_some() {
+        return Vec::new();
+    }
+
+    let mut violations = Vec::new();
+    match &container.data {
+        Data::Struct(_, fields) => check_fields(fields, aliases, &mut violations),
+        Data::Enum(variants) => {
+            let variants: Vec<_> = variants
+                .iter()
+                .filter(|variant| {
+                    !VariantAttributes::from_attrs(&variant.original.attrs)
+                        .unwrap_or_default()
+                        .skip
+                })
+                .collect();
+            variants
+                .iter()
+                .for_each(|variant| check_fields(&variant.fields, aliases, &mut violations));
+            if let TagType::None = container.attrs.tag() {
+                for (index, variant) in variants.iter().enumerate() {
+                    let shadowing = variants[..index].iter().find(|previous| {
+                        accepts(
+                            previous.style,
+                            &previous.fields,
+                            variant.style,
+                            &variant.fields,
+                        )
+                    });
+                    if let Some(shadowing) = shadowing {
+                        violations.push(LintViolation {
+                            lint: Lint::ShadowedUntaggedVariant,
+                            message: format!(
+                                "Variant `{}` is never deserialized, its values are accepted by the previous variant `{}`",
+                                variant.ident, shadowing.ident
+                            ),
+                            span: variant.original.ident.span(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    violations.retain(|violation| config.level(violation.lint) != LintLevel::Allow);
+    violations
+}
+
+fn check_fields(fields: &[Field], aliases: &TypeAliases, violations: &mut Vec<LintViolation>) {
+    // The fields serialized by a function, e.g. with `#[serde(with = "...")]`, don't serialize as their type
+    for field in fields
+        .iter()
+        .filter(|field| !field.attrs.skip_serializing() && field.attrs.serialize_with().is_none())
+    {
+        let attributes = FieldAttributes::from_attrs(&field.original.attrs).unwrap_or_default();
+        if attributes.skip || attributes.ts_type.is_some() {
+            continue;
+        }
+        let mut field_violations = Vec::new();
+        check_type(
+            field.ty,
+            aliases,
+            &mut Vec::new(),
+            None,
+            &mut field_violations,
+        );
+        // One violation per lint and field is enough
+        let mut lints = HashSet::new();
+        field_violations.retain(|violation| lints.insert(violation.lint));
+        violations.extend(field_violations);
+    }
+}
+
+/// Checks a type, and the types it is made of.
+///
+/// The type aliases of the module are checked where they are used, at the span of the use given by `alias_span`,
+/// and `visited` holds the aliases being checked to stop at the recursive ones.
+fn check_type(
+    ty: &Type,
+    aliases: &TypeAliases,
+    visited: &mut Vec<String>,
+    alias_span: Option<Span>,
+    violations: &mut Vec<LintViolation>,
+) {
+    if let Some(aliased) = aliases.resolve(ty) {
+        let name = ty.to_token_stream().to_string();
+        if !visited.contains(&name) {
+            visited.push(name);
+            check_type(
+                aliased,
+                aliases,
+                visited,
+                alias_span.or_else(|| Some(ty.span())),
+                violations,
+            );
+            visited.pop();
+        }
+        return;
+    }
+    let span_of = |ty: &Type| alias_span.unwrap_or_else(|| ty.span());
+    match ty {
+        Type::Path(ty_path) => {
+            let segment = match ty_path.path.segments.last() {
+                Some(segment) => segment,
+                None => return,
+            };
+            let name = segment.ident.to_string();
+            let arguments: Vec<&Type> = match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) => arguments
+                    .args
+                    .iter()
+                    .filter_map(|argument| match argument {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            match name.as_str() {
+                "u64" | "i64" | "u128" | "i128" | "usize" | "isize" => {
+                    violations.push(LintViolation {
+                        lint: Lint::LargeInteger,
+                        message: format!(
+                            "`{}` may exceed 2^53 and lose precision in JavaScript",
+                            name
+                        ),
+                        span: span_of(ty),
+                    })
+                }
+                "f32" | "f64" => violations.push(LintViolation {
+                    lint: Lint::NonFiniteFloat,
+                    message: format!(
+                        "`{}` NaN and infinite values are serialized as `null`",
+                        name
+                    ),
+                    span: span_of(ty),
+                }),
+                "Option" => {
+                    if let Some(Type::Path(inner)) =
+                        arguments.first().map(|inner| aliases.resolve_all(inner))
+                    {
+                        if inner.path.segments.last().map(|s| s.ident == "Option") == Some(true) {
+                            violations.push(LintViolation {
+                                lint: Lint::NestedOption,
+                                message: "`None` and `Some(None)` are both serialized as `null`"
+                                    .to_string(),
+                                span: span_of(ty),
+                            });
+                        }
+                    }
+                }
+                "HashMap" | "BTreeMap" | "IndexMap" => {
+                    if let Some(key) = arguments.first() {
+                        if is_non_string_key(aliases.resolve_all(key)) {
+                            violations.push(LintViolation {
+                                lint: Lint::NonStringMapKey,
+                                message: format!(
+                                    "Map keys of type `{}` are serialized as strings in JSON",
+                                    key.to_token_stream()
+                                ),
+                                span: span_of(key),
+                            });
+                        }
+                    }
+                }
+                _ => (),
+            }
+            arguments.into_iter().for_each(|argument| {
+                check_type(argument, aliases, visited, alias_span, violations)
+            });
+        }
+        Type::Array(array) => check_type(&array.elem, aliases, visited, alias_span, violations),
+        Type::Slice(slice) => check_type(&slice.elem, aliases, visited, alias_span, violations),
+        Type::Reference(reference) => {
+            check_type(&reference.elem, aliases, visited, alias_span, violations)
+        }
+        Type::Paren(paren) => check_type(&paren.elem, aliases, visited, alias_span, violations),
+        Type::Group(group) => check_type(&group.elem, aliases, visited, alias_span, violations),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|elem| check_type(elem, aliases, visited, alias_span, violations)),
+        _ => (),
+    }
+}
+
+fn is_non_string_key(key: &Type) -> bool {
+    match key {
+        Type::Path(ty_path) => ty_path
+            .path
+            .segments
+            .last()
+            .map(|segment| {
+                matches!(
+                    segment.ident.to_string().as_str(),
+                    "u8" | "u16"
+                        | "u32"
+                        | "u64"
+                        | "u128"
+                        | "usize"
+                        | "i8"
+                        | "i16"
+                        | "i32"
+                        | "i64"
+                        | "i128"
+                        | "isize"
+                        | "f32"
+                        | "f64"
+                        | "bool"
+                )
+            })
+            .unwrap_or(false),
+        Type::Tuple(_) | Type::Array(_) => true,
+        Type::Reference(reference) => is_non_string_key(&reference.elem),
+        _ => false,
+    }
+}
+
+/// Whether all the values of the second variant are accepted by the first one, when deserializing an untagged enum
+fn accepts(style: Style, fields: &[Field], other_style: Style, other_fields: &[Field]) -> bool {
+    let type_of = |field: &Field| field.ty.to_token_stream().to_string();
+    match (style, other_style) {
+        (Style::Unit, Style::Unit) => true,
+        (Style::Newtype, Style::Newtype) | (Style::Tuple, Style::Tuple) => {
+            fields.len() == other_fields.len()
+                && fields
+                    .iter()
+                    .zip(other_fields)
+                    .all(|(field, other)| type_of(field) == type_of(other))
+        }
+        // Unknown fields are ignored by serde, so a struct variant accepts any object that has its fields
+        (Style::Struct, Style::Struct) => fields.iter().all(|field| {
+            other_fields.iter().any(|other| {
+                other.attrs.name().deserialize_name() == field.attrs.name().deserialize_name()
+                    && type_of(other) == type_of(field)
+            })
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use serde_derive_internals::{Ctxt, Derive};
+    use syn::{DeriveInput, Item};
+
+    /// Lints the declarations of a module, in order
+    fn lint(source: &str, config: &LintConfig) -> Vec<(Lint, usize)> {
+        let items = syn::parse_file(source).unwrap().items;
+        let aliases = TypeAliases::new(items.iter().filter_map(|item| match item {
+            Item::Type(item) => Some(item),
+            _ => None,
+        }));
+        let ctxt = Ctxt::new();
+        let violations = items
+            .iter()
+            .flat_map(|item| match item {
+                Item::Type(item) => check_type_alias(item, &aliases, config),
+                Item::Struct(item) => {
+                    let derive_input = DeriveInput::from(item.clone());
+                    let container =
+                        Container::from_ast(&ctxt, &derive_input, Derive::Serialize).unwrap();
+                    check_container(&container, &aliases, config)
+                }
+                Item::Enum(item) => {
+                    let derive_input = DeriveInput::from(item.clone());
+                    let container =
+                        Container::from_ast(&ctxt, &derive_input, Derive::Serialize).unwrap();
+                    check_container(&container, &aliases, config)
+                }
+                _ => Vec::new(),
+            })
+            .map(|violation| (violation.lint, violation.span.start().line))
+            .collect();
+        ctxt.check().unwrap();
+        violations
+    }
+
+    #[test]
+    fn should_lint_fields() {
+        let violations = lint(
+            r#"
+            struct A {
+                id: u64,
+                ratio: Option<f64>,
+                by_id: HashMap<u32, Vec<i128>>,
+                patch: Option<Option<String>>,
+                #[serde(skip)]
+                skipped: u64,
+                #[typebinder(type = "string")]
+                overridden: u64,
+                fine: HashMap<String, u32>,
+                pair: (u64, f64, u64),
+                index: usize,
+            }
+            "#,
+            &LintConfig::default(),
+        );
+        assert_eq!(
+            violations,
+            vec![
+                (Lint::LargeInteger, 3),
+                (Lint::NonFiniteFloat, 4),
+                (Lint::NonStringMapKey, 5),
+                (Lint::LargeInteger, 5),
+                (Lint::NestedOption, 6),
+                (Lint::LargeInteger, 12),
+                (Lint::NonFiniteFloat, 12),
+                (Lint::LargeInteger, 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_lint_shadowed_untagged_variants() {
+        let source = r#"
+            #[serde(untagged)]
+            enum A {
+                Named { name: String },
+                Full { name: String, age: u32 },
+                Number(u32),
+                Other(u32),
+                Empty,
+            }
+            "#;
+        let mut config = LintConfig::default();
+        assert_eq!(
+            lint(source, &config),
+            vec![
+                (Lint::ShadowedUntaggedVariant, 5),
+                (Lint::ShadowedUntaggedVariant, 7)
+            ]
+        );
+
+        config.set_level(Lint::ShadowedUntaggedVariant, LintLevel::Allow);
+        assert!(lint(source, &config).is_empty());
+    }
+
+    #[test]
+    fn should_lint_through_the_type_aliases() {
+        let violations = lint(
+            r#"
+            type Id = u64;
+
+            type Ids = Vec<Id>;
+
+            #[typebinder(type = "string")]
+            type Overridden = u64;
+
+            type Maybe = Option<String>;
+
+            struct A {
+                id: Id,
+                ids: Ids,
+                overridden: Overridden,
+                patch: Option<Maybe>,
+                name: String,
+            }
+            "#,
+            &LintConfig::default(),
+        );
+        assert_eq!(
+            violations,
+            vec![
+                (Lint::LargeInteger, 2),
+                (Lint::LargeInteger, 4),
+                (Lint::LargeInteger, 12),
+                (Lint::LargeInteger, 13),
+                (Lint::NestedOption, 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_lint_the_fields_serialized_with_a_function() {
+        let violations = lint(
+            r#"
+            struct A {
+                #[serde(with = "id_as_string")]
+                id: u64,
+                #[serde(serialize_with = "ratio_or_zero")]
+                ratio: f64,
+                count: u64,
+            }
+            "#,
+            &LintConfig::default(),
+        );
+        assert_eq!(violations, vec![(Lint::LargeInteger, 7)]);
+    }
+}
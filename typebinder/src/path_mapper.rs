@@ -1,8 +1,15 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use serde::Deserialize;
+use serde_derive_internals::attr::RenameRule;
 
 use crate::error::TsExportError;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Hash)]
 /// A tool that maps a punctuated path (Rust) to a TypeScript module path.
 /// Useful when you have a complex codebase that requires multiple `typebinder` passes to generate the bindings.
 ///
@@ -41,37 +48,194 @@ use crate::error::TsExportError;
 /// * a::models::my::module -> types/models/a/my/module
 /// * b::api -> types/b
 ///
+/// Mappings can also be patterns, where `*` matches any segment. The output refers to the matched segments by their
+/// position, e.g. with `*::models::*` -> `@models/{1}/{2}` :
+/// * a::models::user -> @models/a/user
+/// * a::models::user::role -> @models/a/user/role
+///
+/// When several mappings match a path, the one that matches the most segments wins, the exact ones first.
+/// The segments taken from the Rust path can be converted to another case, e.g. to kebab-case file names, see
+/// [add_mapping_with_case](PathMapper::add_mapping_with_case).
+///
+/// The mappings are loaded from a JSON or a TOML file, whose values are either the output or a table with the
+/// output and the case :
+///
+/// ```toml
+/// "shared::types" = "@company/shared-types"
+/// "*::models::*" = { output = "@models/{1}/{2}", case = "kebab-case" }
+/// ```
+///
 /// See the tests for more information
 pub struct PathMapper {
     root: PathMapperNode,
+    /// Sorted from the most specific to the least specific
+    patterns: Vec<PathPattern>,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct PathMapperNode {
     mapped_ident: String,
+    case: SegmentCase,
     children: HashMap<String, PathMapperNode>,
 }
 
+/// The case of the Rust path segments once in the TS import path, named after the `rename_all` rules of serde.
+/// The Rust segments are expected in snake_case.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum SegmentCase {
+    #[default]
+    #[serde(rename = "snake_case")]
+    SnakeCase,
+    #[serde(rename = "kebab-case")]
+    KebabCase,
+    #[serde(rename = "camelCase")]
+    CamelCase,
+    #[serde(rename = "PascalCase")]
+    PascalCase,
+}
+
+impl SegmentCase {
+    fn apply(self, segment: &str) -> String {
+        if segment.is_empty() {
+            return String::new();
+        }
+        let rule = match self {
+            SegmentCase::SnakeCase => RenameRule::SnakeCase,
+            SegmentCase::KebabCase => RenameRule::KebabCase,
+            SegmentCase::CamelCase => RenameRule::CamelCase,
+            SegmentCase::PascalCase => RenameRule::PascalCase,
+        };
+        rule.apply_to_field(segment)
+    }
+}
+
+/// A mapping whose path has `*` segments
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PathPattern {
+    segments: Vec<String>,
+    output: String,
+    case: SegmentCase,
+}
+
+/// A mapping as written in a PathMapper file
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MappingDefinition {
+    Output(String),
+    WithCase { output: String, case: SegmentCase },
+}
+
 impl PathMapper {
     pub fn add_mapping<AS: AsRef<str>, S: Into<String>>(&mut self, path: AS, output: S) {
+        self.add_mapping_with_case(path, output, SegmentCase::default())
+    }
+
+    /// Adds a mapping whose segments taken from the Rust path, the captured and the appended ones, are converted
+    /// to the given case
+    pub fn add_mapping_with_case<AS: AsRef<str>, S: Into<String>>(
+        &mut self,
+        path: AS,
+        output: S,
+        case: SegmentCase,
+    ) {
         let path = path.as_ref();
-        self.root.add_mapping(path, output.into())
+        if path
+            .split("::")
+            .any(|segment| segment == PathPattern::WILDCARD)
+        {
+            let pattern = PathPattern {
+                segments: path.split("::").map(str::to_string).collect(),
+                output: output.into(),
+                case,
+            };
+            // Stable, so that the first added pattern wins among the equally specific ones
+            let index = self
+                .patterns
+                .iter()
+                .position(|other| other.specificity() < pattern.specificity())
+                .unwrap_or(self.patterns.len());
+            self.patterns.insert(index, pattern);
+        } else {
+            self.root.add_mapping_with_case(path, output.into(), case)
+        }
     }
 
     pub fn get(&self, path: &str) -> Option<String> {
-        self.root.get(path)
+        if path.is_empty() {
+            return None;
+        }
+        let segments: Vec<&str> = path.split("::").collect();
+        let exact = self.root.lookup(&segments);
+        let pattern = self
+            .patterns
+            .iter()
+            .find_map(|pattern| pattern.get(&segments));
+        match (exact, pattern) {
+            (Some((depth, exact)), Some((pattern_depth, _))) if depth >= pattern_depth => {
+                Some(exact)
+            }
+            (_, Some((_, pattern))) => Some(pattern),
+            (exact, None) => exact.map(|(_, exact)| exact),
+        }
     }
 
+    /// Loads the mappings from a TOML file if its extension is `.toml`, from a JSON file otherwise
     pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self, TsExportError> {
+        let path = path.as_ref();
         let content = std::fs::read_to_string(path)?;
-        Self::load_from_string(&content)
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            Self::load_from_toml_string(&content)
+        } else {
+            Self::load_from_string(&content)
+        }
     }
 
     fn load_from_string(input: &str) -> Result<Self, TsExportError> {
-        let map: HashMap<String, String> = serde_json::from_str(input)?;
-        let mut root = PathMapperNode::default();
-        map.into_iter().for_each(|(k, v)| root.add_mapping(&k, v));
-        Ok(PathMapper { root })
+        let map: BTreeMap<String, MappingDefinition> = serde_json::from_str(input)?;
+        Self::from_definitions(map)
+    }
+
+    fn load_from_toml_string(input: &str) -> Result<Self, TsExportError> {
+        let map: BTreeMap<String, MappingDefinition> = toml::from_str(input)?;
+        Self::from_definitions(map)
+    }
+
+    fn from_definitions(map: BTreeMap<String, MappingDefinition>) -> Result<Self, TsExportError> {
+        let mut mapper = PathMapper::default();
+        for (path, definition) in map {
+            let (output, case) = match definition {
+                MappingDefinition::Output(output) => (output, SegmentCase::default()),
+                MappingDefinition::WithCase { output, case } => (output, case),
+            };
+            let wildcards = path
+                .split("::")
+                .filter(|segment| *segment == PathPattern::WILDCARD)
+                .count();
+            if let Some(index) = placeholders(&output)
+                .into_iter()
+                .find(|index| *index == 0 || *index > wildcards)
+            {
+                return Err(TsExportError::InvalidPathMapping {
+                    path,
+                    reason: format!("{{{}}} does not refer to a `*` segment", index),
+                });
+            }
+            mapper.add_mapping_with_case(path, output, case);
+        }
+        Ok(mapper)
+    }
+}
+
+impl Hash for PathMapperNode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mapped_ident.hash(state);
+        self.case.hash(state);
+        let mut children: Vec<(&String, &PathMapperNode)> = self.children.iter().collect();
+        children.sort_by_key(|(path, _)| *path);
+        children.hash(state);
     }
 }
 
@@ -80,55 +244,128 @@ impl PathMapperNode {
         if path.is_empty() {
             return None;
         }
+        let segments: Vec<&str> = path.split("::").collect();
+        self.lookup(&segments).map(|(_, output)| output)
+    }
 
-        let mut splitted_path = path.split("::");
-        let first = splitted_path.clone().next().expect("Split cannot be empty");
-
-        if !self.children.contains_key(first) {
+    /// The output for a path, along with the number of segments that the mapping matched
+    fn lookup(&self, segments: &[&str]) -> Option<(usize, String)> {
+        let first = segments.first()?;
+        if !self.children.contains_key(*first) {
             return None;
         }
 
-        self.get_inner(&mut splitted_path)
+        let mut node = self;
+        let mut depth = 0;
+        while let Some(child) = segments
+            .get(depth)
+            .and_then(|segment| node.children.get(*segment))
+        {
+            node = child;
+            depth += 1;
+        }
+        Some((
+            depth,
+            append_segments(node.mapped_ident.clone(), &segments[depth..], node.case),
+        ))
     }
 
-    fn get_inner<'a, I: Iterator<Item = &'a str>>(&self, path_iter: &mut I) -> Option<String> {
-        let path = path_iter.next();
-        if let Some(path) = path {
-            if let Some(child) = self.children.get(path) {
-                child.get_inner(path_iter)
-            } else {
-                let rest: Vec<&str> = Some(path).into_iter().chain(path_iter).collect();
-                let rest: String = rest.join("/");
-                Some(format!("{}/{}", self.mapped_ident, rest))
-            }
-        } else {
-            Some(self.mapped_ident.clone())
+    pub fn add_mapping(&mut self, path: &str, output: String) {
+        self.add_mapping_with_case(path, output, SegmentCase::default())
+    }
+
+    fn add_mapping_with_case(&mut self, path: &str, output: String, case: SegmentCase) {
+        let mut node = self;
+        for segment in path.split("::") {
+            node = node.children.entry(segment.to_string()).or_default();
         }
+        node.mapped_ident = output;
+        node.case = case;
     }
+}
 
-    pub fn add_mapping(&mut self, path: &str, output: String) {
-        let mut splitted_path = path.split("::");
-        self.add_mapping_inner(&mut splitted_path, output)
+impl PathPattern {
+    const WILDCARD: &'static str = "*";
+
+    /// Patterns with more segments first, then with more literal segments
+    fn specificity(&self) -> (usize, usize) {
+        let literals = self
+            .segments
+            .iter()
+            .filter(|segment| *segment != Self::WILDCARD)
+            .count();
+        (self.segments.len(), literals)
     }
 
-    fn add_mapping_inner<'a, I: Iterator<Item = &'a str>>(
-        &mut self,
-        path_iter: &mut I,
-        output: String,
-    ) {
-        let path = path_iter.next();
-        if let Some(path) = path {
-            let entry = self.children.entry(path.to_string()).or_default();
-            entry.add_mapping_inner(path_iter, output)
-        } else {
-            self.mapped_ident = output;
+    fn get(&self, segments: &[&str]) -> Option<(usize, String)> {
+        if segments.len() < self.segments.len() {
+            return None;
+        }
+        let mut captures = Vec::new();
+        for (pattern, segment) in self.segments.iter().zip(segments.iter()) {
+            if pattern == Self::WILDCARD {
+                captures.push(self.case.apply(segment));
+            } else if pattern != segment {
+                return None;
+            }
         }
+
+        let mut output = String::with_capacity(self.output.len());
+        let mut rest = self.output.as_str();
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match placeholder(rest)
+                .and_then(|(index, len)| Some((captures.get(index.checked_sub(1)?)?, len)))
+            {
+                Some((capture, len)) => {
+                    output.push_str(capture);
+                    rest = &rest[len..];
+                }
+                None => {
+                    output.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        output.push_str(rest);
+
+        let depth = self.segments.len();
+        Some((
+            depth,
+            append_segments(output, &segments[depth..], self.case),
+        ))
     }
 }
 
+/// The segments of the Rust path that the mapping did not match are appended to its output
+fn append_segments(mut output: String, segments: &[&str], case: SegmentCase) -> String {
+    for segment in segments {
+        output.push('/');
+        output.push_str(&case.apply(segment));
+    }
+    output
+}
+
+/// The index and the length of the `{N}` placeholder at the start of the input
+fn placeholder(input: &str) -> Option<(usize, usize)> {
+    let end = input.find('}')?;
+    let index = input[1..end].parse().ok()?;
+    Some((index, end + 1))
+}
+
+/// The indexes of all the placeholders of an output
+fn placeholders(output: &str) -> Vec<usize> {
+    output
+        .match_indices('{')
+        .filter_map(|(start, _)| placeholder(&output[start..]))
+        .map(|(index, _)| index)
+        .collect()
+}
+
 #[cfg(test)]
 pub mod tests {
-    use super::PathMapper;
+    use super::{PathMapper, SegmentCase};
 
     #[test]
     fn should_add_mapping() {
@@ -167,4 +404,75 @@ pub mod tests {
         assert_eq!(mapper.get(""), None);
         assert_eq!(mapper.get("c::a"), None);
     }
+
+    #[test]
+    fn should_map_the_patterns() {
+        let mut mapper = PathMapper::default();
+        mapper.add_mapping("*::models::*", "@models/{1}/{2}");
+        mapper.add_mapping("*::models::internal", "@internal/{1}");
+        mapper.add_mapping("a", "types/a");
+        mapper.add_mapping("shared::types", "@company/shared-types");
+
+        // The pattern matches more segments than the exact mapping of `a`
+        assert_eq!(
+            mapper.get("a::models::user"),
+            Some("@models/a/user".to_string())
+        );
+        assert_eq!(
+            mapper.get("b::models::user::role"),
+            Some("@models/b/user/role".to_string())
+        );
+        assert_eq!(
+            mapper.get("b::models::internal"),
+            Some("@internal/b".to_string())
+        );
+        assert_eq!(mapper.get("a::api"), Some("types/a/api".to_string()));
+        assert_eq!(mapper.get("b::models"), None);
+        assert_eq!(
+            mapper.get("shared::types::user"),
+            Some("@company/shared-types/user".to_string())
+        );
+    }
+
+    #[test]
+    fn should_convert_the_case() {
+        let mut mapper = PathMapper::default();
+        mapper.add_mapping_with_case("*::models", "types/{1}", SegmentCase::KebabCase);
+        mapper.add_mapping_with_case("my_crate::api", "api", SegmentCase::CamelCase);
+
+        assert_eq!(
+            mapper.get("my_crate::models::user_role"),
+            Some("types/my-crate/user-role".to_string())
+        );
+        assert_eq!(
+            mapper.get("my_crate::api::get_user"),
+            Some("api/getUser".to_string())
+        );
+    }
+
+    #[test]
+    fn should_load_from_toml() {
+        let mapper = PathMapper::load_from_toml_string(
+            r#"
+            "a::b" = "types/a/b"
+            "*::models::*" = { output = "@models/{1}/{2}", case = "kebab-case" }
+            "#,
+        )
+        .expect("Failed to read PathMapper");
+        assert_eq!(mapper.get("a::b::c"), Some("types/a/b/c".to_string()));
+        assert_eq!(
+            mapper.get("my_crate::models::user_role"),
+            Some("@models/my-crate/user-role".to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_the_unknown_placeholders() {
+        let error =
+            PathMapper::load_from_string(r#"{ "*::models": "@models/{1}/{2}" }"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid mapping for `*::models` : {2} does not refer to a `*` segment"
+        );
+    }
 }
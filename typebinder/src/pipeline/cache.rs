@@ -0,0 +1,122 @@
+//! An on-disk cache of the exported modules
+//!
+//! Each Rust module read from a file is cached under a key computed from :
+//! * the contents of the file,
+//! * the Rust path of the module and its crate,
+//! * the options that change its output : the lints and the [PathMapper],
+//! * the solvers, identified by [ModuleCache::with_solvers_key],
+//! * the version of `typebinder`.
+//!
+//! When none of these changed since the last run, the module is neither parsed nor solved again.
+//! Its child modules have their own entries.
+//!
+//! The keys are hashed with FNV-1a, so that they stay the same from one Rust release to the next,
+//! unlike those of the standard library's `DefaultHasher`.
+use std::{
+    hash::{Hash, Hasher},
+    path::PathBuf,
+};
+
+use fnv::FnvHasher;
+
+use serde::{Deserialize, Serialize};
+use syn::Path;
+
+use crate::{
+    diagnostics::Warning, path_mapper::PathMapper, pipeline::config::PipelineConfig,
+    utils::display_path::DisplayPath,
+};
+
+use super::module_step::ModuleExports;
+
+/// Where the cached modules are stored.
+///
+/// Entries are never removed, the directory can be deleted at any time.
+#[derive(Debug, Clone)]
+pub struct ModuleCache {
+    directory: PathBuf,
+    solvers_key: String,
+}
+
+/// The output of a module that is stored in the cache
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedModule {
+    pub exports: ModuleExports,
+    pub warnings: Vec<Warning>,
+    /// The names of the child modules, so that they are exported without parsing the module again.
+    /// `None` when some are inline modules, whose items are in the source.
+    pub modules: Option<Vec<String>>,
+}
+
+impl ModuleCache {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        ModuleCache {
+            directory: directory.into(),
+            solvers_key: String::new(),
+        }
+    }
+
+    /// Identifies the custom type and macro solvers, that can't be inspected.
+    ///
+    /// Change it whenever the solvers given to the pipeline change, e.g. by bumping a version number.
+    pub fn with_solvers_key<S: Into<String>>(mut self, solvers_key: S) -> Self {
+        self.solvers_key = solvers_key.into();
+        self
+    }
+
+    /// The key of a module, given the hash of its source (see [hash_source])
+    pub(crate) fn key(
+        &self,
+        source_hash: u64,
+        path: &Path,
+        crate_name: &str,
+        config: &PipelineConfig,
+        path_mapper: &PathMapper,
+    ) -> u64 {
+        let mut hasher = FnvHasher::default();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        self.solvers_key.hash(&mut hasher);
+        source_hash.hash(&mut hasher);
+        DisplayPath(path).to_string().hash(&mut hasher);
+        crate_name.hash(&mut hasher);
+        config.lints.hash(&mut hasher);
+        path_mapper.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Reads an entry. Entries that can't be read are ignored.
+    pub(crate) fn get(&self, key: u64) -> Option<CachedModule> {
+        let contents = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(cached) => Some(cached),
+            Err(error) => {
+                log::warn!("Ignoring the invalid cache entry {:016x} : {}", key, error);
+                None
+            }
+        }
+    }
+
+    /// Writes an entry. Failing to write to the cache does not prevent the module from being exported.
+    pub(crate) fn put(&self, key: u64, cached: &CachedModule) {
+        let written = std::fs::create_dir_all(&self.directory)
+            .map_err(|error| error.to_string())
+            .and_then(|_| serde_json::to_string(cached).map_err(|error| error.to_string()))
+            .and_then(|contents| {
+                std::fs::write(self.entry_path(key), contents).map_err(|error| error.to_string())
+            });
+        if let Err(error) = written {
+            log::warn!("Could not write the cache entry {:016x} : {}", key, error);
+        }
+    }
+
+    fn entry_path(&self, key: u64) -> PathBuf {
+        self.directory.join(format!("{:016x}.json", key))
+    }
+}
+
+/// Hashes the source of a Rust module
+pub fn hash_source(source: &str) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(source.as_bytes());
+    hasher.finish()
+}
@@ -0,0 +1,28 @@
+use crate::{
+    lints::LintConfig,
+    pipeline::{cache::ModuleCache, imports::ImportStrategy},
+};
+
+/// Options that change what a [Pipeline](crate::pipeline::Pipeline) outputs
+#[derive(Debug, Default, Clone)]
+pub struct PipelineConfig {
+    /// When set, only the declarations reachable from these types are exported.
+    ///
+    /// Types are given by their Rust path, relative to the root of the crate, e.g. `models::user::User`.
+    /// A type of the root module is given by its name only.
+    pub roots: Option<Vec<String>>,
+    /// When set, a declaration or a module that fails to export is recorded and skipped, instead of stopping the pipeline.
+    ///
+    /// The rest of the declarations are exported, and all the errors are given back in [PipelineOutput::errors](crate::pipeline::PipelineOutput::errors)
+    /// along with the output of the exporter, that is finished with [Exporter::finish_incomplete](crate::exporters::Exporter::finish_incomplete).
+    pub collect_errors: bool,
+    /// The levels of the lints run on each exported struct and enum, see [lints](crate::lints)
+    pub lints: LintConfig,
+    /// When set, the modules that did not change since the last run are not exported again, see [ModuleCache]
+    pub cache: Option<ModuleCache>,
+    /// How the modules import each other, see [ImportStrategy]
+    pub import_strategy: ImportStrategy,
+    /// When set, the modules are imported with `import type`, as required by `isolatedModules` and
+    /// `verbatimModuleSyntax` since the bindings only declare types
+    pub type_only_imports: bool,
+}
@@ -0,0 +1,285 @@
+//! Detection of the recursive declarations that can't be expressed in TS
+//!
+//! The solvers never inline a declaration : a Rust type that refers to a declaration is always solved
+//! to a named reference to it. Recursive types are thus expressed by name, e.g. `struct Tree { children: Vec<Tree> }`
+//! gives `interface Tree { children: Tree[] }`.
+//!
+//! TS resolves some of these references eagerly though. A type alias can't refer to itself, directly or
+//! through other aliases, unless the reference is deferred, i.e. in an object member, an array or a tuple.
+//! The same goes for interfaces that extend themselves, which happens with `#[serde(flatten)]`.
+//! For instance, `type Maybe = Option<Maybe>` gives `type Maybe = Maybe | null`, which TS rejects.
+//!
+//! The cycles are found within each module while exporting it, then across the modules once they are all exported.
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use ts_json_subset::{
+    export::ExportStatement,
+    types::{PrimaryType, TsType},
+};
+
+use crate::{
+    error::TsExportError,
+    path_mapper::PathMapper,
+    pipeline::{
+        module_step::{import_statements, ModuleStepResultData},
+        reachability::resolve_module,
+    },
+    type_solving::ImportEntry,
+    utils::display_path::DisplayPath,
+};
+
+/// TS utility types, which are type aliases and resolve their arguments eagerly
+const EAGER_UTILITY_TYPES: &[&str] = &[
+    "Record",
+    "Partial",
+    "Required",
+    "Readonly",
+    "Pick",
+    "Omit",
+    "NonNullable",
+];
+
+/// Finds the cycles of declarations of a module that refer to each other eagerly.
+///
+/// Each cycle is given as the list of the declarations it goes through, starting and ending with the same declaration.
+pub(crate) fn find_unexpressible_cycles<'a, I>(exports: I) -> Vec<Vec<String>>
+where
+    I: IntoIterator<Item = &'a ExportStatement>,
+{
+    let exports: Vec<&ExportStatement> = exports.into_iter().collect();
+    let aliases = alias_names(exports.iter().copied());
+    let edges = eager_edges(exports, &aliases);
+    let order: Vec<String> = edges.iter().map(|(name, _)| name.clone()).collect();
+    find_cycles(&order, &edges.into_iter().collect())
+}
+
+/// Removes the cycles of declarations of different modules that refer to each other eagerly,
+/// e.g. `a::A = b::B | null` and `b::B = a::A | null`.
+///
+/// The cycles within a module are found while exporting it, see [find_unexpressible_cycles].
+/// Gives back the cycles as errors, the declarations being named by their module path.
+pub(crate) fn reject_cross_module_cycles(
+    results: &mut [ModuleStepResultData],
+    path_mapper: &PathMapper,
+) -> Result<Vec<TsExportError>, TsExportError> {
+    let module_paths: Vec<String> = results
+        .iter()
+        .map(|result| DisplayPath(&result.path).to_string())
+        .collect();
+    let modules: HashMap<&str, usize> = module_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| (path.as_str(), index))
+        .collect();
+
+    // A declaration is known by the index of its module and its TS name
+    let mut order: Vec<(usize, String)> = Vec::new();
+    let mut edges: HashMap<(usize, String), Vec<(usize, String)>> = HashMap::new();
+    for (module_index, result) in results.iter().enumerate() {
+        let current_module = module_paths[module_index].as_str();
+        let declared: HashSet<String> = result
+            .exports
+            .iter()
+            .flat_map(|export| export.declared_idents())
+            .map(|ident| ident.to_string())
+            .collect();
+        let imported: HashMap<&str, (usize, &ImportEntry)> = result
+            .dependencies
+            .iter()
+            .flatten()
+            .filter_map(|entry| {
+                let index = resolve_module(&modules, current_module, result, entry)?;
+                Some((entry.local_name(), (index, entry)))
+            })
+            .collect();
+        // The imported aliases resolve their arguments eagerly too
+        let aliases = alias_names(result.exports.iter())
+            .into_iter()
+            .chain(
+                imported
+                    .iter()
+                    .filter(|(_, (index, entry))| {
+                        alias_names(results[*index].exports.iter()).contains(&entry.ident)
+                    })
+                    .map(|(local_name, _)| local_name.to_string()),
+            )
+            .collect();
+        for (name, references) in eager_edges(result.exports.iter(), &aliases) {
+            let references = references
+                .into_iter()
+                .filter_map(|reference| {
+                    if declared.contains(&reference) {
+                        Some((module_index, reference))
+                    } else {
+                        let (index, entry) = imported.get(reference.as_str())?;
+                        Some((*index, entry.ident.clone()))
+                    }
+                })
+                .collect();
+            order.push((module_index, name.clone()));
+            edges.insert((module_index, name), references);
+        }
+    }
+
+    let mut errors = Vec::new();
+    for cycle in find_cycles(&order, &edges) {
+        for (module_index, name) in cycle.iter() {
+            let result = &mut results[*module_index];
+            let mut index = 0;
+            while index < result.exports.len() {
+                if result.exports[index]
+                    .declared_idents()
+                    .iter()
+                    .any(|ident| ident.to_string() == *name)
+                {
+                    result.exports.remove(index);
+                    result.dependencies.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+        }
+        let modules_in_cycle: HashSet<usize> = cycle.iter().map(|(index, _)| *index).collect();
+        for index in modules_in_cycle {
+            let result = &mut results[index];
+            result.imports =
+                import_statements(result.dependencies.iter().flatten().cloned(), path_mapper)?;
+        }
+        errors.push(TsExportError::UnexpressibleCycle(
+            cycle
+                .into_iter()
+                .map(|(index, name)| match module_paths[index].as_str() {
+                    "" => name,
+                    module => format!("{}::{}", module, name),
+                })
+                .collect(),
+        ));
+    }
+    Ok(errors)
+}
+
+/// The names of the type aliases declared by the exports
+fn alias_names<'a, I>(exports: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = &'a ExportStatement>,
+{
+    exports
+        .into_iter()
+        .filter_map(|export| match export {
+            ExportStatement::TypeAliasDeclaration(declaration) => {
+                Some(declaration.ident.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The declarations of the exports, in order, with the names they refer to eagerly
+fn eager_edges<'a, I>(exports: I, aliases: &HashSet<String>) -> Vec<(String, Vec<String>)>
+where
+    I: IntoIterator<Item = &'a ExportStatement>,
+{
+    exports
+        .into_iter()
+        .filter_map(|export| match export {
+            ExportStatement::TypeAliasDeclaration(declaration) => {
+                let mut references = Vec::new();
+                eager_references(&declaration.inner_type, aliases, &mut references);
+                Some((declaration.ident.to_string(), references))
+            }
+            ExportStatement::InterfaceDeclaration(declaration) => {
+                let references = declaration
+                    .extends_clause
+                    .iter()
+                    .flat_map(|clause| clause.type_list.identifiers.iter())
+                    .map(|reference| reference.name.to_string())
+                    .collect();
+                Some((declaration.ident.to_string(), references))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the cycles of the graph, visiting the nodes in the given order.
+///
+/// A node is in one cycle at most, the edges to nodes that are not in the graph are ignored.
+fn find_cycles<N>(order: &[N], edges: &HashMap<N, Vec<N>>) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut cycles: Vec<Vec<N>> = Vec::new();
+    let mut in_cycle: HashSet<N> = HashSet::new();
+    for start in order.iter() {
+        if in_cycle.contains(start) {
+            continue;
+        }
+        let mut path = vec![start.clone()];
+        let mut visited = HashSet::new();
+        if find_path_back(start, edges, &mut path, &mut visited) {
+            in_cycle.extend(path.iter().cloned());
+            cycles.push(path);
+        }
+    }
+    cycles
+}
+
+/// Depth-first search for a path from the last declaration of `path` back to `start`
+fn find_path_back<N>(
+    start: &N,
+    edges: &HashMap<N, Vec<N>>,
+    path: &mut Vec<N>,
+    visited: &mut HashSet<N>,
+) -> bool
+where
+    N: Clone + Eq + Hash,
+{
+    let current = path.last().expect("Empty path").clone();
+    for next in edges.get(&current).into_iter().flatten() {
+        if next == start {
+            path.push(next.clone());
+            return true;
+        }
+        if edges.contains_key(next) && visited.insert(next.clone()) {
+            path.push(next.clone());
+            if find_path_back(start, edges, path, visited) {
+                return true;
+            }
+            path.pop();
+        }
+    }
+    false
+}
+
+/// The references of a type that TS resolves when declaring it
+fn eager_references(ts_type: &TsType, aliases: &HashSet<String>, references: &mut Vec<String>) {
+    match ts_type {
+        TsType::PrimaryType(PrimaryType::TypeReference(reference)) => {
+            let name = reference.name.to_string();
+            // The arguments of an interface (e.g. `Array<T>`) are deferred, those of an alias are not
+            if aliases.contains(&name) || EAGER_UTILITY_TYPES.contains(&name.as_str()) {
+                reference
+                    .args
+                    .iter()
+                    .flat_map(|args| args.types.iter())
+                    .for_each(|ts_type| eager_references(ts_type, aliases, references));
+            }
+            references.push(name);
+        }
+        TsType::PrimaryType(_) => (),
+        TsType::UnionType(union) => union
+            .types
+            .iter()
+            .for_each(|ts_type| eager_references(ts_type, aliases, references)),
+        TsType::IntersectionType(intersection) => intersection
+            .types
+            .iter()
+            .for_each(|ts_type| eager_references(ts_type, aliases, references)),
+        TsType::ParenthesizedType(parenthesized) => {
+            eager_references(&parenthesized.inner, aliases, references)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! How the modules refer to the declarations of the other modules: under which name, given the names taken in the
+//! module and their `#[typebinder(...)]` attributes, see [DeclarationAttributes](crate::attributes::DeclarationAttributes)
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::TsExportError,
+    path_mapper::PathMapper,
+    pipeline::{
+        module_step::{import_statements, ModuleStepResultData},
+        reachability::resolve_module,
+    },
+    type_solving::ImportEntry,
+    utils::{
+        display_path::DisplayPath,
+        rename::{placeholder_name, rename_statement},
+    },
+};
+
+/// How a module refers to a declaration, given by a qualified path or imported with `use`
+enum Reference {
+    /// Imported from another module under the TS name of the declaration
+    Imported(String),
+    /// Not imported, the declaration being a global TS type of the given name
+    Global(String),
+    /// A declaration of the module itself, under its TS name
+    Local(String),
+    Skipped,
+}
+
+/// Gives its name in each module to each declaration it refers to, in place of its placeholder name, see
+/// [placeholder_name].
+///
+/// The renamed declarations of the other modules are imported under their TS name, the global ones are not imported,
+/// and the references to the skipped ones are reported. A declaration is imported under an alias when its name is
+/// already taken in the module, by a declaration of the module or by a declaration of another module that was
+/// referred to first, e.g. `b::Status` as `BStatus`.
+///
+/// The declarations of the current module are already handled by its [ImportContext](crate::contexts::import::ImportContext).
+/// Gives back the references to the skipped declarations as errors.
+pub(crate) fn apply_declaration_attributes(
+    results: &mut [ModuleStepResultData],
+    path_mapper: &PathMapper,
+) -> Result<Vec<TsExportError>, TsExportError> {
+    let module_paths: Vec<String> = results
+        .iter()
+        .map(|result| DisplayPath(&result.path).to_string())
+        .collect();
+    let modules: HashMap<&str, usize> = module_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| (path.as_str(), index))
+        .collect();
+
+    let mut errors = Vec::new();
+    for module_index in 0..results.len() {
+        let current_module = module_paths[module_index].as_str();
+        let result = &results[module_index];
+        let mut taken: HashSet<String> = result
+            .exports
+            .iter()
+            .flat_map(|statement| statement.declared_idents())
+            .map(|ident| ident.to_string())
+            .collect();
+        // Placeholder name -> reference, and its name in the module
+        let mut references: HashMap<String, (Reference, String)> = HashMap::new();
+        // (Module, ident) -> name in the module, as a declaration can be referred to by several paths
+        let mut names: HashMap<(String, String), String> = HashMap::new();
+        for entry in result.dependencies.iter().flatten() {
+            let placeholder = placeholder_name(&entry.path, &entry.ident);
+            if entry.path.is_empty() || references.contains_key(&placeholder) {
+                continue;
+            }
+            let resolved = resolve_module(&modules, current_module, result, entry);
+            let attributes = resolved.map(|index| &results[index].declaration_attributes);
+            let ts_name = attributes
+                .and_then(|attributes| {
+                    attributes
+                        .renamed
+                        .iter()
+                        .find(|(ident, _)| *ident == entry.ident)
+                })
+                .map(|(_, rename)| rename.clone())
+                .unwrap_or_else(|| entry.ident.clone());
+            let reference = match (resolved, attributes) {
+                (Some(index), _) if index == module_index => Reference::Local(ts_name),
+                (_, Some(attributes)) if attributes.skipped.contains(&entry.ident) => {
+                    Reference::Skipped
+                }
+                (_, Some(attributes)) if attributes.global.contains(&entry.ident) => {
+                    Reference::Global(ts_name)
+                }
+                _ => Reference::Imported(ts_name),
+            };
+            let name = match &reference {
+                Reference::Imported(ts_name) => {
+                    let module = match resolved {
+                        Some(index) => module_paths[index].clone(),
+                        None => entry.path.clone(),
+                    };
+                    names
+                        .entry((module, entry.ident.clone()))
+                        .or_insert_with(|| {
+                            let name = import_name(entry, ts_name, &result.crate_name, &taken);
+                            taken.insert(name.clone());
+                            name
+                        })
+                        .clone()
+                }
+                Reference::Global(ts_name) | Reference::Local(ts_name) => ts_name.clone(),
+                Reference::Skipped => {
+                    errors.push(TsExportError::SkippedTypeReferenced {
+                        name: entry.ident.clone(),
+                        module: if current_module.is_empty() {
+                            "the root module".to_string()
+                        } else {
+                            format!("module {}", current_module)
+                        },
+                    });
+                    entry.ident.clone()
+                }
+            };
+            references.insert(placeholder, (reference, name));
+        }
+        if references.is_empty() {
+            continue;
+        }
+
+        let result = &mut results[module_index];
+        for dependencies in result.dependencies.iter_mut() {
+            dependencies.retain(|entry| {
+                !matches!(
+                    references.get(&placeholder_name(&entry.path, &entry.ident)),
+                    Some((Reference::Global(_), _))
+                )
+            });
+        }
+        for entry in result.dependencies.iter_mut().flatten() {
+            if let Some((Reference::Imported(ts_name), name)) =
+                references.get(&placeholder_name(&entry.path, &entry.ident))
+            {
+                entry.alias = Some(name.clone()).filter(|name| name != ts_name);
+                entry.ident = ts_name.clone();
+            }
+        }
+
+        let rename = |name: &str| references.get(name).map(|(_, name)| name.clone());
+        for statement in result.exports.iter_mut() {
+            rename_statement(statement, &rename)?;
+        }
+        result.imports =
+            import_statements(result.dependencies.iter().flatten().cloned(), path_mapper)?;
+    }
+    Ok(errors)
+}
+
+/// The name a declaration of another module is imported under: its TS name, or an alias when the name is taken.
+///
+/// The alias is the Rust ident of a renamed declaration, or its name prefixed with its module, e.g. `BStatus`.
+fn import_name(
+    entry: &ImportEntry,
+    ts_name: &str,
+    crate_name: &str,
+    taken: &HashSet<String>,
+) -> String {
+    let words: Vec<&str> = entry
+        .path
+        .split("::")
+        .filter(|segment| !matches!(*segment, "" | "self" | "super" | "crate"))
+        .filter(|segment| *segment != crate_name)
+        .collect();
+    let prefixed = words
+        .last()
+        .map(|word| pascal_case(word))
+        .into_iter()
+        .chain(Some(words.iter().map(|word| pascal_case(word)).collect()))
+        .map(|prefix: String| format!("{}{}", prefix, ts_name));
+    Some(ts_name.to_string())
+        .into_iter()
+        .chain(Some(entry.ident.clone()))
+        .chain(prefixed)
+        .chain((2..).map(|suffix| format!("{}{}", ts_name, suffix)))
+        .find(|name| !taken.contains(name))
+        .expect("The suffixes are unbounded")
+}
+
+/// `user_data` -> `UserData`
+fn pascal_case(word: &str) -> String {
+    word.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
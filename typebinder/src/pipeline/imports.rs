@@ -0,0 +1,211 @@
+//! How the imports between the generated modules are written
+use std::collections::HashMap;
+
+use crate::{
+    error::TsExportError,
+    path_mapper::PathMapper,
+    pipeline::{
+        module_step::{group_imports, ModuleStepResultData},
+        reachability::resolve_module,
+    },
+    type_solving::ImportEntry,
+    utils::display_path::DisplayPath,
+};
+
+/// Name of the file of the root module, see [FileExporter](crate::exporters::file::FileExporter)
+const ROOT_MODULE_NAME: &str = "index";
+
+/// How a module imports the declarations of the other modules
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportStrategy {
+    /// The Rust path of the imported module is mapped with the [PathMapper], e.g. `"my_crate/models/user"`.
+    ///
+    /// This requires path aliases in the `tsconfig.json`, unless the mapped paths are packages.
+    #[default]
+    PathMapper,
+    /// The modules of the pipeline are imported by their path relative to the importing file, e.g. `"../models/user"`.
+    ///
+    /// The other imports are still mapped with the [PathMapper]. The root module is expected in `index.ts`.
+    Relative {
+        /// Adds the `.js` extension to the imports, as required by the ESM resolution of Node
+        js_extension: bool,
+    },
+}
+
+/// Computes the imports of the modules again, importing the modules of the pipeline by their relative path
+pub(crate) fn relative_imports(
+    results: &mut [ModuleStepResultData],
+    js_extension: bool,
+    path_mapper: &PathMapper,
+) -> Result<(), TsExportError> {
+    let module_paths: Vec<String> = results
+        .iter()
+        .map(|result| DisplayPath(&result.path).to_string())
+        .collect();
+    let modules: HashMap<&str, usize> = module_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| (path.as_str(), index))
+        .collect();
+
+    let mut imports = Vec::with_capacity(results.len());
+    for (module_index, result) in results.iter().enumerate() {
+        let current_module = module_paths[module_index].as_str();
+        let entries = result.dependencies.iter().flatten().cloned();
+        imports.push(group_imports(entries, |path| {
+            let entry = ImportEntry {
+                path: path.to_string(),
+                ..Default::default()
+            };
+            match resolve_module(&modules, current_module, result, &entry) {
+                Some(index) if index == module_index => String::new(),
+                Some(index) => relative_path(current_module, &module_paths[index], js_extension),
+                None => path_mapper.get(path).unwrap_or_else(|| path.to_string()),
+            }
+        })?);
+    }
+    for (result, imports) in results.iter_mut().zip(imports) {
+        result.imports = imports;
+    }
+
+    Ok(())
+}
+
+/// The TS import path of the file of a module, from the file of another module
+fn relative_path(from_module: &str, to_module: &str, js_extension: bool) -> String {
+    let file = |module: &str| -> Vec<String> {
+        let segments: Vec<String> = module
+            .split("::")
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect();
+        if segments.is_empty() {
+            vec![ROOT_MODULE_NAME.to_string()]
+        } else {
+            segments
+        }
+    };
+    let from = file(from_module);
+    let to = file(to_module);
+    let from_directory = &from[..from.len() - 1];
+    let to_directory = &to[..to.len() - 1];
+
+    let common = from_directory
+        .iter()
+        .zip(to_directory.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let parents = from_directory.len() - common;
+    let mut path = if parents == 0 {
+        "./".to_string()
+    } else {
+        "../".repeat(parents)
+    };
+    path.push_str(&to[common..].join("/"));
+    if js_extension {
+        path.push_str(".js");
+    }
+    path
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use syn::{punctuated::Punctuated, Path};
+
+    fn module(path: &str, dependencies: &[(&str, &str)]) -> ModuleStepResultData {
+        let path = if path.is_empty() {
+            Path {
+                leading_colon: None,
+                segments: Punctuated::default(),
+            }
+        } else {
+            syn::parse_str(path).unwrap()
+        };
+        ModuleStepResultData {
+            exports: Vec::new(),
+            imports: Vec::new(),
+            path,
+            dependencies: vec![dependencies
+                .iter()
+                .map(|(path, ident)| ImportEntry {
+                    path: path.to_string(),
+                    ident: ident.to_string(),
+                    alias: None,
+                })
+                .collect()],
+            crate_name: "my_crate".to_string(),
+            declaration_attributes: Default::default(),
+        }
+    }
+
+    fn imports(result: &ModuleStepResultData) -> Vec<String> {
+        result
+            .imports
+            .iter()
+            .map(|import| import.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn should_compute_relative_paths() {
+        assert_eq!(relative_path("", "models", false), "./models");
+        assert_eq!(relative_path("models", "", false), "./index");
+        assert_eq!(
+            relative_path("api::user", "models::user", true),
+            "../models/user.js"
+        );
+        assert_eq!(relative_path("api", "api::user", false), "./api/user");
+        assert_eq!(relative_path("api::user", "api", false), "../api");
+        assert_eq!(
+            relative_path("api::v1::user", "api::role", false),
+            "../role"
+        );
+    }
+
+    #[test]
+    fn should_import_the_modules_relatively() {
+        let mut results = vec![
+            module("", &[("models", "User"), ("", "Root")]),
+            module(
+                "api::user",
+                &[
+                    ("crate::models", "User"),
+                    ("my_crate::models", "Role"),
+                    ("super", "Request"),
+                    ("chrono", "DateTime"),
+                    ("other", "External"),
+                ],
+            ),
+            module("api", &[("user", "GetUser")]),
+            module("models", &[]),
+        ];
+        let mut path_mapper = PathMapper::default();
+        path_mapper.add_mapping("other", "@ext/other");
+
+        relative_imports(&mut results, false, &path_mapper).unwrap();
+        assert_eq!(
+            imports(&results[0]),
+            vec![r#"import { User } from "./models";"#]
+        );
+        assert_eq!(
+            imports(&results[1]),
+            vec![
+                r#"import { User, Role } from "../models";"#,
+                r#"import { Request } from "../api";"#,
+                r#"import { DateTime } from "chrono";"#,
+                r#"import { External } from "@ext/other";"#,
+            ]
+        );
+        assert_eq!(
+            imports(&results[2]),
+            vec![r#"import { GetUser } from "./api/user";"#]
+        );
+
+        relative_imports(&mut results, true, &path_mapper).unwrap();
+        assert_eq!(
+            imports(&results[0]),
+            vec![r#"import { User } from "./models.js";"#]
+        );
+    }
+}
@@ -1,15 +1,29 @@
 //! The core logic of `typebinder`
 
 use crate::{
-    contexts::type_solving::TypeSolvingContext, error::TsExportError, exporters::Exporter,
-    macros::context::MacroSolvingContext, path_mapper::PathMapper,
+    contexts::type_solving::TypeSolvingContext,
+    diagnostics::{Diagnostics, Warning},
+    error::TsExportError,
+    exporters::Exporter,
+    macros::context::MacroSolvingContext,
+    path_mapper::PathMapper,
     step_spawner::PipelineStepSpawner,
 };
 use syn::{punctuated::Punctuated, Path};
 
-use self::module_step::{ModuleStepResult, ModuleStepResultData};
+use self::{
+    config::PipelineConfig,
+    imports::ImportStrategy,
+    module_step::{ModuleStepResult, ModuleStepResultData},
+};
 
+pub mod cache;
+pub mod config;
+mod cycles;
+mod declarations;
+pub mod imports;
 pub mod module_step;
+mod reachability;
 pub mod step_result;
 
 /// The Pipeline is the starting point of `typebinder`.
@@ -19,16 +33,42 @@ pub mod step_result;
 /// When launched, the [Pipeline] will use its [PipelineStepSpawner] to spawn the "default" module, that is, the module with an empty path.
 /// Each [ModuleStep](crate::pipeline::module_step::ModuleStep) thereby generated is then launched, see [ModuleStep::launch](crate::pipeline::module_step::ModuleStep).
 ///
+/// The child modules are read and exported in parallel, the output keeps the order in which they are declared.
+///
+/// When [PipelineConfig::roots] is set, only the declarations reachable from the root types are kept.
+///
 /// Each output is passed to the [Exporter], that is responsible for outputting the data.
 pub struct Pipeline<PSS, E> {
     pub pipeline_step_spawner: PSS,
     pub exporter: E,
     pub path_mapper: PathMapper,
+    pub config: PipelineConfig,
+}
+
+/// What a successful [Pipeline] run gives back
+#[derive(Debug, Default)]
+pub struct PipelineOutput<T = ()> {
+    pub warnings: Vec<Warning>,
+    /// What the [Exporter] gave back when done, see [Exporter::Output]
+    pub exported: T,
+    /// The errors collected along the way, see [PipelineConfig::collect_errors]
+    pub errors: Vec<TsExportError>,
+}
+
+impl<T> PipelineOutput<T> {
+    /// Maps what the [Exporter] gave back
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> PipelineOutput<U> {
+        PipelineOutput {
+            warnings: self.warnings,
+            exported: f(self.exported),
+            errors: self.errors,
+        }
+    }
 }
 
 impl<PSS, E> Pipeline<PSS, E>
 where
-    PSS: PipelineStepSpawner,
+    PSS: PipelineStepSpawner + Sync,
     E: Exporter,
     TsExportError: From<PSS::Error> + From<E::Error>,
 {
@@ -36,7 +76,8 @@ where
         mut self,
         solving_context: &TypeSolvingContext,
         macro_context: &MacroSolvingContext,
-    ) -> Result<(), TsExportError> {
+    ) -> Result<PipelineOutput<E::Output>, TsExportError> {
+        let diagnostics = Diagnostics::default();
         let path = Path {
             leading_colon: None,
             segments: Punctuated::default(),
@@ -51,9 +92,41 @@ where
                 solving_context,
                 macro_context,
                 &self.path_mapper,
+                &self.config,
+                &diagnostics,
             )?;
         let mut all_results: Vec<ModuleStepResultData> = Vec::new();
-        extractor(&mut all_results, res);
+        let mut errors: Vec<TsExportError> = Vec::new();
+        extractor(&mut all_results, &mut errors, res);
+
+        let mut reference_errors =
+            declarations::apply_declaration_attributes(&mut all_results, &self.path_mapper)?;
+        if !self.config.collect_errors && !reference_errors.is_empty() {
+            return Err(reference_errors.remove(0));
+        }
+        errors.append(&mut reference_errors);
+
+        let mut cycle_errors =
+            cycles::reject_cross_module_cycles(&mut all_results, &self.path_mapper)?;
+        if !self.config.collect_errors && !cycle_errors.is_empty() {
+            return Err(cycle_errors.remove(0));
+        }
+        errors.append(&mut cycle_errors);
+
+        if let Some(roots) = &self.config.roots {
+            reachability::retain_reachable(&mut all_results, roots, &self.path_mapper)?;
+        }
+
+        if let ImportStrategy::Relative { js_extension } = self.config.import_strategy {
+            imports::relative_imports(&mut all_results, js_extension, &self.path_mapper)?;
+        }
+
+        if self.config.type_only_imports {
+            all_results
+                .iter_mut()
+                .flat_map(|result| result.imports.iter_mut())
+                .for_each(|import| import.type_only = true);
+        }
 
         for result_data in all_results.into_iter() {
             if result_data.imports.is_empty() && result_data.exports.is_empty() {
@@ -62,16 +135,928 @@ where
             self.exporter.export_module(result_data)?;
         }
 
-        self.exporter.finish();
+        let exported = if errors.is_empty() {
+            self.exporter.finish()?
+        } else {
+            self.exporter.finish_incomplete()?
+        };
 
-        Ok(())
+        Ok(PipelineOutput {
+            warnings: diagnostics.into_warnings(),
+            exported,
+            errors,
+        })
     }
 }
 
 /// TODO: refactor this to a closure
-fn extractor(all: &mut Vec<ModuleStepResultData>, iter: ModuleStepResult) {
+fn extractor(
+    all: &mut Vec<ModuleStepResultData>,
+    errors: &mut Vec<TsExportError>,
+    iter: ModuleStepResult,
+) {
     iter.children
         .into_iter()
-        .for_each(|child| extractor(all, child));
+        .for_each(|child| extractor(all, errors, child));
+    errors.extend(iter.errors);
     all.push(iter.data);
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        contexts::type_solving::TypeSolvingContextBuilder,
+        diagnostics::WarningKind,
+        lints::{Lint, LintConfig, LintLevel},
+        pipeline::cache::ModuleCache,
+        pipeline::module_step::ModuleStep,
+        utils::{display_path::DisplayPath, temp_dir::TempDir},
+    };
+
+    const WARNINGS_SOURCE: &str = r#"
+        use crate::models::*;
+
+        #[derive(Serialize)]
+        struct A {
+            user: User,
+        }
+    "#;
+
+    const SOURCE: &str = r#"
+        #[derive(Serialize)]
+        struct A {
+            callback: fn(),
+        }
+
+        #[derive(Serialize)]
+        struct B(u32);
+
+        #[derive(Serialize)]
+        struct C(fn(u32));
+    "#;
+
+    fn launch(
+        config: PipelineConfig,
+        output: &mut Vec<String>,
+    ) -> Result<PipelineOutput, TsExportError> {
+        launch_source(SOURCE, config, output)
+    }
+
+    /// Launches the pipeline on a single root module, giving its exports
+    fn launch_source(
+        source: &str,
+        config: PipelineConfig,
+        output: &mut Vec<String>,
+    ) -> Result<PipelineOutput, TsExportError> {
+        let mut modules = Vec::new();
+        let result = launch_reader(ModulesReader::new(&[("", source)]), config, &mut modules);
+        output.extend(exports(&modules));
+        result
+    }
+
+    /// The kind and the line of each warning
+    fn warning_lines(warnings: Vec<Warning>) -> Vec<(WarningKind, usize)> {
+        warnings
+            .into_iter()
+            .map(|warning| (warning.kind, warning.location.unwrap().start.line))
+            .collect()
+    }
+
+    /// Reads each module from its Rust source, given by its path, the root module being `""`
+    struct ModulesReader<'a> {
+        modules: &'a [(&'a str, &'a str)],
+        /// The hash given to all the sources instead of their own, see [ModuleStep::with_source_hash]
+        source_hash: Option<u64>,
+    }
+
+    impl<'a> ModulesReader<'a> {
+        fn new(modules: &'a [(&'a str, &'a str)]) -> Self {
+            ModulesReader {
+                modules,
+                source_hash: None,
+            }
+        }
+    }
+
+    impl PipelineStepSpawner for ModulesReader<'_> {
+        type Error = TsExportError;
+
+        fn create_process(&self, path: Path) -> Result<Option<ModuleStep>, TsExportError> {
+            let module = DisplayPath(&path).to_string();
+            let source = match self.modules.iter().find(|(other, _)| *other == module) {
+                Some((_, source)) => source.to_string(),
+                None => return Ok(None),
+            };
+            let module_step = ModuleStep::from_source(path, source, "crate");
+            Ok(Some(match self.source_hash {
+                Some(source_hash) => module_step.with_source_hash(source_hash),
+                None => module_step,
+            }))
+        }
+    }
+
+    /// The path of each module, with its imports and exports
+    type ModuleOutput = Vec<(String, Vec<String>)>;
+
+    /// Exports the imports and the exports of each module
+    struct ModuleExporter<'a>(&'a mut ModuleOutput);
+
+    impl Exporter for ModuleExporter<'_> {
+        type Error = TsExportError;
+        type Output = ();
+
+        fn export_module(
+            &mut self,
+            process_result: ModuleStepResultData,
+        ) -> Result<(), Self::Error> {
+            let statements = process_result
+                .imports
+                .iter()
+                .map(ToString::to_string)
+                .chain(process_result.exports.iter().map(ToString::to_string))
+                .collect();
+            self.0
+                .push((DisplayPath(&process_result.path).to_string(), statements));
+            Ok(())
+        }
+
+        fn finish(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// The imports of all the modules
+    fn imports(output: &ModuleOutput) -> Vec<String> {
+        output
+            .iter()
+            .flat_map(|(_, statements)| statements.iter())
+            .filter(|statement| statement.starts_with("import "))
+            .cloned()
+            .collect()
+    }
+
+    /// The exports of all the modules
+    fn exports(output: &ModuleOutput) -> Vec<String> {
+        output
+            .iter()
+            .flat_map(|(_, statements)| statements.iter())
+            .filter(|statement| !statement.starts_with("import "))
+            .cloned()
+            .collect()
+    }
+
+    fn launch_reader(
+        reader: ModulesReader,
+        config: PipelineConfig,
+        output: &mut ModuleOutput,
+    ) -> Result<PipelineOutput, TsExportError> {
+        let solving_context = TypeSolvingContextBuilder::default()
+            .add_default_solvers()
+            .finish();
+        Pipeline {
+            pipeline_step_spawner: reader,
+            exporter: ModuleExporter(output),
+            path_mapper: PathMapper::default(),
+            config,
+        }
+        .launch(&solving_context, &MacroSolvingContext::default())
+    }
+
+    fn launch_modules(
+        modules: &[(&str, &str)],
+        config: PipelineConfig,
+    ) -> Result<PipelineOutput<ModuleOutput>, TsExportError> {
+        let mut output = Vec::new();
+        let result = launch_reader(ModulesReader::new(modules), config, &mut output)?;
+        Ok(result.map(|_| output))
+    }
+
+    #[test]
+    fn should_stop_at_first_error() {
+        let mut output = Vec::new();
+        let result = launch(PipelineConfig::default(), &mut output);
+        assert!(matches!(
+            result.unwrap_err().without_location(),
+            TsExportError::UnsolvedField(_)
+        ));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn should_collect_errors() {
+        let mut output = Vec::new();
+        let config = PipelineConfig {
+            collect_errors: true,
+            ..Default::default()
+        };
+        let errors = launch(config, &mut output).unwrap().errors;
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].location().unwrap().start.line, 4);
+        assert_eq!(errors[1].location().unwrap().start.line, 11);
+        assert_eq!(output, vec!["export type B = number;"]);
+    }
+
+    #[test]
+    fn should_report_warnings() {
+        let mut output = Vec::new();
+        let warnings = launch_source(WARNINGS_SOURCE, PipelineConfig::default(), &mut output)
+            .unwrap()
+            .warnings;
+        assert_eq!(
+            warning_lines(warnings),
+            vec![
+                (
+                    WarningKind::GlobImportIgnored("crate::models::*".to_string()),
+                    2
+                ),
+                (WarningKind::UnknownType("User".to_string()), 6),
+            ]
+        );
+    }
+
+    const INTERNALLY_TAGGED_SOURCE: &str = r#"
+        #[derive(Serialize)]
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+
+        #[derive(Serialize)]
+        struct Meters(u32);
+
+        #[derive(Serialize)]
+        struct Location(Point);
+
+        #[derive(Serialize)]
+        enum Color {
+            Red,
+            Green,
+        }
+
+        #[derive(Serialize)]
+        enum Shape {
+            Circle { radius: u32 },
+        }
+
+        #[derive(Serialize)]
+        struct Nothing;
+
+        #[derive(Serialize)]
+        #[serde(tag = "kind")]
+        enum Valid {
+            Point(Point),
+            Location(Location),
+            Shape(Shape),
+            Empty,
+        }
+
+        #[derive(Serialize)]
+        #[serde(tag = "kind")]
+        enum Invalid {
+            Distance(Meters),
+        }
+
+        #[derive(Serialize)]
+        #[serde(tag = "type")]
+        enum Units {
+            Color(Color),
+            Nothing(Nothing),
+        }
+
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum Mixed {
+            Point(Point),
+            Nothing(Nothing),
+        }
+
+        #[derive(Serialize)]
+        #[serde(tag = "type")]
+        enum WithMixed {
+            Mixed(Mixed),
+        }
+    "#;
+
+    #[test]
+    fn should_reject_internally_tagged_non_objects() {
+        let mut output = Vec::new();
+        let config = PipelineConfig {
+            collect_errors: true,
+            ..Default::default()
+        };
+        let errors = launch_source(INTERNALLY_TAGGED_SOURCE, config, &mut output)
+            .unwrap()
+            .errors;
+        let invalid: Vec<&str> = errors
+            .iter()
+            .map(|error| match error.without_location() {
+                TsExportError::InvalidSerdeRepresentation(variant) => variant.as_str(),
+                error => panic!("Unexpected error {}", error),
+            })
+            .collect();
+        assert_eq!(invalid, vec!["Invalid::Distance"]);
+        assert!(output
+            .iter()
+            .any(|export| export.starts_with("export type Valid")));
+        assert!(!output
+            .iter()
+            .any(|export| export.starts_with("export type Invalid")));
+    }
+
+    #[test]
+    fn should_export_the_internally_tagged_unit_structs_and_variants() {
+        let mut output = Vec::new();
+        let config = PipelineConfig {
+            collect_errors: true,
+            ..Default::default()
+        };
+        launch_source(INTERNALLY_TAGGED_SOURCE, config, &mut output).unwrap();
+        let export = |name: &str| {
+            output
+                .iter()
+                .find(|export| export.starts_with(&format!("export type {} ", name)))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            export("Units"),
+            "export type Units = ( {\n\ttype: \"Color\",\n\tRed: null\n} | {\n\ttype: \"Color\",\n\tGreen: null\n} ) | ( {\n\ttype: \"Nothing\"\n} );"
+        );
+        // The points are next to the tag, the unit struct is the tag alone
+        assert_eq!(
+            export("WithMixed"),
+            "export type WithMixed = ( {\n\ttype: \"Mixed\"\n} | {\n\ttype: \"Mixed\"\n} & Exclude<Mixed, null> );"
+        );
+    }
+
+    #[test]
+    fn should_warn_about_internally_tagged_variants_of_other_modules() {
+        let mut output = Vec::new();
+        let warnings = launch_source(
+            r#"
+            use crate::models::Color;
+
+            #[derive(Serialize)]
+            #[serde(tag = "kind")]
+            enum A {
+                Color(Color),
+            }
+            "#,
+            PipelineConfig::default(),
+            &mut output,
+        )
+        .unwrap()
+        .warnings;
+        assert_eq!(
+            warning_lines(warnings),
+            vec![(
+                WarningKind::AssumedObjectVariant {
+                    ident: "A".to_string(),
+                    variant: "Color".to_string(),
+                    ts_type: "Color".to_string(),
+                },
+                7
+            )]
+        );
+    }
+
+    #[test]
+    fn should_warn_about_indistinguishable_untagged_variants() {
+        let mut output = Vec::new();
+        let warnings = launch_source(
+            r#"
+            #[derive(Serialize)]
+            #[serde(untagged)]
+            enum A {
+                Count(u32),
+                Ratio(f64),
+                Name(String),
+            }
+            "#,
+            PipelineConfig {
+                lints: {
+                    let mut lints = LintConfig::default();
+                    lints.set_level(Lint::NonFiniteFloat, LintLevel::Allow);
+                    lints
+                },
+                ..Default::default()
+            },
+            &mut output,
+        )
+        .unwrap()
+        .warnings;
+        assert_eq!(
+            warning_lines(warnings),
+            vec![(
+                WarningKind::IndistinguishableVariants {
+                    ident: "A".to_string(),
+                    first: "Count".to_string(),
+                    second: "Ratio".to_string(),
+                    ts_type: "number".to_string(),
+                },
+                6
+            )]
+        );
+    }
+
+    const RECURSIVE_SOURCE: &str = r#"
+        use std::collections::HashMap;
+
+        #[derive(Serialize)]
+        struct Tree {
+            children: Vec<Tree>,
+        }
+
+        type Json = Vec<Json>;
+
+        #[derive(Serialize)]
+        enum Expr {
+            Value(u32),
+            Neg(Box<Expr>),
+        }
+
+        type Maybe = Option<Maybe>;
+
+        type A = HashMap<String, B>;
+
+        type B = Option<A>;
+    "#;
+
+    #[test]
+    fn should_export_recursive_types() {
+        let mut output = Vec::new();
+        let config = PipelineConfig {
+            collect_errors: true,
+            ..Default::default()
+        };
+        let errors = launch_source(RECURSIVE_SOURCE, config, &mut output)
+            .unwrap()
+            .errors;
+        let cycles: Vec<(Vec<String>, usize)> = errors
+            .iter()
+            .map(|error| match error.without_location() {
+                TsExportError::UnexpressibleCycle(cycle) => {
+                    (cycle.clone(), error.location().unwrap().start.line)
+                }
+                error => panic!("Unexpected error {}", error),
+            })
+            .collect();
+        assert_eq!(
+            cycles,
+            vec![
+                (vec!["Maybe".to_string(), "Maybe".to_string()], 17),
+                (vec!["A".to_string(), "B".to_string(), "A".to_string()], 19),
+            ]
+        );
+        assert_eq!(
+            output,
+            vec![
+                "export interface Tree {\n\tchildren: Tree[]\n}",
+                "export type Json = Json[];",
+                "export type Expr = {\n\t\"Value\": number\n} | {\n\t\"Neg\": Expr\n};",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_import_types_only() {
+        let output = launch_modules(
+            &[(
+                "",
+                r#"
+                use crate::models::User;
+
+                #[derive(Serialize)]
+                struct A {
+                    user: User,
+                }
+                "#,
+            )],
+            PipelineConfig {
+                type_only_imports: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .exported;
+        assert_eq!(
+            imports(&output),
+            vec![r#"import type { User } from "crate::models";"#]
+        );
+    }
+
+    const COLLIDING_SOURCE: &str = r#"
+        use crate::b;
+
+        #[derive(Serialize)]
+        struct Status {
+            code: u32,
+        }
+
+        #[derive(Serialize)]
+        struct A {
+            local: Status,
+            a: crate::a::Status,
+            b: b::Status,
+            again: Vec<crate::a::Status>,
+        }
+    "#;
+
+    #[test]
+    fn should_alias_the_colliding_imports() {
+        let mut exports = Vec::new();
+        launch_source(COLLIDING_SOURCE, PipelineConfig::default(), &mut exports).unwrap();
+        assert_eq!(
+            exports[1],
+            "export interface A {\n\tlocal: Status,\n\ta: AStatus,\n\tb: BStatus,\n\tagain: AStatus[]\n}"
+        );
+
+        let output = launch_modules(&[("", COLLIDING_SOURCE)], PipelineConfig::default())
+            .unwrap()
+            .exported;
+        assert_eq!(
+            imports(&output),
+            vec![
+                r#"import { Status as AStatus } from "crate::a";"#,
+                r#"import { Status as BStatus } from "crate::b";"#,
+            ]
+        );
+    }
+
+    const ATTRIBUTES_MODELS: &str = r#"
+        #[derive(Serialize, Typebinder)]
+        #[typebinder(rename = "Client")]
+        struct Customer {
+            name: String,
+        }
+
+        #[derive(Serialize, Typebinder)]
+        #[typebinder(export = false)]
+        struct Handwritten {}
+
+        #[derive(Serialize, Typebinder)]
+        #[typebinder(skip)]
+        struct Internal {
+            id: u64,
+        }
+    "#;
+
+    #[test]
+    fn should_refer_to_the_renamed_declarations_of_other_modules() {
+        let output = launch_modules(
+            &[
+                ("", "mod models; mod api; mod legacy;"),
+                ("models", ATTRIBUTES_MODELS),
+                (
+                    "api",
+                    r#"
+                    use crate::models::{Customer, Handwritten};
+
+                    #[derive(Serialize)]
+                    struct Order {
+                        customer: Customer,
+                        customers: Vec<Customer>,
+                        handwritten: Handwritten,
+                    }
+                    "#,
+                ),
+                (
+                    "legacy",
+                    r#"
+                    #[derive(Serialize)]
+                    struct Client {
+                        id: u32,
+                    }
+
+                    #[derive(Serialize)]
+                    struct Order {
+                        customer: crate::models::Customer,
+                        client: Client,
+                    }
+                    "#,
+                ),
+            ],
+            PipelineConfig::default(),
+        )
+        .unwrap()
+        .exported;
+        let module = |path: &str| {
+            output
+                .iter()
+                .find(|(module, _)| module == path)
+                .map(|(_, statements)| statements.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            module("api"),
+            vec![
+                r#"import { Client } from "crate::models";"#,
+                "export interface Order {\n\tcustomer: Client,\n\tcustomers: Client[],\n\thandwritten: Handwritten\n}",
+            ]
+        );
+        // The TS name is taken by a declaration of the module
+        assert_eq!(
+            module("legacy"),
+            vec![
+                r#"import { Client as Customer } from "crate::models";"#,
+                "export interface Client {\n\tid: number\n}",
+                "export interface Order {\n\tcustomer: Customer,\n\tclient: Client\n}",
+            ]
+        );
+        assert_eq!(
+            module("models"),
+            vec!["export interface Client {\n\tname: string\n}"]
+        );
+    }
+
+    #[test]
+    fn should_alias_the_colliding_declarations_of_other_modules() {
+        let output = launch_modules(
+            &[
+                ("", "mod a; mod b; mod api;"),
+                (
+                    "a",
+                    "#[derive(Serialize)]\nstruct Status {\n    code: u32,\n}",
+                ),
+                (
+                    "b",
+                    "#[derive(Serialize)]\nstruct Status {\n    name: String,\n}",
+                ),
+                (
+                    "api",
+                    r#"
+                    use crate::b::Status;
+
+                    #[derive(Serialize)]
+                    #[serde(untagged)]
+                    enum Either {
+                        A(crate::a::Status),
+                        B(Status),
+                    }
+                    "#,
+                ),
+            ],
+            PipelineConfig::default(),
+        )
+        .unwrap();
+        // The declarations of other modules are not indistinguishable for having the same name
+        assert_eq!(output.warnings, Vec::new());
+        let api = output
+            .exported
+            .iter()
+            .find(|(module, _)| module == "api")
+            .map(|(_, statements)| statements.clone())
+            .unwrap();
+        assert_eq!(
+            api,
+            vec![
+                r#"import { Status } from "crate::a";"#,
+                r#"import { Status as BStatus } from "crate::b";"#,
+                "export type Either = Status | BStatus;",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_the_references_to_skipped_declarations() {
+        let error = launch_modules(
+            &[
+                ("", "mod models; mod api;"),
+                ("models", ATTRIBUTES_MODELS),
+                (
+                    "api",
+                    r#"
+                    #[derive(Serialize)]
+                    struct Order {
+                        internal: crate::models::Internal,
+                    }
+                    "#,
+                ),
+            ],
+            PipelineConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The type `Internal` is skipped with #[typebinder(skip)], but module api refers to it"
+        );
+
+        let error = launch_modules(
+            &[(
+                "",
+                r#"
+                #[derive(Serialize, Typebinder)]
+                #[typebinder(skip)]
+                struct Internal {}
+
+                #[derive(Serialize)]
+                struct Order {
+                    internal: Internal,
+                }
+                "#,
+            )],
+            PipelineConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.without_location().to_string(),
+            "The type `Internal` is skipped with #[typebinder(skip)], but its own module refers to it"
+        );
+    }
+
+    const SIBLING_MODULES: &[(&str, &str)] = &[
+        (
+            "",
+            "mod a; mod b; mod c; mod d; mod e; mod f; mod g; mod h;",
+        ),
+        ("a", "struct A { unknown: UnknownA }"),
+        ("b", "struct B { unknown: UnknownB }"),
+        ("c", "struct C { unknown: UnknownC }"),
+        ("d", "struct D { unknown: UnknownD }"),
+        ("e", "struct E { unknown: UnknownE }"),
+        ("f", "struct F { unknown: UnknownF }"),
+        ("g", "struct G { unknown: UnknownG }"),
+        ("h", "struct H { unknown: UnknownH }"),
+    ];
+
+    #[test]
+    fn should_give_the_same_output_and_warnings_on_each_run() {
+        let expected_modules: Vec<&str> = SIBLING_MODULES[1..]
+            .iter()
+            .map(|(module, _)| *module)
+            .collect();
+        let first = launch_modules(SIBLING_MODULES, PipelineConfig::default()).unwrap();
+        let modules: Vec<&str> = first
+            .exported
+            .iter()
+            .map(|(module, _)| module.as_str())
+            .collect();
+        assert_eq!(modules, expected_modules);
+        // The warnings are all at the same location, hence sorted by message
+        let warnings: Vec<WarningKind> = first
+            .warnings
+            .iter()
+            .map(|warning| warning.kind.clone())
+            .collect();
+        let expected_warnings: Vec<WarningKind> = expected_modules
+            .iter()
+            .map(|module| WarningKind::UnknownType(format!("Unknown{}", module.to_uppercase())))
+            .collect();
+        assert_eq!(warnings, expected_warnings);
+
+        for _ in 0..20 {
+            let output = launch_modules(SIBLING_MODULES, PipelineConfig::default()).unwrap();
+            assert_eq!(output.exported, first.exported);
+            assert_eq!(output.warnings, first.warnings);
+        }
+    }
+
+    const CROSS_MODULE_CYCLE_MODULES: &[(&str, &str)] = &[
+        ("", "mod a; mod b;"),
+        (
+            "a",
+            r#"
+            #[derive(Serialize)]
+            struct Point {
+                x: u32,
+            }
+
+            type A = Option<crate::b::B>;
+            "#,
+        ),
+        (
+            "b",
+            r#"
+            use crate::a::A;
+
+            type B = Option<A>;
+
+            type C = Option<A>;
+
+            type D = Option<Box<D>>;
+            "#,
+        ),
+    ];
+
+    #[test]
+    fn should_reject_the_cycles_across_modules() {
+        let error = launch_modules(
+            &[
+                ("", "mod a; mod b;"),
+                ("a", "type A = Option<crate::b::B>;"),
+                ("b", "type B = Option<crate::a::A>;"),
+            ],
+            PipelineConfig::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            TsExportError::UnexpressibleCycle(cycle) if cycle == vec!["a::A", "b::B", "a::A"]
+        ));
+
+        let output = launch_modules(
+            CROSS_MODULE_CYCLE_MODULES,
+            PipelineConfig {
+                collect_errors: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let cycles: Vec<&Vec<String>> = output
+            .errors
+            .iter()
+            .map(|error| match error.without_location() {
+                TsExportError::UnexpressibleCycle(cycle) => cycle,
+                error => panic!("Unexpected error {}", error),
+            })
+            .collect();
+        assert_eq!(cycles, vec![&vec!["D", "D"], &vec!["a::A", "b::B", "a::A"]]);
+        // `C` refers to the rejected `A`, but is not part of the cycle
+        assert_eq!(
+            output.exported,
+            vec![
+                (
+                    "a".to_string(),
+                    vec!["export interface Point {\n\tx: number\n}".to_string()]
+                ),
+                (
+                    "b".to_string(),
+                    vec![
+                        r#"import { A } from "crate::a";"#.to_string(),
+                        "export type C = A | null;".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reuse_cached_modules() {
+        let directory = TempDir::new("should_reuse_cached_modules");
+        let launch_hashed = |source: &str, hash: u64, output: &mut Vec<String>| {
+            let reader = ModulesReader {
+                modules: &[("", source)],
+                source_hash: Some(hash),
+            };
+            let config = PipelineConfig {
+                cache: Some(ModuleCache::new(directory.path())),
+                ..Default::default()
+            };
+            let mut modules = Vec::new();
+            let result = launch_reader(reader, config, &mut modules).unwrap();
+            output.extend(exports(&modules));
+            result
+        };
+
+        let mut first = Vec::new();
+        let first_warnings = launch_hashed(WARNINGS_SOURCE, 1, &mut first).warnings;
+        assert_eq!(first_warnings.len(), 2);
+
+        // Same hash : the cached exports and warnings are replayed, whatever the source
+        let mut second = Vec::new();
+        let second_warnings = launch_hashed("struct Unused;", 1, &mut second).warnings;
+        assert_eq!(second, first);
+        assert_eq!(second_warnings, first_warnings);
+
+        // The cached module is not parsed again
+        let mut unparsed = Vec::new();
+        launch_hashed("not Rust {", 1, &mut unparsed);
+        assert_eq!(unparsed, first);
+
+        // Another hash : the module is exported again
+        let mut third = Vec::new();
+        launch_hashed("#[derive(Serialize)]\nstruct B { b: u32 }", 2, &mut third);
+        assert_eq!(third, vec!["export interface B {\n\tb: number\n}"]);
+    }
+
+    #[test]
+    fn should_reuse_the_cached_modules_with_raw_identifiers() {
+        let directory = TempDir::new("should_reuse_the_cached_modules_with_raw_identifiers");
+        let modules: &[(&str, &str)] = &[
+            ("", "mod r#type;"),
+            (
+                "r#type",
+                "#[derive(Serialize)]\nstruct Kind {\n    name: String,\n}",
+            ),
+        ];
+        let launch_cached = || {
+            let config = PipelineConfig {
+                cache: Some(ModuleCache::new(directory.path())),
+                ..Default::default()
+            };
+            launch_modules(modules, config).unwrap().exported
+        };
+
+        let first = launch_cached();
+        assert_eq!(
+            first,
+            vec![(
+                "r#type".to_string(),
+                vec!["export interface Kind {\n\tname: string\n}".to_string()]
+            )]
+        );
+        // The child modules of the cached root module are declared again from their names
+        assert_eq!(launch_cached(), first);
+    }
+}
@@ -1,21 +1,36 @@
-use std::str::FromStr;
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
 use crate::{
-    contexts::import::ImportContext,
+    attributes::DeclarationAttributes,
+    contexts::import::{find_glob_imports, ImportContext},
     contexts::{exporter::ExporterContext, type_solving::TypeSolvingContext},
+    diagnostics::{Diagnostics, Warning, WarningKind},
     error::TsExportError,
+    lints::{check_container, check_type_alias, LintConfig, LintLevel, LintViolation, TypeAliases},
     macros::context::MacroSolvingContext,
     path_mapper::PathMapper,
+    pipeline::{
+        cache::{hash_source, CachedModule},
+        config::PipelineConfig,
+        cycles::find_unexpressible_cycles,
+    },
     step_spawner::PipelineStepSpawner,
     type_solving::ImportEntry,
+    utils::display_path::DisplayPath,
 };
 use indexmap::{IndexMap, IndexSet};
+use proc_macro2::Span;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
 use serde_derive_internals::{ast::Container, Ctxt, Derive};
-use syn::{DeriveInput, Item, ItemMacro, ItemMod, ItemType, Path, PathArguments, PathSegment};
+use syn::{
+    spanned::Spanned, DeriveInput, Ident, Item, ItemMacro, ItemMod, ItemType, Path, PathArguments,
+    PathSegment, Visibility,
+};
 use ts_json_subset::{
     export::ExportStatement,
     ident::{IdentError, TSIdent},
-    import::{ImportKind, ImportList, ImportStatement},
+    import::{ImportKind, ImportList, ImportSpecifier, ImportStatement},
 };
 
 /// A step of the pipeline that is generated by a PipelineStepSpawner.
@@ -32,182 +47,693 @@ use ts_json_subset::{
 /// This is the result of this ModuleStep, that is given back to the caller to be handled by an Exporter.
 pub struct ModuleStep {
     current_path: Path,
-    items: Vec<Item>,
-    import_context: ImportContext,
+    source: ModuleSource,
+    crate_name: String,
+    source_file: Option<PathBuf>,
+    source_hash: Option<u64>,
+}
+
+/// The Rust module, parsed or not
+enum ModuleSource {
+    Items(Vec<Item>),
+    /// Parsed when launched, unless the module is cached
+    Text(String),
 }
 
 impl ModuleStep {
     pub fn new(current_path: syn::Path, items: Vec<Item>, crate_name: &str) -> Self {
-        let mut import_context = ImportContext::default();
-        import_context.parse_imported(&items, crate_name);
-        import_context.parse_scoped(&items);
+        ModuleStep {
+            current_path,
+            source: ModuleSource::Items(items),
+            crate_name: crate_name.to_string(),
+            source_file: None,
+            source_hash: None,
+        }
+    }
 
+    /// A module read from its Rust source, that is hashed to make it cacheable (see [ModuleStep::with_source_hash]).
+    ///
+    /// The source is parsed when launched, unless the module is found in the cache.
+    pub fn from_source(current_path: syn::Path, source: String, crate_name: &str) -> Self {
         ModuleStep {
             current_path,
-            items,
-            import_context,
+            source_hash: Some(hash_source(&source)),
+            source: ModuleSource::Text(source),
+            crate_name: crate_name.to_string(),
+            source_file: None,
+        }
+    }
+
+    /// Sets the Rust file the module was read from, used to locate errors
+    pub fn with_source_file(mut self, source_file: PathBuf) -> Self {
+        self.source_file = Some(source_file);
+        self
+    }
+
+    /// Sets the hash of the Rust source of the module, which makes it cacheable, see [ModuleCache](crate::pipeline::cache::ModuleCache)
+    pub fn with_source_hash(mut self, source_hash: u64) -> Self {
+        self.source_hash = Some(source_hash);
+        self
+    }
+
+    /// Declares an external child module, as if `mod <ident>;` was written in the Rust module.
+    ///
+    /// Fails if the Rust module already declares a child module of the same name.
+    pub(crate) fn add_module_declaration(&mut self, ident: Ident) -> Result<(), TsExportError> {
+        let items = self.items()?;
+        let declared = items
+            .iter()
+            .any(|item| matches!(item, Item::Mod(item_mod) if item_mod.ident == ident));
+        if declared {
+            return Err(TsExportError::ModuleNameCollision(ident.to_string()));
+        }
+        items.push(Item::Mod(external_module(ident)));
+        Ok(())
+    }
+
+    /// Parses the module if it is not parsed yet
+    fn items(&mut self) -> Result<&mut Vec<Item>, TsExportError> {
+        if let ModuleSource::Text(source) = &self.source {
+            let items = parse_source(source, self.source_file.as_deref())?;
+            self.source = ModuleSource::Items(items);
+        }
+        match &mut self.source {
+            ModuleSource::Items(items) => Ok(items),
+            ModuleSource::Text(_) => unreachable!("The module was just parsed"),
         }
     }
 
-    pub fn launch<PSS: PipelineStepSpawner>(
+    pub fn launch<PSS: PipelineStepSpawner + Sync>(
         self,
         process_spawner: &PSS,
         solving_context: &TypeSolvingContext,
         macro_context: &MacroSolvingContext,
         path_mapper: &PathMapper,
+        config: &PipelineConfig,
+        diagnostics: &Diagnostics,
     ) -> Result<ModuleStepResult, TsExportError> {
         let ModuleStep {
             current_path,
-            import_context,
-            items,
+            source,
+            crate_name,
+            source_file,
+            source_hash,
         } = self;
 
-        let mut derive_inputs: Vec<(usize, DeriveInput)> = Vec::new();
-        let mut type_aliases: Vec<(usize, ItemType)> = Vec::new();
-        let mut mod_declarations: Vec<ItemMod> = Vec::new();
-        let mut macros: Vec<(usize, ItemMacro)> = Vec::new();
+        let source_file = source_file.as_deref();
+        let cache_key = match (&config.cache, source_hash) {
+            (Some(cache), Some(source_hash)) => Some((
+                cache,
+                cache.key(source_hash, &current_path, &crate_name, config, path_mapper),
+            )),
+            _ => None,
+        };
+        let cached = cache_key.and_then(|(cache, key)| cache.get(key));
+
+        // A cached module is not parsed when its child modules are known
+        let (mod_declarations, items): (Vec<ItemMod>, Vec<Item>) = match (
+            cached.as_ref().and_then(|cached| cached.modules.as_ref()),
+            source,
+        ) {
+            (Some(modules), ModuleSource::Text(_)) => (
+                modules
+                    .iter()
+                    .map(|module| external_module(ident_from_segment(module)))
+                    .collect(),
+                Vec::new(),
+            ),
+            (_, source) => {
+                let items = match source {
+                    ModuleSource::Items(items) => items,
+                    ModuleSource::Text(source) => parse_source(&source, source_file)?,
+                };
+                let (mod_declarations, items): (Vec<Item>, Vec<Item>) = items
+                    .into_iter()
+                    .partition(|item| matches!(item, Item::Mod(_)));
+                let mod_declarations = mod_declarations
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        Item::Mod(item) => Some(item),
+                        _ => None,
+                    })
+                    .collect();
+                (mod_declarations, items)
+            }
+        };
+        let modules: Option<Vec<String>> = mod_declarations
+            .iter()
+            .map(|item_mod| {
+                item_mod
+                    .content
+                    .is_none()
+                    .then(|| item_mod.ident.to_string())
+            })
+            .collect();
+
+        let mut errors: Vec<TsExportError> = Vec::new();
 
-        items
-            .into_iter()
+        // External modules are read and exported in parallel. syn's types can't be sent between threads :
+        // the paths are sent as text, and the results are converted to a SendableResult.
+        // Inline modules are exported on the current thread, as their items are already parsed.
+        let external_modules: Vec<(usize, Vec<String>)> = mod_declarations
+            .iter()
             .enumerate()
-            .for_each(|(index, item)| match item {
-                Item::Enum(item) => derive_inputs.push((index, DeriveInput::from(item))),
-                Item::Struct(item) => derive_inputs.push((index, DeriveInput::from(item))),
-                Item::Type(item) => {
-                    type_aliases.push((index, item));
-                }
-                Item::Mod(item) => {
-                    mod_declarations.push(item);
-                }
-                Item::Macro(item) => {
-                    macros.push((index, item));
-                }
-                _ => {}
-            });
-
-        let children: Vec<ModuleStepResult> = mod_declarations
-            .into_iter()
-            .filter_map(|item_mod| {
-                let ident = item_mod.ident;
-                let mut path = current_path.clone();
-                path.segments.push(PathSegment {
-                    ident,
-                    arguments: PathArguments::None,
-                });
-                match item_mod.content {
-                    Some((_, items)) => Some(Ok(ModuleStep::new(path, items, "crate"))),
-                    _ => process_spawner
-                        .create_process(path)
+            .filter(|(_, item_mod)| item_mod.content.is_none())
+            .map(|(index, item_mod)| {
+                let segments = current_path
+                    .segments
+                    .iter()
+                    .chain(std::iter::once(&PathSegment {
+                        ident: item_mod.ident.clone(),
+                        arguments: PathArguments::None,
+                    }))
+                    .map(|segment| segment.ident.to_string())
+                    .collect();
+                (index, segments)
+            })
+            .collect();
+        let mut external_results: HashMap<usize, Result<Option<SendableResult>, TsExportError>> =
+            external_modules
+                .into_par_iter()
+                .map(|(index, segments)| {
+                    let result = process_spawner
+                        .create_process(path_from_segments(&segments))
                         .map_err(|e| e.into())
+                        .and_then(|process_module| {
+                            process_module
+                                .map(|process_module| {
+                                    process_module
+                                        .launch(
+                                            process_spawner,
+                                            solving_context,
+                                            macro_context,
+                                            path_mapper,
+                                            config,
+                                            diagnostics,
+                                        )
+                                        .map(SendableResult::from)
+                                })
+                                .transpose()
+                        });
+                    (index, result)
+                })
+                .collect();
+
+        let children_results =
+            mod_declarations
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, item_mod)| match item_mod.content {
+                    Some((_, items)) => {
+                        let mut path = current_path.clone();
+                        path.segments.push(PathSegment {
+                            ident: item_mod.ident,
+                            arguments: PathArguments::None,
+                        });
+                        let mut module_step = ModuleStep::new(path, items, "crate");
+                        module_step.source_file = source_file.map(ToOwned::to_owned);
+                        module_step.source_hash = source_hash;
+                        Some(module_step.launch(
+                            process_spawner,
+                            solving_context,
+                            macro_context,
+                            path_mapper,
+                            config,
+                            diagnostics,
+                        ))
+                    }
+                    None => external_results
+                        .remove(&index)
+                        .expect("External module not exported")
+                        .map(|result| result.map(ModuleStepResult::from))
                         .transpose(),
+                });
+        let mut children: Vec<ModuleStepResult> = Vec::new();
+        for child in children_results {
+            match child {
+                Ok(child) => children.push(child),
+                Err(error) if config.collect_errors => errors.push(error),
+                Err(error) => return Err(error),
+            }
+        }
+
+        let ModuleExports {
+            exports,
+            imports,
+            dependencies,
+            declaration_attributes,
+        } = match cached {
+            Some(CachedModule {
+                exports, warnings, ..
+            }) => {
+                log::info!("Module {} is up to date", DisplayPath(&current_path));
+                warnings
+                    .into_iter()
+                    .for_each(|warning| diagnostics.warn(warning));
+                exports
+            }
+            None => {
+                // The warnings of the module are cached along with its exports
+                let module_diagnostics = Diagnostics::default();
+                let result = export_items(
+                    items,
+                    &crate_name,
+                    solving_context,
+                    macro_context,
+                    path_mapper,
+                    config,
+                    &module_diagnostics,
+                    source_file,
+                );
+                let warnings = module_diagnostics.into_warnings();
+                warnings
+                    .iter()
+                    .cloned()
+                    .for_each(|warning| diagnostics.warn(warning));
+                let (exports, mut item_errors) = result?;
+                match cache_key {
+                    Some((cache, key)) if item_errors.is_empty() => {
+                        let cached = CachedModule {
+                            exports,
+                            warnings,
+                            modules,
+                        };
+                        cache.put(key, &cached);
+                        errors.append(&mut item_errors);
+                        cached.exports
+                    }
+                    _ => {
+                        errors.append(&mut item_errors);
+                        exports
+                    }
                 }
-            })
-            .map(|process_module_result| {
-                process_module_result.and_then(|process_module| {
-                    process_module.launch(
-                        process_spawner,
-                        solving_context,
-                        macro_context,
-                        path_mapper,
-                    )
-                })
-            })
-            .collect::<Result<_, _>>()?;
+            }
+        };
+
+        Ok(ModuleStepResult {
+            data: ModuleStepResultData {
+                exports,
+                imports,
+                path: current_path,
+                dependencies,
+                crate_name,
+                declaration_attributes,
+            },
+            children,
+            errors,
+        })
+    }
+}
+
+/// `mod <ident>;`
+fn external_module(ident: Ident) -> ItemMod {
+    ItemMod {
+        attrs: Vec::new(),
+        vis: Visibility::Inherited,
+        mod_token: Default::default(),
+        ident,
+        content: None,
+        semi: Some(Default::default()),
+    }
+}
+
+fn parse_source(
+    source: &str,
+    source_file: Option<&std::path::Path>,
+) -> Result<Vec<Item>, TsExportError> {
+    syn::parse_file(source)
+        .map(|file| file.items)
+        .map_err(|e| TsExportError::from(e).with_location(source_file, Span::call_site()))
+}
+
+/// The exports of a module, without its child modules
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ModuleExports {
+    exports: Vec<ExportStatement>,
+    imports: Vec<ImportStatement>,
+    dependencies: Vec<Vec<ImportEntry>>,
+    declaration_attributes: DeclarationAttributes,
+}
+
+/// Exports the declarations of a module. The errors are collected if [PipelineConfig::collect_errors] is set.
+#[allow(clippy::too_many_arguments)]
+fn export_items(
+    items: Vec<Item>,
+    crate_name: &str,
+    solving_context: &TypeSolvingContext,
+    macro_context: &MacroSolvingContext,
+    path_mapper: &PathMapper,
+    config: &PipelineConfig,
+    diagnostics: &Diagnostics,
+    source_file: Option<&std::path::Path>,
+) -> Result<(ModuleExports, Vec<TsExportError>), TsExportError> {
+    let mut import_context = ImportContext::default();
+    import_context.parse_imported(&items, crate_name);
+    import_context.parse_scoped(&items);
 
-        let ctxt = Ctxt::default();
-        let containers = derive_inputs.iter().filter_map(|(index, derive_input)| {
-            Container::from_ast(&ctxt, derive_input, Derive::Serialize)
-                .map(|container| (*index, container))
+    find_glob_imports(&items)
+        .into_iter()
+        .for_each(|(glob, span)| {
+            diagnostics.warn(Warning::new(
+                WarningKind::GlobImportIgnored(glob),
+                source_file,
+                span,
+            ))
         });
 
-        let exporter = ExporterContext {
-            type_solving_context: solving_context,
-            macro_context,
-            import_context,
-        };
+    let mut derive_inputs: Vec<(usize, DeriveInput)> = Vec::new();
+    let mut type_aliases: Vec<(usize, ItemType)> = Vec::new();
+    let mut macros: Vec<(usize, ItemMacro)> = Vec::new();
+    let mut spans: HashMap<usize, Span> = HashMap::new();
 
-        let type_export_statements = type_aliases.into_iter().map(|(index, item)| {
-            exporter
+    items
+        .into_iter()
+        .enumerate()
+        .for_each(|(index, item)| match item {
+            Item::Enum(item) => {
+                spans.insert(index, item.ident.span());
+                derive_inputs.push((index, DeriveInput::from(item)))
+            }
+            Item::Struct(item) => {
+                spans.insert(index, item.ident.span());
+                derive_inputs.push((index, DeriveInput::from(item)))
+            }
+            Item::Type(item) => {
+                spans.insert(index, item.ident.span());
+                type_aliases.push((index, item));
+            }
+            Item::Macro(item) => {
+                macros.push((index, item));
+            }
+            _ => {}
+        });
+
+    let mut errors: Vec<TsExportError> = Vec::new();
+
+    let ctxt = Ctxt::default();
+    let containers = derive_inputs.iter().filter_map(|(index, derive_input)| {
+        Container::from_ast(&ctxt, derive_input, Derive::Serialize)
+            .map(|container| (*index, container))
+    });
+
+    let declaration_attributes = import_context.declaration_attributes().clone();
+    let exporter = ExporterContext {
+        type_solving_context: solving_context,
+        macro_context,
+        import_context,
+        diagnostics,
+        source_file,
+    };
+
+    let aliases = TypeAliases::new(type_aliases.iter().map(|(_, item)| item));
+    let report = |violations: Vec<LintViolation>| {
+        report_lints(violations, &config.lints, diagnostics, source_file)
+    };
+    let type_export_statements = type_aliases.into_iter().map(|(index, item)| {
+        let span = item.ident.span();
+        let denied = report(check_type_alias(&item, &aliases, &config.lints));
+        match denied.len() {
+            0 => exporter
                 .export_statements_from_type_alias(item)
                 .map(|statements| (index, statements))
-        });
-        let container_statements = containers.into_iter().map(|(index, container)| {
-            exporter
+                .map_err(|e| e.with_location(source_file, span)),
+            1 => Err(denied.into_iter().next().unwrap()),
+            _ => Err(TsExportError::Multiple(denied)),
+        }
+    });
+    let container_statements = containers.into_iter().map(|(index, container)| {
+        let span = container.ident.span();
+        let denied = report(check_container(&container, &aliases, &config.lints));
+        match denied.len() {
+            0 => exporter
                 .export_statements_from_container(container)
                 .map(|statements| (index, statements))
-        });
-        let macros_statements = macros.into_iter().map(|(index, item)| {
-            exporter
-                .export_statements_from_macro(&item.into())
-                .map(|statements| (index, statements))
-        });
+                .map_err(|e| e.with_location(source_file, span)),
+            1 => Err(denied.into_iter().next().unwrap()),
+            _ => Err(TsExportError::Multiple(denied)),
+        }
+    });
+    let macros_statements = macros.into_iter().map(|(index, item)| {
+        let span = item.mac.path.span();
+        exporter
+            .export_statements_from_macro(&item.into())
+            .map(|statements| (index, statements))
+            .map_err(|e| e.with_location(source_file, span))
+    });
 
-        let mut imports: Vec<ImportEntry> = Vec::new();
+    let mut statements: Vec<(usize, Vec<ExportStatement>, Vec<ImportEntry>)> = Vec::new();
+    for result in type_export_statements
+        .chain(container_statements)
+        .chain(macros_statements)
+    {
+        match result {
+            Ok((index, solved)) => statements.push((index, solved.inner, solved.import_entries)),
+            Err(TsExportError::Multiple(mut multiple)) if config.collect_errors => {
+                errors.append(&mut multiple)
+            }
+            Err(error) if config.collect_errors => errors.push(error),
+            Err(error) => return Err(error),
+        }
+    }
 
-        let mut statements: Vec<(usize, Vec<ExportStatement>)> = type_export_statements
-            .chain(container_statements)
-            .chain(macros_statements)
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .map(|(index, mut solved)| {
-                imports.append(&mut solved.import_entries);
-                (index, solved.inner)
+    let cycles = find_unexpressible_cycles(
+        statements
+            .iter()
+            .flat_map(|(_, statements, _)| statements.iter()),
+    );
+    for cycle in cycles {
+        let declares_cycle = |statements: &[ExportStatement]| {
+            statements.iter().any(|statement| {
+                statement
+                    .declared_idents()
+                    .iter()
+                    .any(|ident| cycle.contains(&ident.to_string()))
             })
-            .collect();
+        };
+        let span = statements
+            .iter()
+            .find(|(_, statements, _)| declares_cycle(statements))
+            .and_then(|(index, _, _)| spans.get(index));
+        let error = TsExportError::UnexpressibleCycle(cycle.clone());
+        let error = match span {
+            Some(span) => error.with_location(source_file, *span),
+            None => error,
+        };
+        if !config.collect_errors {
+            return Err(error);
+        }
+        errors.push(error);
+        statements.retain(|(_, statements, _)| !declares_cycle(statements));
+    }
 
-        let mut all_imports: IndexMap<String, IndexSet<String>> = IndexMap::default();
-        imports.into_iter().for_each(|entry| {
-            let hm_entry = all_imports.entry(entry.path).or_default();
-            hm_entry.insert(entry.ident);
-        });
+    let imports = import_statements(
+        statements
+            .iter()
+            .flat_map(|(_, _, import_entries)| import_entries.iter().cloned()),
+        path_mapper,
+    )?;
 
-        let imports: Vec<ImportStatement> = all_imports
-            .into_iter()
-            .filter_map(|(path, items)| {
-                let items: Result<Vec<TSIdent>, IdentError> =
-                    items.into_iter().map(|i| TSIdent::from_str(&i)).collect();
-                match items {
-                    Ok(items) => {
-                        let path = path_mapper.get(&path).unwrap_or(path);
-                        if path.is_empty() {
-                            None
-                        } else {
-                            Some(Ok(ImportStatement {
-                                path: format!("\"{}\"", path),
-                                import_kind: ImportKind::ImportList(ImportList { items }),
-                            }))
-                        }
-                    }
-                    Err(e) => Some(Err(e)),
-                }
-            })
-            .collect::<Result<Vec<ImportStatement>, _>>()?;
+    statements.sort_by_key(|(index, _, _)| *index);
 
-        statements.sort_by_key(|(index, _)| *index);
+    let (exports, dependencies): (Vec<ExportStatement>, Vec<Vec<ImportEntry>>) = statements
+        .into_iter()
+        .flat_map(|(_, statements, import_entries)| {
+            statements
+                .into_iter()
+                .map(move |statement| (statement, import_entries.clone()))
+        })
+        .unzip();
 
-        let exports: Vec<ExportStatement> = statements
-            .into_iter()
-            .flat_map(|(_, statements)| statements.into_iter())
-            .collect();
+    Ok((
+        ModuleExports {
+            exports,
+            imports,
+            dependencies,
+            declaration_attributes,
+        },
+        errors,
+    ))
+}
 
-        Ok(ModuleStepResult {
-            data: ModuleStepResultData {
-                exports,
-                imports,
-                path: current_path,
+/// Reports the lint violations as warnings, or gives them back as errors when their lint is denied
+fn report_lints(
+    violations: Vec<LintViolation>,
+    lints: &LintConfig,
+    diagnostics: &Diagnostics,
+    source_file: Option<&std::path::Path>,
+) -> Vec<TsExportError> {
+    violations
+        .into_iter()
+        .filter_map(
+            |LintViolation {
+                 lint,
+                 message,
+                 span,
+             }| match lints.level(lint) {
+                LintLevel::Deny => Some(
+                    TsExportError::LintDenied { lint, message }.with_location(source_file, span),
+                ),
+                _ => {
+                    diagnostics.warn(Warning::new(
+                        WarningKind::Lint { lint, message },
+                        source_file,
+                        span,
+                    ));
+                    None
+                }
             },
-            children,
-        })
-    }
+        )
+        .collect()
 }
 
 pub struct ModuleStepResultData {
     pub exports: Vec<ExportStatement>,
     pub imports: Vec<ImportStatement>,
     pub path: Path,
+    /// The types each export refers to, in the same order as `exports`
+    pub dependencies: Vec<Vec<ImportEntry>>,
+    /// The name of the crate the module belongs to, as used in its resolved imports
+    pub crate_name: String,
+    /// How the other modules refer to the declarations of the module
+    pub declaration_attributes: DeclarationAttributes,
+}
+
+/// Groups the import entries by path, and maps the paths to TS import paths.
+///
+/// Entries with an empty path refer to the current module and are not imported.
+pub(crate) fn import_statements<I>(
+    import_entries: I,
+    path_mapper: &PathMapper,
+) -> Result<Vec<ImportStatement>, IdentError>
+where
+    I: IntoIterator<Item = ImportEntry>,
+{
+    group_imports(import_entries, |path| {
+        path_mapper.get(path).unwrap_or_else(|| path.to_string())
+    })
+}
+
+/// Maps the paths of the import entries to TS import paths with `map_path`, then groups the entries by TS path.
+///
+/// Entries mapped to an empty path refer to the current module and are not imported.
+pub(crate) fn group_imports<I, F>(
+    import_entries: I,
+    map_path: F,
+) -> Result<Vec<ImportStatement>, IdentError>
+where
+    I: IntoIterator<Item = ImportEntry>,
+    F: Fn(&str) -> String,
+{
+    let mut all_imports: IndexMap<String, IndexSet<(String, Option<String>)>> = IndexMap::default();
+    import_entries.into_iter().for_each(|entry| {
+        let hm_entry = all_imports.entry(map_path(&entry.path)).or_default();
+        hm_entry.insert((entry.ident, entry.alias));
+    });
+
+    all_imports
+        .into_iter()
+        .filter(|(path, _)| !path.is_empty())
+        .map(|(path, items)| {
+            let items: Vec<ImportSpecifier> = items
+                .into_iter()
+                .map(|(ident, alias)| {
+                    Ok(ImportSpecifier {
+                        ident: TSIdent::from_str(&ident)?,
+                        alias: alias.as_deref().map(TSIdent::from_str).transpose()?,
+                    })
+                })
+                .collect::<Result<_, IdentError>>()?;
+            Ok(ImportStatement {
+                path: format!("\"{}\"", path),
+                import_kind: ImportKind::ImportList(ImportList { items }),
+                type_only: false,
+            })
+        })
+        .collect()
 }
 
 pub struct ModuleStepResult {
     pub data: ModuleStepResultData,
     pub children: Vec<ModuleStepResult>,
+    /// The errors that were collected instead of stopping the module, see [PipelineConfig::collect_errors]
+    pub errors: Vec<TsExportError>,
+}
+
+/// A [ModuleStepResult] that can be sent to another thread, its Rust path being kept as text
+struct SendableResult {
+    path: Vec<String>,
+    exports: Vec<ExportStatement>,
+    imports: Vec<ImportStatement>,
+    dependencies: Vec<Vec<ImportEntry>>,
+    crate_name: String,
+    declaration_attributes: DeclarationAttributes,
+    children: Vec<SendableResult>,
+    errors: Vec<TsExportError>,
+}
+
+impl From<ModuleStepResult> for SendableResult {
+    fn from(result: ModuleStepResult) -> Self {
+        let ModuleStepResult {
+            data,
+            children,
+            errors,
+        } = result;
+        SendableResult {
+            path: data
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect(),
+            exports: data.exports,
+            imports: data.imports,
+            dependencies: data.dependencies,
+            crate_name: data.crate_name,
+            declaration_attributes: data.declaration_attributes,
+            children: children.into_iter().map(SendableResult::from).collect(),
+            errors,
+        }
+    }
+}
+
+impl From<SendableResult> for ModuleStepResult {
+    fn from(result: SendableResult) -> Self {
+        ModuleStepResult {
+            data: ModuleStepResultData {
+                exports: result.exports,
+                imports: result.imports,
+                path: path_from_segments(&result.path),
+                dependencies: result.dependencies,
+                crate_name: result.crate_name,
+                declaration_attributes: result.declaration_attributes,
+            },
+            children: result
+                .children
+                .into_iter()
+                .map(ModuleStepResult::from)
+                .collect(),
+            errors: result.errors,
+        }
+    }
+}
+
+fn path_from_segments(segments: &[String]) -> Path {
+    Path {
+        leading_colon: None,
+        segments: segments
+            .iter()
+            .map(|segment| PathSegment {
+                ident: ident_from_segment(segment),
+                arguments: PathArguments::None,
+            })
+            .collect(),
+    }
+}
+
+/// The ident of a segment of a path sent as text, which is a raw ident when it is a keyword, e.g. `r#type`
+fn ident_from_segment(segment: &str) -> Ident {
+    match segment.strip_prefix("r#") {
+        Some(raw) => Ident::new_raw(raw, Span::call_site()),
+        None => Ident::new(segment, Span::call_site()),
+    }
 }
@@ -0,0 +1,262 @@
+//! Restricts the output of a pipeline to the declarations reachable from root types
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    error::TsExportError, path_mapper::PathMapper, pipeline::module_step::import_statements,
+    type_solving::ImportEntry, utils::display_path::DisplayPath,
+};
+
+use super::module_step::ModuleStepResultData;
+
+/// Position of an export : index of the module, then index of the export in the module
+type ExportPosition = (usize, usize);
+
+/// Removes the exports that are not referenced, directly or transitively, by the root types.
+///
+/// The imports of the remaining modules are computed again from the remaining exports.
+pub(crate) fn retain_reachable(
+    results: &mut [ModuleStepResultData],
+    roots: &[String],
+    path_mapper: &PathMapper,
+) -> Result<(), TsExportError> {
+    let module_paths: Vec<String> = results
+        .iter()
+        .map(|result| DisplayPath(&result.path).to_string())
+        .collect();
+    let modules: HashMap<&str, usize> = module_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| (path.as_str(), index))
+        .collect();
+    let declarations: HashMap<(usize, String), ExportPosition> = results
+        .iter()
+        .enumerate()
+        .flat_map(|(module_index, result)| {
+            result
+                .exports
+                .iter()
+                .enumerate()
+                .flat_map(move |(export_index, export)| {
+                    export.declared_idents().into_iter().map(move |ident| {
+                        (
+                            (module_index, ident.to_string()),
+                            (module_index, export_index),
+                        )
+                    })
+                })
+        })
+        .collect();
+
+    let mut queue: VecDeque<ExportPosition> = roots
+        .iter()
+        .map(|root| {
+            let (module_path, ident) = match root.rfind("::") {
+                Some(index) => (&root[..index], &root[index + 2..]),
+                None => ("", root.as_str()),
+            };
+            modules
+                .get(module_path)
+                .and_then(|module_index| declarations.get(&(*module_index, ident.to_string())))
+                .copied()
+                .ok_or_else(|| TsExportError::RootNotFound(root.clone()))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut reachable: HashSet<ExportPosition> = HashSet::new();
+    while let Some(position) = queue.pop_front() {
+        if !reachable.insert(position) {
+            continue;
+        }
+        let (module_index, export_index) = position;
+        let result = &results[module_index];
+        for entry in &result.dependencies[export_index] {
+            let dependency = resolve_module(&modules, &module_paths[module_index], result, entry)
+                .and_then(|index| declarations.get(&(index, entry.ident.clone())));
+            if let Some(dependency) = dependency {
+                queue.push_back(*dependency);
+            }
+        }
+    }
+
+    for (module_index, result) in results.iter_mut().enumerate() {
+        let exports = std::mem::take(&mut result.exports);
+        let dependencies = std::mem::take(&mut result.dependencies);
+        let (exports, dependencies): (Vec<_>, Vec<_>) = exports
+            .into_iter()
+            .zip(dependencies)
+            .enumerate()
+            .filter(|(export_index, _)| reachable.contains(&(module_index, *export_index)))
+            .map(|(_, export)| export)
+            .unzip();
+        result.imports = import_statements(dependencies.iter().flatten().cloned(), path_mapper)?;
+        result.exports = exports;
+        result.dependencies = dependencies;
+    }
+
+    Ok(())
+}
+
+/// Finds the module an import entry refers to. Returns `None` when the module is not part of the pipeline.
+pub(crate) fn resolve_module(
+    modules: &HashMap<&str, usize>,
+    current_module: &str,
+    result: &ModuleStepResultData,
+    entry: &ImportEntry,
+) -> Option<usize> {
+    let mut segments: Vec<&str> = entry.path.split("::").filter(|s| !s.is_empty()).collect();
+    if segments.is_empty() {
+        return modules.get(current_module).copied();
+    }
+    let current_segments = || current_module.split("::").filter(|s| !s.is_empty());
+
+    if matches!(segments[0], "self" | "super") {
+        let mut resolved: Vec<&str> = current_segments().collect();
+        for segment in segments.iter() {
+            match *segment {
+                "self" => (),
+                "super" => {
+                    resolved.pop()?;
+                }
+                other => resolved.push(other),
+            }
+        }
+        return modules.get(resolved.join("::").as_str()).copied();
+    }
+
+    if let Some(index) = modules.get(segments.join("::").as_str()) {
+        return Some(*index);
+    }
+
+    if segments[0] == result.crate_name || segments[0] == "crate" {
+        segments.remove(0);
+        // The modules of a dependency of the workspace are output under the name of its crate
+        let in_dependency = current_segments().next() == Some(result.crate_name.as_str());
+        if in_dependency {
+            segments.insert(0, &result.crate_name);
+        }
+        return modules.get(segments.join("::").as_str()).copied();
+    }
+
+    // A child module of the current one
+    let child: Vec<&str> = current_segments().chain(segments).collect();
+    modules.get(child.join("::").as_str()).copied()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use syn::{punctuated::Punctuated, Path};
+    use ts_json_subset::{
+        declarations::type_alias::TypeAliasDeclaration,
+        export::ExportStatement,
+        ident::StrictTSIdent,
+        types::{PredefinedType, PrimaryType, TsType},
+    };
+
+    fn module(path: &str, exports: &[(&str, &[(&str, &str)])]) -> ModuleStepResultData {
+        let path = if path.is_empty() {
+            Path {
+                leading_colon: None,
+                segments: Punctuated::default(),
+            }
+        } else {
+            syn::parse_str(path).unwrap()
+        };
+        let (exports, dependencies) = exports
+            .iter()
+            .map(|(ident, dependencies)| {
+                let export = ExportStatement::TypeAliasDeclaration(TypeAliasDeclaration {
+                    ident: StrictTSIdent::from_str(ident).unwrap(),
+                    type_params: None,
+                    inner_type: TsType::PrimaryType(PrimaryType::Predefined(PredefinedType::Null)),
+                });
+                let dependencies = dependencies
+                    .iter()
+                    .map(|(path, ident)| ImportEntry {
+                        path: path.to_string(),
+                        ident: ident.to_string(),
+                        alias: None,
+                    })
+                    .collect();
+                (export, dependencies)
+            })
+            .unzip();
+        ModuleStepResultData {
+            exports,
+            imports: Vec::new(),
+            path,
+            dependencies,
+            crate_name: "my_crate".to_string(),
+            declaration_attributes: Default::default(),
+        }
+    }
+
+    fn exported(results: &[ModuleStepResultData]) -> Vec<String> {
+        results
+            .iter()
+            .flat_map(|result| {
+                result.exports.iter().flat_map(move |export| {
+                    export
+                        .declared_idents()
+                        .into_iter()
+                        .map(move |ident| format!("{}::{}", DisplayPath(&result.path), ident))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_keep_reachable_declarations() {
+        let mut results = vec![
+            module(
+                "models",
+                &[
+                    (
+                        "User",
+                        &[("", "Address"), ("my_crate::models::role", "Role")],
+                    ),
+                    ("Address", &[("chrono", "DateTime")]),
+                    ("Internal", &[]),
+                ],
+            ),
+            module(
+                "models::role",
+                &[("Role", &[("super", "Address")]), ("Unused", &[])],
+            ),
+            module("", &[("Root", &[])]),
+        ];
+        let mut path_mapper = PathMapper::default();
+        path_mapper.add_mapping("my_crate::models::role", "types/role");
+
+        retain_reachable(&mut results, &["models::User".to_string()], &path_mapper).unwrap();
+        assert_eq!(
+            exported(&results),
+            vec!["models::User", "models::Address", "models::role::Role"]
+        );
+        assert_eq!(
+            results[0]
+                .imports
+                .iter()
+                .map(|import| import.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                r#"import { Role } from "types/role";"#,
+                r#"import { DateTime } from "chrono";"#
+            ]
+        );
+        assert!(results[2].exports.is_empty());
+    }
+
+    #[test]
+    fn should_fail_on_unknown_root() {
+        let mut results = vec![module("", &[("Root", &[])])];
+        let result = retain_reachable(
+            &mut results,
+            &["Root".to_string(), "models::Root".to_string()],
+            &PathMapper::default(),
+        );
+        assert!(matches!(result, Err(TsExportError::RootNotFound(root)) if root == "models::Root"));
+    }
+}
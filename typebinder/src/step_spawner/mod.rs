@@ -7,6 +7,7 @@ use crate::pipeline::module_step::ModuleStep;
 
 pub mod discard;
 pub mod mod_reader;
+pub mod workspace;
 
 /// An abstraction that specifies how to create a Step of the pipeline.
 ///
@@ -22,6 +22,17 @@ impl RustModuleReader {
             return Err(TsExportError::DirectoryGiven(path));
         }
         let crate_name = crate::utils::cargo::fetch_crate_name_for_source_file(&path)?;
+        Self::try_new_with_crate_name(path, crate_name)
+    }
+
+    /// Same as `try_new`, but does not look up the crate name in the `Cargo.toml`
+    pub fn try_new_with_crate_name(
+        path: PathBuf,
+        crate_name: String,
+    ) -> Result<Self, TsExportError> {
+        if path.is_dir() {
+            return Err(TsExportError::DirectoryGiven(path));
+        }
         let root_module_name = path
             .file_stem()
             .expect("Path should be a file")
@@ -39,13 +50,13 @@ impl RustModuleReader {
             crate_name,
         })
     }
-}
 
-impl PipelineStepSpawner for RustModuleReader {
-    type Error = TsExportError;
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
 
-    fn create_process(&self, path: Path) -> Result<Option<ModuleStep>, TsExportError> {
-        log::info!("Creating process for Rust module : {}", DisplayPath(&path));
+    /// Finds the file that contains the module at `path`, relative to the root module
+    pub(crate) fn module_file_path(&self, path: &Path) -> PathBuf {
         let file_path: PathBuf = if path.segments.is_empty() {
             self.root_module_name.clone().into()
         } else {
@@ -59,28 +70,31 @@ impl PipelineStepSpawner for RustModuleReader {
 
         // Case 1: <path>/file_path/mod.rs a.k.a <full_path>/mod.rs
         if full_path.is_dir() {
-            let mut full_path_file = full_path.clone();
-            full_path_file.push("mod");
-            full_path_file.set_extension("rs");
-            create_process_from_path(full_path_file, path, &self.crate_name)
-        } else {
-            // Case 2: <path>/file_path.rs a.k.a. <full_path>.rs
-            let mut full_path_file = full_path.clone();
-            full_path_file.set_extension("rs");
-            create_process_from_path(full_path_file, path, &self.crate_name)
+            full_path.push("mod");
         }
+        // Case 2: <path>/file_path.rs a.k.a. <full_path>.rs
+        full_path.set_extension("rs");
+        full_path
+    }
+}
+
+impl PipelineStepSpawner for RustModuleReader {
+    type Error = TsExportError;
+
+    fn create_process(&self, path: Path) -> Result<Option<ModuleStep>, TsExportError> {
+        log::info!("Creating process for Rust module : {}", DisplayPath(&path));
+        create_process_from_path(self.module_file_path(&path), path, &self.crate_name)
     }
 }
 
-fn create_process_from_path<P: AsRef<std::path::Path> + std::fmt::Debug>(
+pub(crate) fn create_process_from_path<P: AsRef<std::path::Path> + std::fmt::Debug>(
     full_path: P,
     path: Path,
     crate_name: &str,
 ) -> Result<Option<ModuleStep>, TsExportError> {
     log::info!("Reading module from path {:?}", full_path);
     let contents = std::fs::read_to_string(&full_path)?;
-    let ast = syn::parse_file(&contents)?;
-
-    let process_module = ModuleStep::new(path, ast.items, crate_name);
+    let process_module = ModuleStep::from_source(path, contents, crate_name)
+        .with_source_file(full_path.as_ref().to_path_buf());
     Ok(Some(process_module))
 }
@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use proc_macro2::Span;
+use syn::{punctuated::Punctuated, Ident, Path};
+
+use crate::{
+    error::TsExportError,
+    path_mapper::PathMapper,
+    pipeline::module_step::ModuleStep,
+    utils::{
+        cargo::{fetch_path_dependencies, find_cargo_toml_for_source_file},
+        display_path::DisplayPath,
+    },
+};
+
+use super::{
+    mod_reader::{create_process_from_path, RustModuleReader},
+    PipelineStepSpawner,
+};
+
+/// A strategy that reads the Rust modules of a crate, and of all the crates it depends upon through `path`
+/// dependencies (typically, the sibling crates of a Cargo workspace).
+///
+/// The root module of each dependency is spawned as a child module of the root module, named after the crate.
+/// A root module that declares a child module of the same name as a dependency is an error.
+/// This means that the bindings of `shared_models::money` are output next to the bindings of the root crate,
+/// e.g. in `shared_models/money.ts`.
+///
+/// Use [WorkspaceReader::add_path_mappings] to make the imports between the crates point to the generated modules.
+pub struct WorkspaceReader {
+    root: RustModuleReader,
+    dependencies: IndexMap<String, RustModuleReader>,
+}
+
+impl WorkspaceReader {
+    /// Path is the path to the root module of the root crate
+    pub fn try_new(path: PathBuf) -> Result<Self, TsExportError> {
+        let cargo_toml_path = find_cargo_toml_for_source_file(&path)?;
+        let root = RustModuleReader::try_new(path)?;
+
+        let mut dependencies: IndexMap<String, RustModuleReader> = IndexMap::new();
+        let mut manifests_to_visit = vec![cargo_toml_path];
+        while let Some(cargo_toml_path) = manifests_to_visit.pop() {
+            for dependency in fetch_path_dependencies(&cargo_toml_path)? {
+                if dependency.crate_name == root.crate_name()
+                    || dependencies.contains_key(&dependency.crate_name)
+                {
+                    continue;
+                }
+                log::info!(
+                    "Following path dependency {} at {:?}",
+                    dependency.crate_name,
+                    dependency.lib_path
+                );
+                let reader = RustModuleReader::try_new_with_crate_name(
+                    dependency.lib_path,
+                    dependency.crate_name.clone(),
+                )?;
+                dependencies.insert(dependency.crate_name, reader);
+                manifests_to_visit.push(dependency.cargo_toml_path);
+            }
+        }
+
+        Ok(WorkspaceReader { root, dependencies })
+    }
+
+    /// Names of the crates that are read in addition to the root crate
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.dependencies.keys().map(String::as_str)
+    }
+
+    /// Maps the root crate to `prefix`, and each dependency to `prefix/<crate_name>`.
+    ///
+    /// Crates that are already mapped in the given PathMapper are left untouched.
+    pub fn add_path_mappings(&self, path_mapper: &mut PathMapper, prefix: &str) {
+        let root_crate_name = self.root.crate_name();
+        if path_mapper.get(root_crate_name).is_none() {
+            path_mapper.add_mapping(root_crate_name, prefix);
+        }
+        self.dependencies().for_each(|crate_name| {
+            if path_mapper.get(crate_name).is_none() {
+                path_mapper.add_mapping(crate_name, format!("{}/{}", prefix, crate_name));
+            }
+        });
+    }
+}
+
+impl PipelineStepSpawner for WorkspaceReader {
+    type Error = TsExportError;
+
+    fn create_process(&self, path: Path) -> Result<Option<ModuleStep>, TsExportError> {
+        let first_segment = match path.segments.first() {
+            Some(segment) => segment.ident.to_string(),
+            None => {
+                let mut root_step = self.root.create_process(path)?;
+                if let Some(step) = root_step.as_mut() {
+                    for crate_name in self.dependencies() {
+                        step.add_module_declaration(Ident::new(crate_name, Span::call_site()))?;
+                    }
+                }
+                return Ok(root_step);
+            }
+        };
+
+        match self.dependencies.get(&first_segment) {
+            Some(reader) => {
+                log::info!(
+                    "Creating process for Rust module : {} in crate {}",
+                    DisplayPath(&path),
+                    first_segment
+                );
+                let path_in_crate = Path {
+                    leading_colon: None,
+                    segments: path
+                        .segments
+                        .iter()
+                        .skip(1)
+                        .cloned()
+                        .collect::<Punctuated<_, _>>(),
+                };
+                let file_path = reader.module_file_path(&path_in_crate);
+                create_process_from_path(file_path, path, reader.crate_name())
+            }
+            None => self.root.create_process(path),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn should_read_path_dependencies() {
+        let root_module = PathBuf::from(format!("../{}", file!()))
+            .parent()
+            .unwrap()
+            .join("../lib.rs");
+        let reader = WorkspaceReader::try_new(root_module).expect("Failed to read workspace");
+        assert_eq!(
+            reader.dependencies().collect::<Vec<_>>(),
+            vec!["ts_json_subset"]
+        );
+
+        let path: Path = syn::parse_str("ts_json_subset::types").unwrap();
+        let step = reader
+            .create_process(path)
+            .expect("Failed to create process");
+        assert!(step.is_some());
+
+        let mut path_mapper = PathMapper::default();
+        reader.add_path_mappings(&mut path_mapper, "types");
+        assert_eq!(
+            path_mapper.get("typebinder::pipeline"),
+            Some("types/pipeline".to_string())
+        );
+        assert_eq!(
+            path_mapper.get("ts_json_subset::types"),
+            Some("types/ts_json_subset/types".to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_a_dependency_named_like_a_root_module() {
+        let file: syn::File = syn::parse_str("mod shared_models;").unwrap();
+        let mut step = ModuleStep::new(
+            Path {
+                leading_colon: None,
+                segments: Punctuated::new(),
+            },
+            file.items,
+            "app",
+        );
+        let error = step
+            .add_module_declaration(Ident::new("shared_models", Span::call_site()))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The crate `shared_models` can't be exported as a child module of the root module, which declares a module of the same name"
+        );
+        assert!(step
+            .add_module_declaration(Ident::new("billing", Span::call_site()))
+            .is_ok());
+    }
+}
@@ -16,7 +16,7 @@ pub struct FnSolver<F>(F);
 
 impl<F> TypeSolver for FnSolver<F>
 where
-    F: Fn(&ExporterContext, &TypeInfo) -> SolverResult<TsType, TsExportError>,
+    F: Fn(&ExporterContext, &TypeInfo) -> SolverResult<TsType, TsExportError> + Send + Sync,
 {
     fn solve_as_type(
         &self,
@@ -33,7 +33,7 @@ pub trait AsFnSolver: Sized {
 
 impl<F> AsFnSolver for F
 where
-    F: Fn(&ExporterContext, &TypeInfo) -> SolverResult<TsType, TsExportError>,
+    F: Fn(&ExporterContext, &TypeInfo) -> SolverResult<TsType, TsExportError> + Send + Sync,
 {
     fn fn_solver(self) -> FnSolver<Self> {
         FnSolver(self)
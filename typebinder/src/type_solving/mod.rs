@@ -2,6 +2,7 @@
 
 use self::{member_info::MemberInfo, result::SolverResult, type_info::TypeInfo};
 use crate::{contexts::exporter::ExporterContext, error::TsExportError};
+use serde::{Deserialize, Serialize};
 use std::{rc::Rc, sync::Arc};
 use ts_json_subset::types::{PropertyName, PropertySignature, TsType, TypeMember};
 
@@ -12,10 +13,20 @@ pub mod result;
 pub mod solvers;
 pub mod type_info;
 
-#[derive(Debug, Hash, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Hash, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ImportEntry {
     pub path: String,
     pub ident: String,
+    /// The name the declaration is imported as, when its own name is already taken in the importing module
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+impl ImportEntry {
+    /// The name under which the declaration is known in the importing module
+    pub fn local_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.ident)
+    }
 }
 
 /// The TypeSolver is the main abstraction of Typebinder. It is what allows its modularity.
@@ -41,7 +52,9 @@ pub struct ImportEntry {
 /// * SolverResult::Solved means that the solver has succeeded in its task
 /// * SolverResult::Error means that the solver had an unrecoverable error
 ///
-pub trait TypeSolver {
+/// Solvers are shared by the threads that solve the modules in parallel, hence the `Send + Sync` bound.
+///
+pub trait TypeSolver: Send + Sync {
     fn solve_as_type(
         &self,
         solving_context: &ExporterContext,
@@ -69,6 +82,9 @@ pub trait TypeSolver {
 }
 
 pub trait TypeSolverExt: TypeSolver + Sized {
+    #[deprecated(
+        note = "an `Rc` can't be shared by the threads that solve the modules, use `into_arc` instead"
+    )]
     fn into_rc(self) -> Rc<Self> {
         Rc::new(self)
     }
@@ -84,27 +100,6 @@ pub trait TypeSolverExt: TypeSolver + Sized {
 
 impl<T: TypeSolver> TypeSolverExt for T {}
 
-impl<T> TypeSolver for Rc<T>
-where
-    T: TypeSolver,
-{
-    fn solve_as_type(
-        &self,
-        solving_context: &ExporterContext,
-        solver_info: &TypeInfo,
-    ) -> SolverResult<TsType, TsExportError> {
-        <T as TypeSolver>::solve_as_type(self.as_ref(), solving_context, solver_info)
-    }
-
-    fn solve_as_member(
-        &self,
-        solving_context: &ExporterContext,
-        solver_info: &MemberInfo,
-    ) -> SolverResult<TypeMember, TsExportError> {
-        <T as TypeSolver>::solve_as_member(self.as_ref(), solving_context, solver_info)
-    }
-}
-
 impl<T> TypeSolver for Arc<T>
 where
     T: TypeSolver,
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use syn::{GenericArgument, Generics, PathArguments, Type, TypePath};
+use syn::{spanned::Spanned, GenericArgument, Generics, PathArguments, Type, TypePath};
 use ts_json_subset::{
     ident::StrictTSIdent,
     types::{
@@ -11,13 +11,14 @@ use ts_json_subset::{
 
 use crate::{
     contexts::exporter::ExporterContext,
+    diagnostics::WarningKind,
     error::TsExportError,
     type_solving::ImportEntry,
     type_solving::{
         generic_constraints::GenericConstraints, member_info::MemberInfo, result::Solved,
     },
     type_solving::{SolverResult, TypeInfo, TypeSolver},
-    utils::display_path::DisplayPath,
+    utils::{display_path::DisplayPath, rename::placeholder_name},
 };
 
 /// The last solver of the pipeline. It recurses after trying to solve the type using
@@ -39,7 +40,6 @@ impl TypeSolver for ImportSolver {
                         let ty_import_dp = DisplayPath(&ty_import.path).to_string();
                         let ty_path_dp = DisplayPath(&ty_path.path).to_string();
                         if ty_import_dp == ty_path_dp {
-                            log::warn!("This type exists in the import");
                             // This type exists in the import and no further information about the path can be obtained,
                             // so it is a special case that we must handle
                             match solve_type_path(solving_context, generics, ty_path.clone()) {
@@ -58,10 +58,16 @@ impl TypeSolver for ImportSolver {
                             Err(e) => SolverResult::Error(e),
                         }
                     }
-                    None => match solve_type_path(solving_context, generics, ty_path.clone()) {
-                        Ok(solved) => SolverResult::Solved(solved),
-                        Err(e) => SolverResult::Error(e),
-                    },
+                    None => {
+                        if let Err(e) = check_skipped(solving_context, ty_path) {
+                            return SolverResult::Error(e);
+                        }
+                        warn_unknown_type(solving_context, ty_path);
+                        match solve_type_path(solving_context, generics, ty_path.clone()) {
+                            Ok(solved) => SolverResult::Solved(solved),
+                            Err(e) => SolverResult::Error(e),
+                        }
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -118,16 +124,22 @@ impl TypeSolver for ImportSolver {
                             Err(e) => SolverResult::Error(e),
                         }
                     }
-                    None => match solve_type_path(solving_context, generics, ty_path.clone()) {
-                        Ok(solved) => SolverResult::Solved(solved.map(|ts_type| {
-                            TypeMember::PropertySignature(PropertySignature {
-                                inner_type: ts_type,
-                                name: PropertyName::from(name.to_string()),
-                                optional: false,
-                            })
-                        })),
-                        Err(e) => SolverResult::Error(e),
-                    },
+                    None => {
+                        if let Err(e) = check_skipped(solving_context, ty_path) {
+                            return SolverResult::Error(e);
+                        }
+                        warn_unknown_type(solving_context, ty_path);
+                        match solve_type_path(solving_context, generics, ty_path.clone()) {
+                            Ok(solved) => SolverResult::Solved(solved.map(|ts_type| {
+                                TypeMember::PropertySignature(PropertySignature {
+                                    inner_type: ts_type,
+                                    name: PropertyName::from(name.to_string()),
+                                    optional: false,
+                                })
+                            })),
+                            Err(e) => SolverResult::Error(e),
+                        }
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -136,13 +148,38 @@ impl TypeSolver for ImportSolver {
     }
 }
 
+/// The declarations of the module that are skipped with `#[typebinder(skip)]` can't be referred to
+fn check_skipped(
+    solving_context: &ExporterContext,
+    ty_path: &TypePath,
+) -> Result<(), TsExportError> {
+    match ty_path.path.get_ident() {
+        Some(ident) if solving_context.import_context.is_skipped(ident) => {
+            Err(TsExportError::SkippedTypeReferenced {
+                name: ident.to_string(),
+                module: "its own module".to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A type with a single segment that is not found in the module can't be imported, e.g. when it comes from a glob import
+fn warn_unknown_type(solving_context: &ExporterContext, ty_path: &TypePath) {
+    if ty_path.path.segments.len() == 1 {
+        solving_context.warn(
+            WarningKind::UnknownType(DisplayPath(&ty_path.path).to_string()),
+            ty_path.span(),
+        );
+    }
+}
+
 pub fn solve_type_path(
     solving_context: &ExporterContext,
     generics: &Generics,
     ty_path: TypePath,
 ) -> Result<Solved<TsType>, TsExportError> {
     let segment = ty_path.path.segments.last().expect("Empty path");
-    let ident = StrictTSIdent::from_str(&segment.ident.to_string())?;
     let mut imports: Vec<ImportEntry> = Vec::new();
     let mut constraints = GenericConstraints::default();
 
@@ -155,9 +192,12 @@ pub fn solve_type_path(
         .map(|segm| segm.ident.to_string())
         .collect();
     let path = path_segments.join("::");
+    // The declarations pass gives its name in the module to a declaration of another module
+    let ident = StrictTSIdent::from_str(&placeholder_name(&path, &segment.ident.to_string()))?;
     let mut other_imports = vec![ImportEntry {
         ident: segment.ident.to_string(),
         path,
+        alias: None,
     }];
     imports.append(&mut other_imports);
 
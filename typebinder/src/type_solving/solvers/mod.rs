@@ -11,4 +11,5 @@ pub mod ranges;
 pub mod reference;
 pub mod serde_json_value;
 pub mod skip_serialize_if;
+pub mod smart_pointers;
 pub mod tuple;
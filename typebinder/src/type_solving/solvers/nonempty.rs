@@ -76,7 +76,7 @@ impl Default for NonEmptySolver {
             }
         })
         .fn_solver()
-        .into_rc();
+        .into_arc();
 
         let mut inner = PathSolver::default();
         inner.add_entry("nonempty::NonEmpty".to_string(), non_empty_solver);
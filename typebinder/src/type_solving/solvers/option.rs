@@ -45,7 +45,7 @@ impl Default for OptionSolver {
             }
         })
         .fn_solver()
-        .into_rc();
+        .into_arc();
 
         let mut inner = PathSolver::default();
         inner.add_entry("std::option::Option".to_string(), option_solver);
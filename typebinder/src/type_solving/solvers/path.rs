@@ -1,6 +1,6 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
-    rc::Rc,
+    sync::Arc,
 };
 
 use syn::Type;
@@ -18,11 +18,11 @@ use crate::{
 /// It is used when a solver needs to match a certain type's name
 #[derive(Default)]
 pub struct PathSolver {
-    pub entries: HashMap<String, Rc<dyn TypeSolver>>,
+    pub entries: HashMap<String, Arc<dyn TypeSolver>>,
 }
 
 impl PathSolver {
-    pub fn add_entry<S, I>(&mut self, ident: I, solver: Rc<S>)
+    pub fn add_entry<S, I>(&mut self, ident: I, solver: Arc<S>)
     where
         S: TypeSolver + 'static,
         I: Into<String>,
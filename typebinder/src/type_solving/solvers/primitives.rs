@@ -26,7 +26,7 @@ fn solve_number(
 
 impl Default for PrimitivesSolver {
     fn default() -> Self {
-        let solver_number = solve_number.fn_solver().into_rc();
+        let solver_number = solve_number.fn_solver().into_arc();
 
         let solver_string = (|_: &ExporterContext, _: &TypeInfo| {
             SolverResult::Solved(Solved::new(
@@ -34,7 +34,7 @@ impl Default for PrimitivesSolver {
             ))
         })
         .fn_solver()
-        .into_rc();
+        .into_arc();
 
         let solver_bool = (|_: &ExporterContext, _: &TypeInfo| {
             SolverResult::Solved(Solved::new(
@@ -42,7 +42,7 @@ impl Default for PrimitivesSolver {
             ))
         })
         .fn_solver()
-        .into_rc();
+        .into_arc();
 
         let mut inner = PathSolver {
             entries: HashMap::default(),
@@ -66,7 +66,7 @@ fn solve_range(
 impl Default for RangesSolver {
     fn default() -> Self {
         let mut inner = PathSolver::default();
-        let solver_range = solve_range.fn_solver().into_rc();
+        let solver_range = solve_range.fn_solver().into_arc();
 
         inner.add_entry("std::ops::Range".to_string(), solver_range.clone());
         inner.add_entry("std::ops::RangeInclusive".to_string(), solver_range);
@@ -0,0 +1,61 @@
+use crate::{
+    contexts::exporter::ExporterContext,
+    error::TsExportError,
+    type_solving::fn_solver::AsFnSolver,
+    type_solving::{SolverResult, TypeInfo, TypeSolver, TypeSolverExt},
+    utils::inner_generic::solve_segment_generics,
+};
+use syn::Type;
+use ts_json_subset::types::TsType;
+
+use super::path::PathSolver;
+
+/// Solver for Box<T>, Rc<T> and Arc<T>.
+///
+/// When serializing, serde will treat smart pointers as a no-op, so they are solved as their inner type.
+/// This is what allows recursive types such as `enum Expr { Neg(Box<Expr>) }` to be exported.
+pub struct SmartPointerSolver {
+    inner: PathSolver,
+}
+
+impl Default for SmartPointerSolver {
+    fn default() -> Self {
+        let pointer_solver = (|solving_context: &ExporterContext, solver_info: &TypeInfo| {
+            let TypeInfo { generics, ty } = solver_info;
+            match ty {
+                Type::Path(ty) => {
+                    let segment = ty.path.segments.last().expect("Empty path");
+                    match solve_segment_generics(solving_context, generics, segment) {
+                        Ok(solved) => {
+                            if !solved.inner.is_empty() {
+                                SolverResult::Solved(solved.map(|mut types| types.swap_remove(0)))
+                            } else {
+                                SolverResult::Error(TsExportError::EmptyGenerics)
+                            }
+                        }
+                        Err(e) => SolverResult::Error(e),
+                    }
+                }
+                _ => unreachable!(),
+            }
+        })
+        .fn_solver()
+        .into_arc();
+
+        let mut inner = PathSolver::default();
+        inner.add_entry("std::boxed::Box".to_string(), pointer_solver.clone());
+        inner.add_entry("std::rc::Rc".to_string(), pointer_solver.clone());
+        inner.add_entry("std::sync::Arc".to_string(), pointer_solver);
+        SmartPointerSolver { inner }
+    }
+}
+
+impl TypeSolver for SmartPointerSolver {
+    fn solve_as_type(
+        &self,
+        solving_context: &ExporterContext,
+        solver_info: &TypeInfo,
+    ) -> SolverResult<TsType, TsExportError> {
+        self.inner.solve_as_type(solving_context, solver_info)
+    }
+}
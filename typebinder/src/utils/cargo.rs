@@ -12,6 +12,21 @@ pub enum Error {
     CargoTomlReadFailed(#[from] cargo_toml::Error),
     #[error("package section not found in {0}")]
     NoPackageSection(PathBuf),
+    #[error("library target not found for the dependency {0}")]
+    NoLibTarget(String),
+    #[error("the dependency {0} is inherited from a workspace that does not define it")]
+    UnresolvedWorkspaceDependency(String),
+}
+
+/// A crate depended upon through a `path` dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathDependency {
+    /// The name under which the crate is used in Rust code, e.g. `shared_models`
+    pub crate_name: String,
+    /// Path to the root module of the crate, e.g. `../shared-models/src/lib.rs`
+    pub lib_path: PathBuf,
+    /// Path to the `Cargo.toml` of the crate
+    pub cargo_toml_path: PathBuf,
 }
 
 pub fn fetch_crate_name_for_source_file(source_file_path: &Path) -> Result<String, Error> {
@@ -23,7 +38,87 @@ pub fn fetch_crate_name_for_source_file(source_file_path: &Path) -> Result<Strin
     Ok(package.name)
 }
 
-fn find_cargo_toml_for_source_file(source_file_path: &Path) -> Result<PathBuf, Error> {
+/// Lists the direct `path` dependencies of a crate, including the ones inherited from the workspace
+pub fn fetch_path_dependencies(cargo_toml_path: &Path) -> Result<Vec<PathDependency>, Error> {
+    let manifest_dir = cargo_toml_path
+        .parent()
+        .ok_or_else(|| Error::CargoTomlNotFound(cargo_toml_path.to_path_buf()))?;
+    let content = std::fs::read(cargo_toml_path)
+        .map_err(|io_err| Error::CargoTomlReadFailed(io_err.into()))?;
+    let mut manifest = cargo_toml::Manifest::from_slice(&content)?;
+    // `{ workspace = true }` dependencies are defined in the `[workspace.dependencies]` of the workspace manifest,
+    // with a path relative to it
+    let workspace = match find_workspace_cargo_toml(manifest_dir)? {
+        Some(workspace_cargo_toml_path) => Some((
+            cargo_toml::Manifest::from_slice(
+                &std::fs::read(&workspace_cargo_toml_path)
+                    .map_err(|io_err| Error::CargoTomlReadFailed(io_err.into()))?,
+            )?,
+            workspace_cargo_toml_path,
+        )),
+        None => None,
+    };
+    manifest.complete_from_path_and_workspace(
+        cargo_toml_path,
+        workspace
+            .as_ref()
+            .map(|(workspace, path)| (workspace, path.as_path())),
+    )?;
+
+    let mut dependencies = Vec::new();
+    for (name, dependency) in manifest.dependencies.iter() {
+        let path = match dependency {
+            cargo_toml::Dependency::Inherited(_) => {
+                return Err(Error::UnresolvedWorkspaceDependency(name.clone()))
+            }
+            dependency => match dependency.detail().and_then(|detail| detail.path.as_ref()) {
+                Some(path) => path,
+                None => continue,
+            },
+        };
+        let dependency_dir = manifest_dir.join(path);
+        let cargo_toml_path = dependency_dir.join("Cargo.toml");
+        let dependency_manifest = cargo_toml::Manifest::from_path(&cargo_toml_path)?;
+        let lib_path = dependency_manifest
+            .lib
+            .and_then(|lib| lib.path)
+            .unwrap_or_else(|| "src/lib.rs".to_string());
+        let lib_path = dependency_dir.join(lib_path);
+        if !lib_path.is_file() {
+            return Err(Error::NoLibTarget(name.clone()));
+        }
+        dependencies.push(PathDependency {
+            crate_name: name.replace('-', "_"),
+            lib_path,
+            cargo_toml_path,
+        });
+    }
+    Ok(dependencies)
+}
+
+/// The `Cargo.toml` with a `[workspace]` section in the directory of a crate or in one of its parents, if any
+fn find_workspace_cargo_toml(manifest_dir: &Path) -> Result<Option<PathBuf>, Error> {
+    let manifest_dir = manifest_dir
+        .canonicalize()
+        .map_err(|io_err| Error::PathCanonicalizeError(manifest_dir.to_path_buf(), io_err))?;
+    for dir in manifest_dir.ancestors() {
+        let cargo_toml_path = dir.join("Cargo.toml");
+        if !cargo_toml_path.is_file() {
+            continue;
+        }
+        let content = std::fs::read(&cargo_toml_path)
+            .map_err(|io_err| Error::CargoTomlReadFailed(io_err.into()))?;
+        if cargo_toml::Manifest::from_slice(&content)?
+            .workspace
+            .is_some()
+        {
+            return Ok(Some(cargo_toml_path));
+        }
+    }
+    Ok(None)
+}
+
+pub fn find_cargo_toml_for_source_file(source_file_path: &Path) -> Result<PathBuf, Error> {
     let path = source_file_path
         .canonicalize()
         .map_err(|io_err| Error::PathCanonicalizeError(source_file_path.to_path_buf(), io_err))?;
@@ -43,6 +138,7 @@ fn find_cargo_toml_for_source_file(source_file_path: &Path) -> Result<PathBuf, E
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::temp_dir::TempDir;
 
     #[test]
     fn test_fetch_crate_name_for_source_file() {
@@ -51,4 +147,51 @@ mod tests {
         let crate_name = fetch_crate_name_for_source_file(&path).unwrap();
         assert_eq!(crate_name, "typebinder")
     }
+
+    #[test]
+    fn test_fetch_path_dependencies() {
+        let current_file = format!("../{}", file!());
+        let cargo_toml_path =
+            find_cargo_toml_for_source_file(&PathBuf::from(current_file)).unwrap();
+        let dependencies = fetch_path_dependencies(&cargo_toml_path).unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].crate_name, "ts_json_subset");
+        assert!(dependencies[0]
+            .lib_path
+            .ends_with("ts_json_subset/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_fetch_inherited_path_dependencies() {
+        let temp_dir = TempDir::new("test_fetch_inherited_path_dependencies");
+        let workspace = temp_dir.path();
+        let files = [
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"app\", \"libs/shared-models\"]\n\n[workspace.dependencies]\nshared-models = { path = \"libs/shared-models\" }\n",
+            ),
+            (
+                "app/Cargo.toml",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nshared-models = { workspace = true }\n",
+            ),
+            ("app/src/lib.rs", ""),
+            (
+                "libs/shared-models/Cargo.toml",
+                "[package]\nname = \"shared-models\"\nversion = \"0.1.0\"\n",
+            ),
+            ("libs/shared-models/src/lib.rs", ""),
+        ];
+        for (path, content) in files.iter() {
+            let path = workspace.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let dependencies = fetch_path_dependencies(&workspace.join("app/Cargo.toml")).unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].crate_name, "shared_models");
+        assert!(dependencies[0]
+            .lib_path
+            .ends_with("libs/shared-models/src/lib.rs"));
+    }
 }
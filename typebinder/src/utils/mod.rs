@@ -3,3 +3,6 @@
 pub mod cargo;
 pub mod display_path;
 pub mod inner_generic;
+pub(crate) mod rename;
+#[cfg(test)]
+pub(crate) mod temp_dir;
@@ -0,0 +1,192 @@
+//! Renaming the declarations and the type references of the TS statements
+use std::{
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use fnv::FnvHasher;
+
+use ts_json_subset::{
+    export::ExportStatement,
+    ident::StrictTSIdent,
+    types::{ArrayTypeInner, PrimaryType, TsType, TypeMember, TypeParameters},
+};
+
+use crate::error::TsExportError;
+
+/// Separates the Rust ident of a declaration of another module from the hash of its path in its placeholder name
+const PLACEHOLDER_SEPARATOR: &str = "$$";
+
+/// The name a type refers to a declaration of another module by, until the declarations pass gives it its name in
+/// the module, see [apply_declaration_attributes](crate::pipeline::declarations::apply_declaration_attributes).
+///
+/// Declarations of the same name in several modules are told apart by the hash of their path, e.g. `Status$$hfcm...`.
+/// The declarations of the current module, with an empty path, keep their name.
+pub(crate) fn placeholder_name(path: &str, ident: &str) -> String {
+    if path.is_empty() {
+        return ident.to_string();
+    }
+    let mut hasher = FnvHasher::default();
+    path.hash(&mut hasher);
+    // Letters only, as the digits are not all valid in a TS ident
+    let hash: String = (0..16)
+        .map(|nibble| (b'a' + ((hasher.finish() >> (nibble * 4)) & 0xf) as u8) as char)
+        .collect();
+    format!("{}{}{}", ident, PLACEHOLDER_SEPARATOR, hash)
+}
+
+/// Displays a type with the Rust idents of the declarations of the other modules instead of their placeholder names,
+/// for the diagnostics given before the declarations pass
+pub(crate) fn display_type(ts_type: &TsType) -> String {
+    let mut ts_type = ts_type.clone();
+    let without_placeholder = |name: &str| {
+        name.split_once(PLACEHOLDER_SEPARATOR)
+            .map(|(ident, _)| ident.to_string())
+    };
+    // An ident that is not a valid TS ident on its own, e.g. `Void`, keeps its placeholder name
+    rename_type(&mut ts_type, &without_placeholder, &[]).ok();
+    ts_type.to_string()
+}
+
+/// Renames the declarations and the references of a statement
+pub(crate) fn rename_statement<F>(
+    statement: &mut ExportStatement,
+    rename: &F,
+) -> Result<(), TsExportError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let rename_ident = |ident: &mut StrictTSIdent| -> Result<(), TsExportError> {
+        if let Some(renamed) = rename(&ident.to_string()) {
+            *ident = StrictTSIdent::from_str(&renamed)?;
+        }
+        Ok(())
+    };
+    match statement {
+        ExportStatement::InterfaceDeclaration(declaration) => {
+            rename_ident(&mut declaration.ident)?;
+            let params = type_param_names(&declaration.type_params);
+            rename_type_params(&mut declaration.type_params, rename, &params)?;
+            if let Some(extends_clause) = declaration.extends_clause.as_mut() {
+                for reference in extends_clause.type_list.identifiers.iter_mut() {
+                    let mut ts_type: TsType = PrimaryType::TypeReference(reference.clone()).into();
+                    rename_type(&mut ts_type, rename, &params)?;
+                    if let TsType::PrimaryType(PrimaryType::TypeReference(renamed)) = ts_type {
+                        *reference = renamed;
+                    }
+                }
+            }
+            for member in declaration.obj_type.body.members.iter_mut() {
+                let TypeMember::PropertySignature(property) = member;
+                rename_type(&mut property.inner_type, rename, &params)?;
+            }
+        }
+        ExportStatement::TypeAliasDeclaration(declaration) => {
+            rename_ident(&mut declaration.ident)?;
+            let params = type_param_names(&declaration.type_params);
+            rename_type_params(&mut declaration.type_params, rename, &params)?;
+            rename_type(&mut declaration.inner_type, rename, &params)?;
+        }
+        ExportStatement::ConstEnumDeclaration(declaration) => rename_ident(&mut declaration.ident)?,
+        ExportStatement::ReexportDeclaration(declaration) => {
+            for clause in declaration.reexports.iter_mut() {
+                rename_ident(&mut clause.scope)?;
+                rename_ident(&mut clause.export_as)?;
+            }
+        }
+        ExportStatement::ExportAllDeclaration(_) => (),
+    }
+    Ok(())
+}
+
+fn type_param_names(type_params: &Option<TypeParameters>) -> Vec<String> {
+    type_params
+        .iter()
+        .flat_map(|type_params| type_params.parameters.iter())
+        .map(|parameter| parameter.identifier.to_string())
+        .collect()
+}
+
+fn rename_type_params<F>(
+    type_params: &mut Option<TypeParameters>,
+    rename: &F,
+    params: &[String],
+) -> Result<(), TsExportError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let parameters = type_params
+        .iter_mut()
+        .flat_map(|type_params| type_params.parameters.iter_mut());
+    for parameter in parameters {
+        if let Some(constraint) = parameter.constraint.as_mut() {
+            for ts_type in constraint.types.iter_mut() {
+                rename_type(ts_type, rename, params)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renames the references of a type, except those to the type parameters
+fn rename_type<F>(ts_type: &mut TsType, rename: &F, params: &[String]) -> Result<(), TsExportError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match ts_type {
+        TsType::PrimaryType(primary) => rename_primary(primary, rename, params),
+        TsType::UnionType(union) => union
+            .types
+            .iter_mut()
+            .try_for_each(|ts_type| rename_type(ts_type, rename, params)),
+        TsType::IntersectionType(intersection) => intersection
+            .types
+            .iter_mut()
+            .try_for_each(|ts_type| rename_type(ts_type, rename, params)),
+        TsType::ParenthesizedType(parenthesized) => {
+            rename_type(&mut parenthesized.inner, rename, params)
+        }
+    }
+}
+
+fn rename_primary<F>(
+    primary: &mut PrimaryType,
+    rename: &F,
+    params: &[String],
+) -> Result<(), TsExportError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match primary {
+        PrimaryType::TypeReference(reference) => {
+            let name = reference.name.to_string();
+            if !params.contains(&name) {
+                if let Some(renamed) = rename(&name) {
+                    reference.name = StrictTSIdent::from_str(&renamed)?;
+                }
+            }
+            reference
+                .args
+                .iter_mut()
+                .flat_map(|args| args.types.iter_mut())
+                .try_for_each(|ts_type| rename_type(ts_type, rename, params))
+        }
+        PrimaryType::ObjectType(object) => object.body.members.iter_mut().try_for_each(|member| {
+            let TypeMember::PropertySignature(property) = member;
+            rename_type(&mut property.inner_type, rename, params)
+        }),
+        PrimaryType::ArrayType(array) => match array.inner_type.as_mut() {
+            ArrayTypeInner::Primary(primary) => rename_primary(primary, rename, params),
+            ArrayTypeInner::Parenthesized(parenthesized) => {
+                rename_type(&mut parenthesized.inner, rename, params)
+            }
+        },
+        PrimaryType::TupleType(tuple) => tuple
+            .inner_types
+            .iter_mut()
+            .try_for_each(|ts_type| rename_type(ts_type, rename, params)),
+        PrimaryType::Predefined(_) | PrimaryType::LiteralType(_) | PrimaryType::Verbatim(_) => {
+            Ok(())
+        }
+    }
+}
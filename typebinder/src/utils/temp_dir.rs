@@ -0,0 +1,25 @@
+//! A temporary directory for the tests, removed when dropped
+use std::path::{Path, PathBuf};
+
+/// A directory under the temporary directory of the system, removed when dropped, even if the test panicked
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// A directory named after the test, and the process so that concurrent runs don't share it
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("typebinder_{}_{}", name, std::process::id()));
+        // Left behind by a previous run that was killed
+        let _ = std::fs::remove_dir_all(&path);
+        TempDir(path)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
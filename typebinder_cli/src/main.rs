@@ -31,18 +31,30 @@
 //!    +- index.ts
 //! ```
 //!
-use std::path::PathBuf;
-use typebinder::exporters::check::CheckExport;
+use report::ReportFormat;
+use std::path::{Path, PathBuf};
+use typebinder::{
+    compat::compare_directories,
+    exporters::check::{CheckExport, CheckReport},
+};
+
+mod report;
+mod snippet;
 
 use structopt::StructOpt;
 use typebinder::{
     contexts::type_solving::TypeSolvingContextBuilder,
     error::TsExportError,
-    exporters::{file::FileExporter, stdout::StdoutExport},
+    exporters::{bundle::BundleExporter, file::FileExporter, stdout::StdoutExport},
+    lints::{Lint, LintConfig, LintLevel},
     macros::context::MacroSolvingContext,
     path_mapper::PathMapper,
-    pipeline::Pipeline,
-    step_spawner::mod_reader::RustModuleReader,
+    pipeline::{
+        cache::ModuleCache, config::PipelineConfig, imports::ImportStrategy, Pipeline,
+        PipelineOutput,
+    },
+    step_spawner::{mod_reader::RustModuleReader, workspace::WorkspaceReader, PipelineStepSpawner},
+    ts::printer::{Indent, PrintConfig, QuoteStyle},
 };
 
 #[derive(Debug, StructOpt)]
@@ -53,11 +65,65 @@ use typebinder::{
 /// CLI arguments
 struct Options {
     #[structopt(parse(from_os_str))]
-    /// Rust module to generate the bindings for
-    input: PathBuf,
+    /// Rust module to generate the bindings for, required unless comparing bindings
+    input: Option<PathBuf>,
     #[structopt(short, parse(from_os_str))]
-    /// Path to the PathMapper definition
+    /// Path to the PathMapper definition, a JSON file or a TOML file with the `.toml` extension
     path_mapper_file: Option<PathBuf>,
+    #[structopt(long)]
+    /// Also generates the bindings of the crates that the input crate depends upon through path dependencies
+    /// (e.g. the other crates of the workspace). The value is the TS import path of the output folder.
+    workspace: Option<String>,
+    #[structopt(long = "root", number_of_values = 1)]
+    /// Only exports the declarations reachable from this type, given by its Rust path relative to the
+    /// root of the crate (e.g. `models::user::User`). Can be repeated.
+    roots: Vec<String>,
+    #[structopt(long)]
+    /// Imports the generated modules by their path relative to the importing file (e.g. `../models/user`),
+    /// instead of their mapped Rust path
+    relative_imports: bool,
+    #[structopt(long, requires = "relative-imports")]
+    /// Adds the `.js` extension to the relative imports, for the ESM resolution of Node
+    js_extension: bool,
+    #[structopt(long)]
+    /// Imports the declarations with `import type`, as required by `isolatedModules` and `verbatimModuleSyntax`
+    type_only_imports: bool,
+    #[structopt(long)]
+    /// Reports all the errors at the end instead of stopping at the first one. The declarations
+    /// that can be exported still are, and the stale files are not pruned.
+    collect_errors: bool,
+    #[structopt(long)]
+    /// Fails if any warning is emitted, e.g. in CI
+    deny_warnings: bool,
+    #[structopt(long = "allow", number_of_values = 1)]
+    /// Disables a lint, e.g. `large-integer`. Can be repeated.
+    allowed_lints: Vec<Lint>,
+    #[structopt(long = "deny", number_of_values = 1)]
+    /// Makes a lint an error, e.g. `large-integer`. Can be repeated.
+    denied_lints: Vec<Lint>,
+    #[structopt(long, parse(from_os_str))]
+    /// Directory where the exported modules are cached, so that the modules that did not change are not
+    /// exported again on the next run
+    cache: Option<PathBuf>,
+    #[structopt(long)]
+    /// Formats the bindings like prettier does with its default options, instead of the compact default style.
+    /// Implied by the other formatting options.
+    pretty: bool,
+    #[structopt(long, parse(try_from_str = parse_indent))]
+    /// Indentation of the pretty-printed bindings, `tab` or a number of spaces (default: 2)
+    indent: Option<Indent>,
+    #[structopt(long)]
+    /// Pretty-prints the bindings without semicolons, members being separated with line breaks only
+    no_semicolons: bool,
+    #[structopt(long)]
+    /// Pretty-prints the bindings without trailing commas
+    no_trailing_commas: bool,
+    #[structopt(long)]
+    /// Pretty-prints the bindings with single quotes
+    single_quotes: bool,
+    #[structopt(long)]
+    /// Width of the lines that the pretty-printed unions are broken at (default: 80)
+    print_width: Option<usize>,
     #[structopt(subcommand)]
     command: TypebinderCommand,
 }
@@ -69,6 +135,19 @@ enum TypebinderCommand {
         #[structopt(short, parse(from_os_str))]
         /// Output path, will use stdout if no path is specified
         output: Option<PathBuf>,
+        #[structopt(long)]
+        /// Deletes the previously generated files that are no longer generated, e.g. when a Rust module was removed
+        prune: bool,
+        #[structopt(long, parse(from_os_str), conflicts_with = "output")]
+        /// Outputs all the bindings to this single file instead, dropping the imports between the modules
+        bundle: Option<PathBuf>,
+        #[structopt(long, requires = "bundle")]
+        /// Prefixes the declarations of the same name with the path of their module, instead of failing
+        rename_duplicates: bool,
+        #[structopt(long, requires = "output")]
+        /// Also outputs an `index.ts` per directory that reexports its modules, so that the bindings can be
+        /// imported from a directory
+        barrels: bool,
     },
     /// Runs typebinder in "check" mode : no files will be produced.
     ///
@@ -80,66 +159,345 @@ enum TypebinderCommand {
         #[structopt(parse(from_os_str))]
         /// Output path where the bindings that we are checking against are stored
         output: PathBuf,
+        #[structopt(long, default_value = "human", possible_values = &["human", "json"])]
+        /// `human` prints a unified diff of the files that are not up to date, `json` prints the
+        /// status and the diff hunks of every file to stdout
+        format: ReportFormat,
+        #[structopt(long)]
+        /// Compares the declarations instead of the text of the files, so that formatting the bindings
+        /// (e.g. with prettier) does not fail the check
+        semantic: bool,
+        #[structopt(long)]
+        /// Also checks the `index.ts` of each directory, for bindings generated with `--barrels`
+        barrels: bool,
+    },
+    /// Compares two versions of the generated bindings, e.g. before and after a change of the backend.
+    ///
+    /// Each change is reported as compatible or breaking for the consumers, that read the values
+    /// (e.g. responses), and for the producers, that write them (e.g. requests). Fails if any
+    /// change is breaking.
+    Compat {
+        #[structopt(parse(from_os_str))]
+        /// Folder of the old bindings
+        old: PathBuf,
+        #[structopt(parse(from_os_str))]
+        /// Folder of the new bindings
+        new: PathBuf,
+        #[structopt(long, default_value = "human", possible_values = &["human", "json"])]
+        /// `human` prints a line per change, `json` prints the changes to stdout
+        format: ReportFormat,
     },
 }
 
-fn main() -> Result<(), TsExportError> {
+fn main() {
     pretty_env_logger::init();
     let options = Options::from_args();
-    main_process(options)
+    let deny_warnings = options.deny_warnings;
+    let format = match &options.command {
+        TypebinderCommand::Check { format, .. } => *format,
+        TypebinderCommand::Generate { .. } => ReportFormat::Human,
+        TypebinderCommand::Compat { old, new, format } => {
+            std::process::exit(compat(old, new, *format))
+        }
+    };
+    if options.input.is_none() {
+        structopt::clap::Error::with_description(
+            "The Rust module to generate the bindings for is required",
+            structopt::clap::ErrorKind::MissingRequiredArgument,
+        )
+        .exit();
+    }
+    match main_process(options) {
+        Ok(output) => {
+            let mut failed = false;
+            for warning in output.warnings.iter() {
+                eprintln!("{}\n", snippet::render_warning(warning));
+            }
+            if let Some(report) = output.exported.as_ref() {
+                match format {
+                    ReportFormat::Human if !report.is_up_to_date() => {
+                        eprintln!("{}\n", report::render_human(report, report::use_colors()))
+                    }
+                    ReportFormat::Human => (),
+                    ReportFormat::Json => println!("{}", report::render_json(report)),
+                }
+                if !report.is_up_to_date() {
+                    eprintln!(
+                        "error: {} file(s) of the bindings are not up to date",
+                        report.outdated_files().count()
+                    );
+                    failed = true;
+                }
+            }
+            if !output.errors.is_empty() {
+                eprintln!(
+                    "{}",
+                    snippet::render_error(&TsExportError::Multiple(output.errors))
+                );
+                failed = true;
+            }
+            if deny_warnings && !output.warnings.is_empty() {
+                eprintln!(
+                    "error: {} warning(s) emitted while warnings are denied",
+                    output.warnings.len()
+                );
+                failed = true;
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Err(error) => {
+            eprintln!("{}", snippet::render_error(&error));
+            std::process::exit(1);
+        }
+    }
 }
 
-fn main_process(options: Options) -> Result<(), TsExportError> {
+fn parse_indent(input: &str) -> Result<Indent, String> {
+    match input {
+        "tab" => Ok(Indent::Tabs),
+        spaces => spaces
+            .parse()
+            .map(Indent::Spaces)
+            .map_err(|_| format!("Expected `tab` or a number of spaces, got `{}`", input)),
+    }
+}
+
+/// Compares two versions of the bindings, returns the exit code
+fn compat(old: &Path, new: &Path, format: ReportFormat) -> i32 {
+    let report = match compare_directories(old, new) {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("{}", snippet::render_error(&error));
+            return 1;
+        }
+    };
+    match format {
+        ReportFormat::Human => {
+            if !report.changes.is_empty() {
+                eprintln!("{}\n", report::render_compat(&report, report::use_colors()));
+            }
+        }
+        ReportFormat::Json => println!("{}", report::render_json(&report)),
+    }
+    if report.is_breaking() {
+        eprintln!(
+            "error: {} breaking change(s) between the bindings",
+            report
+                .changes
+                .iter()
+                .filter(|change| change.is_breaking())
+                .count()
+        );
+        1
+    } else {
+        0
+    }
+}
+
+fn main_process(options: Options) -> Result<PipelineOutput<Option<CheckReport>>, TsExportError> {
     let Options {
         input,
         path_mapper_file,
+        workspace,
+        roots,
+        relative_imports,
+        js_extension,
+        type_only_imports,
+        collect_errors,
+        deny_warnings: _,
+        allowed_lints,
+        denied_lints,
+        cache,
+        pretty,
+        indent,
+        no_semicolons,
+        no_trailing_commas,
+        single_quotes,
+        print_width,
         command,
     } = options;
+    let input = input.expect("The input is checked when parsing the arguments");
+
+    let mut lints = LintConfig::default();
+    allowed_lints
+        .into_iter()
+        .for_each(|lint| lints.set_level(lint, LintLevel::Allow));
+    denied_lints
+        .into_iter()
+        .for_each(|lint| lints.set_level(lint, LintLevel::Deny));
+    let config = PipelineConfig {
+        roots: if roots.is_empty() { None } else { Some(roots) },
+        collect_errors,
+        lints,
+        cache: cache.map(ModuleCache::new),
+        import_strategy: if relative_imports {
+            ImportStrategy::Relative { js_extension }
+        } else {
+            ImportStrategy::PathMapper
+        },
+        type_only_imports,
+    };
+
+    let print_config = if pretty
+        || indent.is_some()
+        || no_semicolons
+        || no_trailing_commas
+        || single_quotes
+        || print_width.is_some()
+    {
+        let default = PrintConfig::default();
+        Some(PrintConfig {
+            indent: indent.unwrap_or(default.indent),
+            semicolons: !no_semicolons,
+            trailing_commas: !no_trailing_commas,
+            quote_style: if single_quotes {
+                QuoteStyle::Single
+            } else {
+                QuoteStyle::Double
+            },
+            print_width: print_width.unwrap_or(default.print_width),
+        })
+    } else {
+        None
+    };
 
-    let pipeline_step_spawner = RustModuleReader::try_new(input)?;
+    let mut path_mapper = if let Some(path) = path_mapper_file {
+        PathMapper::load_from(path)?
+    } else {
+        PathMapper::default()
+    };
+
+    match workspace {
+        Some(prefix) => {
+            let pipeline_step_spawner = WorkspaceReader::try_new(input)?;
+            pipeline_step_spawner.add_path_mappings(&mut path_mapper, &prefix);
+            launch(
+                pipeline_step_spawner,
+                path_mapper,
+                config,
+                print_config,
+                command,
+            )
+        }
+        None => {
+            let pipeline_step_spawner = RustModuleReader::try_new(input)?;
+            launch(
+                pipeline_step_spawner,
+                path_mapper,
+                config,
+                print_config,
+                command,
+            )
+        }
+    }
+}
 
+fn launch<PSS>(
+    pipeline_step_spawner: PSS,
+    path_mapper: PathMapper,
+    config: PipelineConfig,
+    print_config: Option<PrintConfig>,
+    command: TypebinderCommand,
+) -> Result<PipelineOutput<Option<CheckReport>>, TsExportError>
+where
+    PSS: PipelineStepSpawner + Sync,
+    TsExportError: From<PSS::Error>,
+{
     let solving_context = TypeSolvingContextBuilder::default()
         .add_default_solvers()
         .finish();
 
     let macro_context = MacroSolvingContext::default();
+    let js_extension = config.import_strategy == ImportStrategy::Relative { js_extension: true };
 
-    let path_mapper = if let Some(path) = path_mapper_file {
-        PathMapper::load_from(path)?
-    } else {
-        PathMapper::default()
-    };
-    match command {
-        TypebinderCommand::Check { output } => {
+    let output = match command {
+        TypebinderCommand::Check {
+            output,
+            semantic,
+            barrels,
+            ..
+        } => {
             log::info!("Launching Typebinder in check mode");
+            let mut exporter = CheckExport::new(output);
+            exporter.set_semantic(semantic);
+            exporter.set_barrels(barrels);
+            exporter.set_js_extension(js_extension);
+            if let Some(print_config) = print_config {
+                exporter.set_print_config(print_config);
+            }
             Pipeline {
                 pipeline_step_spawner,
-                exporter: CheckExport::new(output),
+                exporter,
                 path_mapper,
+                config,
             }
-            .launch(&solving_context, &macro_context)?;
+            .launch(&solving_context, &macro_context)?
+            .map(Some)
         }
-        TypebinderCommand::Generate { output } => match output {
+        TypebinderCommand::Generate {
+            bundle: Some(bundle_path),
+            rename_duplicates,
+            ..
+        } => {
+            log::info!("Launching Typebinder in BundleExporter mode");
+            let mut exporter = BundleExporter::new(bundle_path);
+            exporter.set_rename_duplicates(rename_duplicates);
+            if let Some(print_config) = print_config {
+                exporter.set_print_config(print_config);
+            }
+            Pipeline {
+                pipeline_step_spawner,
+                exporter,
+                path_mapper,
+                config,
+            }
+            .launch(&solving_context, &macro_context)?
+            .map(|_| None)
+        }
+        TypebinderCommand::Generate {
+            output,
+            prune,
+            barrels,
+            ..
+        } => match output {
             Some(out_path) => {
                 log::info!("Launching Typebinder in FileExporter mode");
+                let mut exporter = FileExporter::new(out_path);
+                exporter.set_prune(prune);
+                exporter.set_barrels(barrels);
+                exporter.set_js_extension(js_extension);
+                if let Some(print_config) = print_config {
+                    exporter.set_print_config(print_config);
+                }
                 Pipeline {
                     pipeline_step_spawner,
-                    exporter: FileExporter::new(out_path),
+                    exporter,
                     path_mapper,
+                    config,
                 }
-                .launch(&solving_context, &macro_context)?;
+                .launch(&solving_context, &macro_context)?
+                .map(|_| None)
             }
             None => {
                 log::info!("Launching Typebinder in StdoutExport mode");
+                let mut exporter = StdoutExport::default();
+                if let Some(print_config) = print_config {
+                    exporter.set_print_config(print_config);
+                }
                 Pipeline {
                     pipeline_step_spawner,
-                    exporter: StdoutExport,
+                    exporter,
                     path_mapper,
+                    config,
                 }
-                .launch(&solving_context, &macro_context)?;
+                .launch(&solving_context, &macro_context)?
+                .map(|_| None)
             }
         },
-    }
+        TypebinderCommand::Compat { .. } => unreachable!("The bindings are compared in `main`"),
+    };
 
-    Ok(())
+    Ok(output)
 }
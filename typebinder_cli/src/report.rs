@@ -0,0 +1,112 @@
+//! Renders the reports of the check and compat modes, for humans or as JSON
+use std::{io::IsTerminal, str::FromStr};
+
+use serde::Serialize;
+use typebinder::{compat::CompatReport, exporters::check::CheckReport};
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// How the report of the check mode is output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A unified diff of each file that is not up to date, on stderr
+    Human,
+    /// The whole report, on stdout
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(ReportFormat::Human),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!(
+                "Unknown format `{}`, expected `human` or `json`",
+                s
+            )),
+        }
+    }
+}
+
+/// Colours are used when stderr is a terminal, unless `NO_COLOR` is set
+pub fn use_colors() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Renders the files that are not up to date as unified diffs
+pub fn render_human(report: &CheckReport, colors: bool) -> String {
+    report
+        .outdated_files()
+        .map(|file| {
+            let diff = file.to_string();
+            if colors {
+                colorize(&diff)
+            } else {
+                diff
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a line per change, the breaking ones in red
+pub fn render_compat(report: &CompatReport, colors: bool) -> String {
+    report
+        .changes
+        .iter()
+        .map(|change| {
+            if colors && change.is_breaking() {
+                format!("{}{}{}", RED, change, RESET)
+            } else {
+                change.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn render_json<R: Serialize>(report: &R) -> String {
+    serde_json::to_string_pretty(report).expect("Failed to serialize the report")
+}
+
+/// Colours a unified diff of a single file, whose first two lines are the file headers
+fn colorize(diff: &str) -> String {
+    diff.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let color = if index < 2 {
+                BOLD
+            } else if line.starts_with("@@") {
+                CYAN
+            } else if line.starts_with('+') {
+                GREEN
+            } else if line.starts_with('-') {
+                RED
+            } else {
+                return line.to_string();
+            };
+            format!("{}{}{}", color, line, RESET)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::colorize;
+
+    #[test]
+    fn should_colorize_the_diff_lines() {
+        let diff = "--- a.ts\n+++ a.ts\n@@ -1,2 +1,2 @@\n-a\n+b\n c";
+        assert_eq!(
+            colorize(diff),
+            "\x1b[1m--- a.ts\x1b[0m\n\x1b[1m+++ a.ts\x1b[0m\n\x1b[36m@@ -1,2 +1,2 @@\x1b[0m\n\x1b[31m-a\x1b[0m\n\x1b[32m+b\x1b[0m\n c"
+        );
+    }
+}
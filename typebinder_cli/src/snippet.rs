@@ -0,0 +1,125 @@
+//! Renders errors and warnings the way rustc does, with the offending Rust code
+use typebinder::{
+    diagnostics::Warning,
+    error::{SourceLocation, TsExportError},
+};
+
+/// Renders the error, with a snippet of the Rust source code when the error is located
+pub fn render_error(error: &TsExportError) -> String {
+    if let TsExportError::Multiple(errors) = error {
+        return errors
+            .iter()
+            .map(render_error)
+            .chain(Some(format!(
+                "error: could not export the bindings due to {} previous error{}",
+                errors.len(),
+                if errors.len() > 1 { "s" } else { "" }
+            )))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    }
+    render(
+        &format!("error: {}", error.without_location()),
+        error.location(),
+    )
+}
+
+/// Renders the warning, with a snippet of the Rust source code when the warning is located
+pub fn render_warning(warning: &Warning) -> String {
+    render(
+        &format!("warning: {}", warning.kind),
+        warning.location.as_ref(),
+    )
+}
+
+fn render(message: &str, location: Option<&SourceLocation>) -> String {
+    let location = match location {
+        Some(location) => location,
+        None => return message.to_string(),
+    };
+    let source = location
+        .file
+        .as_ref()
+        .and_then(|file| std::fs::read_to_string(file).ok());
+    match source {
+        Some(source) => format!("{}\n{}", message, render_snippet(location, &source)),
+        None => format!("{}\n  --> {}", message, location),
+    }
+}
+
+/// Renders the first line of the location, underlining the offending code
+pub fn render_snippet(location: &SourceLocation, source: &str) -> String {
+    let line_number = location.start.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let line = source
+        .lines()
+        .nth(location.start.line.saturating_sub(1))
+        .unwrap_or_default();
+    let underline_end = if location.end.line == location.start.line {
+        location.end.column
+    } else {
+        line.chars().count()
+    };
+    let underline_len = underline_end.saturating_sub(location.start.column).max(1);
+    // The tabs of the line are kept so that the underline is aligned whatever their width
+    let padding: String = line
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(location.start.column)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!(
+        "{gutter}--> {location}\n{gutter} |\n{line_number} | {line}\n{gutter} | {padding}{underline}",
+        gutter = gutter,
+        location = location,
+        line_number = line_number,
+        line = line,
+        padding = padding,
+        underline = "^".repeat(underline_len),
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use typebinder::proc_macro2::LineColumn;
+
+    #[test]
+    fn should_render_snippet() {
+        let source = "struct A {\n    field: fn(),\n}\n";
+        let location = SourceLocation {
+            file: Some(PathBuf::from("src/models.rs")),
+            start: LineColumn {
+                line: 2,
+                column: 11,
+            },
+            end: LineColumn {
+                line: 2,
+                column: 15,
+            },
+        };
+        assert_eq!(
+            render_snippet(&location, source),
+            " --> src/models.rs:2:12\n  |\n2 |     field: fn(),\n  |            ^^^^"
+        );
+    }
+
+    #[test]
+    fn should_align_the_underline_on_tab_indented_lines() {
+        let source = "struct A {\n\t\tfield: fn(),\n}\n";
+        let location = SourceLocation {
+            file: Some(PathBuf::from("src/models.rs")),
+            start: LineColumn { line: 2, column: 9 },
+            end: LineColumn {
+                line: 2,
+                column: 13,
+            },
+        };
+        assert_eq!(
+            render_snippet(&location, source),
+            " --> src/models.rs:2:10\n  |\n2 | \t\tfield: fn(),\n  | \t\t       ^^^^"
+        );
+    }
+}
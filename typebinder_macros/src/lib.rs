@@ -0,0 +1,20 @@
+//! Registers the `#[typebinder(...)]` attribute, so that it compiles in your code.
+//!
+//! The derive macro generates nothing : the attribute is only read by `typebinder` when exporting.
+//!
+//! ```ignore
+//! #[derive(Serialize, Typebinder)]
+//! #[typebinder(rename = "Person")]
+//! pub struct User {
+//!     #[typebinder(type = "`${number}px`")]
+//!     pub width: u32,
+//! }
+//! ```
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(Typebinder, attributes(typebinder))]
+pub fn derive_typebinder(_input: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
@@ -0,0 +1,4 @@
+// A global type, written by hand for the `#[typebinder(export = false)]` declaration of the demo
+interface Handwritten {
+	note: string;
+}
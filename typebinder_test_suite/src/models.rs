@@ -127,3 +127,40 @@ struct User {
     #[serde(flatten)]
     address: Address,
 }
+
+#[derive(Serialize, Deserialize, Typebinder)]
+#[typebinder(rename = "Client")]
+struct Customer {
+    name: String,
+    #[typebinder(type = "`${number}px`")]
+    width: u32,
+    #[typebinder(optional)]
+    nickname: String,
+    #[typebinder(skip)]
+    internal_id: u64,
+}
+
+// Declared in handwritten.d.ts
+#[derive(Serialize, Deserialize, Typebinder)]
+#[typebinder(export = false)]
+struct Handwritten {}
+
+#[derive(Serialize, Deserialize, Typebinder)]
+#[typebinder(type = "string")]
+struct Token(Vec<u8>);
+
+#[derive(Serialize, Deserialize, Typebinder)]
+struct Order {
+    customer: Customer,
+    token: Token,
+    handwritten: Handwritten,
+    status: OrderStatus,
+}
+
+#[derive(Serialize, Deserialize, Typebinder)]
+enum OrderStatus {
+    Pending,
+    Shipped,
+    #[typebinder(skip)]
+    Internal,
+}
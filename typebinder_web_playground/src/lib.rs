@@ -6,7 +6,7 @@ use typebinder::{
     exporters::Exporter,
     macros::context::MacroSolvingContext,
     path_mapper::PathMapper,
-    pipeline::{module_step::ModuleStep, Pipeline},
+    pipeline::{config::PipelineConfig, module_step::ModuleStep, Pipeline},
     step_spawner::PipelineStepSpawner,
     syn,
 };
@@ -23,6 +23,7 @@ fn typebinder_pass(input: &str) -> Result<String, TsExportError> {
         pipeline_step_spawner,
         exporter: StringOutputter::new(&mut output),
         path_mapper,
+        config: PipelineConfig::default(),
     }
     .launch(&solving_context, &macro_context)?;
     Ok(output)
@@ -61,6 +62,7 @@ impl PipelineStepSpawner for StringInputReader<'_> {
 
 impl Exporter for StringOutputter<'_> {
     type Error = TsExportError;
+    type Output = ();
 
     fn export_module(
         &mut self,
@@ -81,6 +83,10 @@ impl Exporter for StringOutputter<'_> {
 
         Ok(())
     }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
 
 // Send Msg::OnTick every 200ms